@@ -37,6 +37,15 @@ pub struct ModelsConf {
     pub wpl: String,
     #[serde(default = "default_oml_root")]
     pub oml: String,
+    /// grok 模式目录（每个文件每行 `NAME regex`）
+    #[serde(default = "default_grok_root")]
+    pub grok: String,
+    /// user-agent 正则库（uap-core regexes.yaml 格式）
+    #[serde(default = "default_uap_file")]
+    pub uap: String,
+    /// protobuf 描述符集目录（protoc --descriptor_set_out 产物）
+    #[serde(default = "default_proto_root")]
+    pub proto: String,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
@@ -53,16 +62,28 @@ pub struct PerformanceConf {
     pub rate_limit_rps: usize,
     #[serde(default = "default_parse_workers")]
     pub parse_workers: usize,
+    /// 解压管道（decode/gzip 等）单条输出上限（字节），不配置时使用内置默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decompress_max_bytes: Option<usize>,
 }
 impl Default for PerformanceConf {
     fn default() -> Self {
         Self {
             rate_limit_rps: 10000,
             parse_workers: 2,
+            decompress_max_bytes: None,
         }
     }
 }
 
+/// OML 隐私处理的项目级配置
+#[derive(Debug, PartialEq, Deserialize, Serialize, Clone, Default)]
+pub struct PrivacyConf {
+    /// hash/token 使用的 salt；含这类规则的模型在 salt 为空时拒绝加载
+    #[serde(default)]
+    pub salt: String,
+}
+
 #[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
 pub struct EngineConfig {
     #[serde(default = "default_version")]
@@ -75,6 +96,8 @@ pub struct EngineConfig {
     topology: TopologyConf,
     #[serde(default)]
     performance: PerformanceConf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    privacy: Option<PrivacyConf>,
     #[serde(default)]
     rescue: RescueConf,
     #[serde(default)]
@@ -107,6 +130,18 @@ pub fn default_oml_root() -> String {
     "./models/oml".to_string()
 }
 
+pub fn default_grok_root() -> String {
+    "./models/grok".to_string()
+}
+
+pub fn default_uap_file() -> String {
+    "./models/uap/regexes.yaml".to_string()
+}
+
+pub fn default_proto_root() -> String {
+    "./models/proto".to_string()
+}
+
 pub fn default_sinks_root() -> String {
     "./models/sinks".to_string()
 }
@@ -144,6 +179,9 @@ pub fn default_models_conf() -> ModelsConf {
     ModelsConf {
         wpl: default_wpl_root(),
         oml: default_oml_root(),
+        grok: default_grok_root(),
+        uap: default_uap_file(),
+        proto: default_proto_root(),
     }
 }
 
@@ -155,6 +193,7 @@ impl Default for EngineConfig {
             models: default_models_conf(),
             topology: default_topology_conf(),
             performance: PerformanceConf::default(),
+            privacy: None,
             log_conf: LogConf::default(),
             stat_conf: StatConf::default(),
             robust: RobustnessMode::Normal,
@@ -175,16 +214,17 @@ impl EngineConfig {
             models: ModelsConf {
                 wpl: format!("{}/models/wpl", root.as_ref().display()),
                 oml: format!("{}/models/oml", root.as_ref().display()),
+                grok: format!("{}/models/grok", root.as_ref().display()),
+                uap: format!("{}/models/uap/regexes.yaml", root.as_ref().display()),
+                proto: format!("{}/models/proto", root.as_ref().display()),
                 // Use pluralized roots for sources/sinks; legacy single forms are no longer default
             },
             topology: TopologyConf {
                 sources: format!("{}/topology/sources", root.as_ref().display()),
                 sinks: format!("{}/topology/sinks", root.as_ref().display()),
             },
-            performance: PerformanceConf {
-                rate_limit_rps: 10000,
-                parse_workers: 2,
-            },
+            performance: PerformanceConf::default(),
+            privacy: None,
             log_conf: LogConf::default(),
             stat_conf: StatConf::default(),
             robust: RobustnessMode::Normal,
@@ -210,6 +250,18 @@ impl EngineConfig {
         self.models.oml.as_str()
     }
 
+    pub fn grok_root(&self) -> &str {
+        self.models.grok.as_str()
+    }
+
+    pub fn uap_file(&self) -> &str {
+        self.models.uap.as_str()
+    }
+
+    pub fn proto_root(&self) -> &str {
+        self.models.proto.as_str()
+    }
+
     pub fn sinks_root(&self) -> &str {
         self.topology.sinks.as_str()
     }
@@ -226,6 +278,14 @@ impl EngineConfig {
         self.performance.rate_limit_rps
     }
 
+    pub fn decompress_max_bytes(&self) -> Option<usize> {
+        self.performance.decompress_max_bytes
+    }
+
+    pub fn privacy_salt(&self) -> &str {
+        self.privacy.as_ref().map(|p| p.salt.as_str()).unwrap_or("")
+    }
+
     pub fn stat_conf(&self) -> &StatConf {
         &self.stat_conf
     }
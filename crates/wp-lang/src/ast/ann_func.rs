@@ -3,6 +3,7 @@ use crate::{WparseError, WparseReason};
 use orion_error::{ToStructError, UvsDataFrom};
 use smol_str::SmolStr;
 use std::collections::BTreeMap;
+use wildmatch::WildMatch;
 use wp_connector_api::SourceEvent;
 use wp_model_core::model::{DataField, DataRecord};
 use wp_parse_api::RawData;
@@ -115,6 +116,41 @@ impl AnnotationType {
     }
 }
 
+/// `source(...)` 中匹配事件来源 key 的键名，其余键名匹配事件标签
+pub const SOURCE_KEY_SEL: &str = "src_key";
+
+/// 规则来源选择：仅处理来源 key / 标签满足全部通配模式的事件
+#[derive(Clone, Debug)]
+pub struct SourceGuard {
+    items: Vec<(SmolStr, WildMatch)>,
+}
+
+impl SourceGuard {
+    pub fn convert(ann: &Option<AnnFun>) -> Option<Self> {
+        let ann = ann.as_ref()?;
+        if ann.source.is_empty() {
+            return None;
+        }
+        let items = ann
+            .source
+            .iter()
+            .map(|(k, v)| (k.clone(), WildMatch::new(v.as_str())))
+            .collect();
+        Some(Self { items })
+    }
+
+    pub fn accept(&self, src: &SourceEvent) -> bool {
+        self.items.iter().all(|(key, pattern)| {
+            let val = if key == SOURCE_KEY_SEL {
+                Some(src.src_key.as_str())
+            } else {
+                src.tags.get(key.as_str())
+            };
+            val.is_some_and(|v| pattern.matches(v))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,6 +166,7 @@ mod tests {
         let ann = AnnFun {
             tags: BTreeMap::from([("tag_1".into(), "x".into())]),
             copy_raw: None,
+            source: Default::default(),
         };
         let tag = AnnotationType::convert(&Some(ann));
         let mut data = DataRecord::test_value();
@@ -151,6 +188,7 @@ mod tests {
         let ann = AnnFun {
             tags: Default::default(),
             copy_raw: Some(("name".into(), "raw".into())),
+            source: Default::default(),
         };
         let tag = AnnotationType::convert(&Some(ann));
         let mut data = DataRecord::test_value();
@@ -166,4 +204,28 @@ mod tests {
             Some(&DataField::from_chars("raw", "test"))
         );
     }
+
+    #[test]
+    fn test_source_guard() {
+        let ann = AnnFun {
+            source: BTreeMap::from([
+                ("src_key".into(), "fw_*".into()),
+                ("dev".into(), "asa?".into()),
+            ]),
+            ..Default::default()
+        };
+        let guard = SourceGuard::convert(&Some(ann)).assert();
+        let event = |key: &str, dev: Option<&str>| {
+            let mut tags = Tags::new();
+            if let Some(dev) = dev {
+                tags.set("dev", dev);
+            }
+            SourceEvent::new(1, key, RawData::from_string("x"), tags.into())
+        };
+        assert!(guard.accept(&event("fw_syslog", Some("asa5"))));
+        assert!(!guard.accept(&event("proxy_file", Some("asa5"))));
+        assert!(!guard.accept(&event("fw_syslog", Some("pan"))));
+        assert!(!guard.accept(&event("fw_syslog", None)));
+        assert!(SourceGuard::convert(&Some(AnnFun::default())).is_none());
+    }
 }
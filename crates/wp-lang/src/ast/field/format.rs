@@ -3,8 +3,9 @@ use std::{fmt::Display, io::Write};
 use crate::{
     WplSep,
     ast::{
-        DEFAULT_FIELD_KEY, WplField, WplFieldFmt,
+        DEFAULT_FIELD_KEY, WplField, WplFieldFmt, WplFmt,
         debug::{DebugFormat, DepIndent},
+        fld_fmt::write_pipes,
     },
 };
 
@@ -15,50 +16,21 @@ impl DebugFormat for WplFieldSet {
     where
         W: ?Sized + Write + DepIndent,
     {
-        let item_count = self.conf_items().len();
-
-        for (index, x) in self.conf_items().exact_iter().enumerate() {
-            if item_count == 1 && index == 0 {
-                self.write_open_parenthesis(w)?;
-                let set = x.clone();
-                FieldConfSetWrap(set.0, set.1).write(w)?;
-                self.write_close_parenthesis(w)?;
-                return Ok(());
-            }
-
-            if index == 0 {
-                self.write_open_parenthesis(w)?;
-            }
-            let set = x.clone();
-            FieldConfSetWrap(set.0, set.1).write(w)?;
-            write!(w, ",")?;
-
-            if index == self.conf_items().len() - 1 {
-                self.write_close_parenthesis(w)?;
+        let items = self.conf_items();
+        let exact = items
+            .exact_iter()
+            .map(|x| FieldConfSetWrap(x.0.clone(), x.1.clone()));
+        let wild = items
+            .wild_iter()
+            .map(|x| FieldConfSetWrap(x.0.clone(), x.2.clone()));
+        self.write_open_parenthesis(w)?;
+        for (index, sub) in exact.chain(wild).enumerate() {
+            if index > 0 {
+                write!(w, ", ")?;
             }
+            sub.write(w)?;
         }
-        for (index, x) in self.conf_items().wild_iter().enumerate() {
-            if item_count == 1 && index == 0 {
-                self.write_open_parenthesis(w)?;
-                let set = x.clone();
-                FieldConfSetWrap(set.0, set.2).write(w)?;
-                self.write_close_parenthesis(w)?;
-                return Ok(());
-            }
-
-            if index == 0 {
-                self.write_open_parenthesis(w)?;
-            }
-            let set = x.clone();
-            FieldConfSetWrap(set.0, set.2).write(w)?;
-            write!(w, ",")?;
-
-            if index == self.conf_items().len() - 1 {
-                self.write_close_parenthesis(w)?;
-            }
-        }
-
-        Ok(())
+        self.write_close_parenthesis(w)
     }
 }
 
@@ -72,25 +44,8 @@ impl DebugFormat for (&WplFieldFmt, &Option<WplSep>) {
             if beg == end && beg == "\"" {
                 write!(w, "\"")?;
             } else {
-                write!(w, "<")?;
-                for c in beg.chars() {
-                    if c == ',' || c == '\\' {
-                        write!(w, "\\{}", c)?;
-                    } else {
-                        write!(w, "{}", c)?;
-                    }
-                }
-
-                write!(w, ",")?;
-
-                for c in end.chars() {
-                    if c == ',' || c == '\\' {
-                        write!(w, "\\{}", c)?;
-                    } else {
-                        write!(w, "{}", c)?;
-                    }
-                }
-                write!(w, ">")?;
+                // 解析端按首个 ',' 切分且不做转义，原样输出
+                write!(w, "<{},{}>", beg, end)?;
             }
         }
 
@@ -118,8 +73,14 @@ impl DebugFormat for FieldConfSetWrap {
     {
         let key = &self.0;
         let conf = &self.1;
-        if conf.meta_name != "chars" {
-            write!(w, "{}", conf.meta_name)?;
+        let meta = conf.wpl_type_name();
+        if conf.is_opt {
+            write!(w, "opt({})", meta)?;
+        } else if meta != "chars" || key == DEFAULT_FIELD_KEY && conf.name.is_none() {
+            write!(w, "{}", meta)?;
+        }
+        if let Some(content) = &conf.content {
+            write!(w, "({})", content)?;
         }
 
         if key != DEFAULT_FIELD_KEY {
@@ -131,8 +92,10 @@ impl DebugFormat for FieldConfSetWrap {
         }
 
         write!(w, "{}", conf.fmt_conf)?;
-
-        Ok(())
+        if let Some(sep) = &conf.separator {
+            write!(w, "{}", WplFmt(sep))?;
+        }
+        write_pipes(conf, w)
     }
 }
 
@@ -74,6 +74,16 @@ impl WplField {
         }
     }
 
+    /// 源码中的类型写法：`meta_name` 为规范化名称，部分类型需还原为可解析的别名
+    pub fn wpl_type_name(&self) -> Cow<'_, str> {
+        match &self.meta_type {
+            DataType::PeekSymbol => Cow::Borrowed("peek_symbol"),
+            DataType::TimeCLF => Cow::Borrowed("time/clf"),
+            DataType::Array(_) => Cow::Owned(String::from(&self.meta_type)),
+            _ => Cow::Borrowed(self.meta_name.as_str()),
+        }
+    }
+
     pub fn resolve_sep_ref<'a>(&'a self, ups: &'a WplSep) -> Cow<'a, WplSep> {
         if self.separator.is_some() {
             Cow::Owned(self.resolve_sep(ups))
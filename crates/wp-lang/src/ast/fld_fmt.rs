@@ -1,5 +1,5 @@
 use crate::ast::debug::{DebugFormat, DepIndent};
-use crate::ast::{DEFAULT_META_NAME, WplField};
+use crate::ast::{DEFAULT_META_NAME, WplField, WplFmt, WplPipe};
 use derive_getters::Getters;
 use std::fmt::{Display, Formatter};
 use std::io::Write;
@@ -13,19 +13,18 @@ impl DebugFormat for (&WplField, &Option<usize>, &Option<WplSep>) {
         W: ?Sized + Write + DepIndent,
     {
         let field_conf = self.0;
+        let base_group_len = self.1;
         let base_group_sep = self.2;
         if let Some(cnt) = field_conf.continuous_cnt {
             write!(w, "{}", cnt)?;
         }
-
         if field_conf.continuous {
             write!(w, "*")?;
-
             if field_conf.meta_name != DEFAULT_META_NAME {
-                write!(w, "{}", field_conf.meta_name)?;
+                write!(w, "{}", field_conf.wpl_type_name())?;
             }
         } else {
-            write!(w, "{}", field_conf.meta_name)?;
+            write!(w, "{}", field_conf.wpl_type_name())?;
         }
 
         if let Some(content) = &field_conf.content {
@@ -39,13 +38,41 @@ impl DebugFormat for (&WplField, &Option<usize>, &Option<WplSep>) {
         if let Some(name) = &field_conf.name {
             write!(w, ":{}", name)?;
         }
-        if let Some(len) = field_conf.length {
+        // 组长度会覆盖到组内每个字段，由组统一输出
+        if let (Some(len), None) = (field_conf.length, base_group_len) {
             write!(w, "[{}]", len)?;
         }
         (&field_conf.fmt_conf, base_group_sep).write(w)?;
+        if let Some(sep) = &field_conf.separator {
+            write!(w, "{}", WplFmt(sep))?;
+        }
+        write_pipes(field_conf, w)
+    }
+}
 
-        Ok(())
+pub(crate) fn write_pipes<W>(field_conf: &WplField, w: &mut W) -> std::io::Result<()>
+where
+    W: ?Sized + Write + DepIndent,
+{
+    for pipe in &field_conf.pipe {
+        write!(w, " | ")?;
+        match pipe {
+            WplPipe::Fun(fun) => write!(w, "{}", fun)?,
+            WplPipe::Group(group) => group.write(w)?,
+        }
+    }
+    for enrich in &field_conf.enriches {
+        write!(w, " | enrich({}, {}", enrich.dict, enrich.key)?;
+        for (col, alias) in &enrich.adds {
+            if col == alias {
+                write!(w, ", {}", col)?;
+            } else {
+                write!(w, ", {}:{}", col, alias)?;
+            }
+        }
+        write!(w, ")")?;
     }
+    Ok(())
 }
 
 #[derive(Debug, Clone, PartialEq, Default, Getters, Serialize, Deserialize)]
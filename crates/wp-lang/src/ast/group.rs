@@ -1,4 +1,5 @@
 use orion_overload::new::New1;
+use smol_str::SmolStr;
 use std::fmt::{Display, Formatter};
 use std::io::Write;
 use std::str::FromStr;
@@ -68,6 +69,8 @@ pub struct WplGroup {
     pub fields: Vec<WplField>,
     pub base_group_sep: Option<WplSep>,
     pub base_group_len: Option<usize>,
+    /// 字段组引用 `@<name>`，包解析时展开为所引用的组
+    pub refer: Option<SmolStr>,
 }
 
 impl New1<Vec<WplField>> for WplGroup {
@@ -80,6 +83,12 @@ impl New1<Vec<WplField>> for WplGroup {
 }
 
 impl WplGroup {
+    pub fn refer<S: Into<SmolStr>>(name: S) -> Self {
+        Self {
+            refer: Some(name.into()),
+            ..Default::default()
+        }
+    }
     pub fn first(&self) -> Option<&WplField> {
         self.fields.first()
     }
@@ -108,6 +117,9 @@ impl DebugFormat for WplGroup {
     where
         W: ?Sized + Write + DepIndent,
     {
+        if let Some(name) = &self.refer {
+            return write!(w, "@{}", name);
+        }
         if self.meta != WplGroupType::Seq(GroupSeq) {
             write!(w, "{}", self.meta)?;
         }
@@ -15,7 +15,7 @@ pub struct GenFmt<T>(pub T);
 
 pub use code::WplCode;
 pub use field::types::WplField;
-pub use field::types::{DEFAULT_FIELD_KEY, DEFAULT_META_NAME, WplFieldSet};
+pub use field::types::{DEFAULT_FIELD_KEY, DEFAULT_META_NAME, EnrichConf, WplFieldSet};
 pub use fld_fmt::WplFieldFmt;
 pub use package::WplPackage;
 pub use package::WplPkgMeta;
@@ -23,6 +23,6 @@ pub use processor::WplFun;
 pub use processor::WplPipe;
 pub use rule::meta::WplRuleMeta;
 pub use rule::meta::WplTag;
-pub use rule::types::{WplExpress, WplRule, WplStatementType};
+pub use rule::types::{WplExpectField, WplExpress, WplRule, WplStatementType, WplTestCase, WplUse};
 pub use syntax::tag::{AnnEnum, AnnFun, TagKvs};
 pub use syntax::wpl_sep::WplSep;
@@ -7,7 +7,7 @@ use std::io::Write;
 use wp_parser::Parser;
 
 use crate::ast::debug::{DebugFormat, DepIndent};
-use crate::ast::{WplRule, WplRuleMeta, WplTag};
+use crate::ast::{WplRule, WplRuleMeta, WplStatementType, WplTag, WplTestCase};
 use crate::parser::MergeTags;
 use crate::parser::wpl_pkg::wpl_package;
 
@@ -18,17 +18,31 @@ pub struct WplPackage {
     pub name: SmolStr,
     pub rules: VecDeque<WplRule>,
     pub tags: Option<AnnFun>,
+    /// 字段组声明（`fields`）、引入（`use`）与样例测试（`test`），不参与解析调度
+    pub decls: Vec<WplRule>,
 }
 
 impl WplPackage {
     pub(crate) fn append(&mut self, p0: Vec<WplRule>) {
         for i in p0 {
-            self.rules.push_back(i);
+            if i.statement.express().is_some() {
+                self.rules.push_back(i);
+            } else {
+                self.decls.push(i);
+            }
         }
     }
 }
 
 impl WplPackage {
+    /// 包内样例测试：(被测规则名, 样例)
+    pub fn tests(&self) -> impl Iterator<Item = (&SmolStr, &WplTestCase)> {
+        self.decls.iter().filter_map(|decl| match &decl.statement {
+            WplStatementType::Test(case) => Some((&decl.name, case)),
+            _ => None,
+        })
+    }
+
     pub fn export_tags(&self) -> Option<Vec<WplTag>> {
         self.tags.as_ref().map(|x| x.export_tags())
     }
@@ -71,7 +85,12 @@ impl DebugFormat for WplPackage {
         self.write_open_brace(w)?;
         self.write_new_line(w)?;
 
-        for rule in &self.rules {
+        // 样例测试置于规则之后
+        let (tests, decls): (Vec<_>, Vec<_>) = self
+            .decls
+            .iter()
+            .partition(|x| matches!(x.statement, WplStatementType::Test(_)));
+        for rule in decls.into_iter().chain(self.rules.iter()).chain(tests) {
             rule.write(w)?;
             self.write_new_line(w)?;
         }
@@ -99,11 +118,12 @@ impl WplPackage {
         let name = name.into();
         debug_assert!(!name.is_empty());
 
-        Self {
+        let mut package = Self {
             name,
-            rules: VecDeque::from(rules),
-            tags: None,
-        }
+            ..Default::default()
+        };
+        package.append(rules);
+        package
     }
 
     pub fn parse(data: &mut &str, path: &str) -> Result<Self, WplCodeError> {
@@ -1,10 +1,35 @@
+use std::fmt::{Display, Formatter};
 use std::net::IpAddr;
 
+use ipnet::IpNet;
+use regex::Regex;
 use smol_str::SmolStr;
 
+use crate::ast::rule::types::quote_str;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct CharsValue(pub(crate) SmolStr);
 
+/// Parser element for `cidr_in([...])`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CidrValue(pub(crate) IpNet);
+
+/// Regex compiled at load time; compared by pattern text
+#[derive(Clone, Debug)]
+pub struct PipeRegex(pub(crate) Regex);
+
+impl PartialEq for PipeRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Display for PipeRegex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&quote_str(self.0.as_str()))
+    }
+}
+
 // ============ Field Existence Check ============
 
 /// Checks if active field exists
@@ -70,6 +95,60 @@ pub struct TargetCharsIn {
     pub(crate) value: Vec<SmolStr>,
 }
 
+/// Checks if active field's character value matches a regex
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegexMatch {
+    pub(crate) value: PipeRegex,
+}
+
+/// Checks if specified field's character value matches a regex
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetRegexMatch {
+    pub(crate) target: Option<SmolStr>,
+    pub(crate) value: PipeRegex,
+}
+
+/// Checks if active field's character value starts with a prefix
+#[derive(Clone, Debug, PartialEq)]
+pub struct StartsWith {
+    pub(crate) value: SmolStr,
+}
+
+/// Checks if specified field's character value starts with a prefix
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetStartsWith {
+    pub(crate) target: Option<SmolStr>,
+    pub(crate) value: SmolStr,
+}
+
+/// Checks if active field's character value ends with a suffix
+#[derive(Clone, Debug, PartialEq)]
+pub struct EndsWith {
+    pub(crate) value: SmolStr,
+}
+
+/// Checks if specified field's character value ends with a suffix
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetEndsWith {
+    pub(crate) target: Option<SmolStr>,
+    pub(crate) value: SmolStr,
+}
+
+/// Checks if active field's character length (in chars) is within `[min, max]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LenBetween {
+    pub(crate) min: usize,
+    pub(crate) max: usize,
+}
+
+/// Checks if specified field's character length (in chars) is within `[min, max]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetLenBetween {
+    pub(crate) target: Option<SmolStr>,
+    pub(crate) min: usize,
+    pub(crate) max: usize,
+}
+
 // ============ Numeric Operations ============
 
 /// Checks if active field's numeric value equals a specific number
@@ -98,6 +177,21 @@ pub struct TargetDigitIn {
     pub(crate) value: Vec<i64>,
 }
 
+/// Checks if active field's numeric value is within `[min, max]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DigitRange {
+    pub(crate) min: i64,
+    pub(crate) max: i64,
+}
+
+/// Checks if specified field's numeric value is within `[min, max]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetDigitRange {
+    pub(crate) target: Option<SmolStr>,
+    pub(crate) min: i64,
+    pub(crate) max: i64,
+}
+
 // ============ IP Address Operations ============
 
 /// Checks if active field's IP address is in a list
@@ -113,6 +207,19 @@ pub struct TargetIpIn {
     pub(crate) value: Vec<IpAddr>,
 }
 
+/// Checks if active field's IP address is in any of the networks
+#[derive(Clone, Debug, PartialEq)]
+pub struct CidrIn {
+    pub(crate) value: Vec<IpNet>,
+}
+
+/// Checks if specified field's IP address is in any of the networks
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetCidrIn {
+    pub(crate) target: Option<SmolStr>,
+    pub(crate) value: Vec<IpNet>,
+}
+
 // ============ Legacy/Compatibility ============
 
 #[derive(Clone, Default)]
@@ -127,6 +234,36 @@ pub struct JsonUnescape {}
 #[derive(Clone, Debug, PartialEq)]
 pub struct Base64Decode {}
 
+/// Lowercases active field's character value
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToLower {}
+
+/// Lowercases specified field's character value
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetToLower {
+    pub(crate) target: Option<SmolStr>,
+}
+
+/// Uppercases active field's character value
+#[derive(Clone, Debug, PartialEq)]
+pub struct ToUpper {}
+
+/// Uppercases specified field's character value
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetToUpper {
+    pub(crate) target: Option<SmolStr>,
+}
+
+/// Trims surrounding whitespace of active field's character value
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trim {}
+
+/// Trims surrounding whitespace of specified field's character value
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetTrim {
+    pub(crate) target: Option<SmolStr>,
+}
+
 // ============ Field Selector Functions ============
 
 #[derive(Clone, Debug, PartialEq)]
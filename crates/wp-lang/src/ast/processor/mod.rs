@@ -2,10 +2,12 @@ mod function;
 mod pipe;
 pub(crate) use function::normalize_target;
 pub use function::{
-    Base64Decode, CharsHas, CharsIn, CharsInArg, CharsNotHas, CharsNotHasArg, CharsValue, DigitHas,
-    DigitHasArg, DigitIn, DigitInArg, Has, HasArg, IpIn, IpInArg, JsonUnescape, SelectLast,
-    TakeField, TargetCharsHas, TargetCharsIn, TargetCharsNotHas, TargetDigitHas, TargetDigitIn,
-    TargetHas, TargetIpIn,
+    Base64Decode, CharsHas, CharsIn, CharsInArg, CharsNotHas, CharsNotHasArg, CharsValue, CidrIn,
+    CidrValue, DigitHas, DigitHasArg, DigitIn, DigitInArg, DigitRange, EndsWith, Has, HasArg, IpIn,
+    IpInArg, JsonUnescape, LenBetween, PipeRegex, RegexMatch, SelectLast, StartsWith, TakeField,
+    TargetCharsHas, TargetCharsIn, TargetCharsNotHas, TargetCidrIn, TargetDigitHas, TargetDigitIn,
+    TargetDigitRange, TargetEndsWith, TargetHas, TargetIpIn, TargetLenBetween, TargetRegexMatch,
+    TargetStartsWith, TargetToLower, TargetToUpper, TargetTrim, ToLower, ToUpper, Trim,
 };
 pub use pipe::WplFun;
 pub use pipe::WplPipe;
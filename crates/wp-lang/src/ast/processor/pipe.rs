@@ -1,11 +1,16 @@
+use std::fmt::{Display, Formatter};
+
 use derive_getters::Getters;
 use smol_str::SmolStr;
 
 use super::function::{
-    CharsHas, CharsIn, CharsNotHas, DigitHas, DigitIn, Has, IpIn, JsonUnescape, SelectLast,
-    TakeField, TargetCharsHas, TargetCharsIn, TargetCharsNotHas, TargetDigitHas, TargetDigitIn,
-    TargetHas, TargetIpIn,
+    CharsHas, CharsIn, CharsNotHas, CidrIn, DigitHas, DigitIn, DigitRange, EndsWith, Has, IpIn,
+    JsonUnescape, LenBetween, RegexMatch, SelectLast, StartsWith, TakeField, TargetCharsHas,
+    TargetCharsIn, TargetCharsNotHas, TargetCidrIn, TargetDigitHas, TargetDigitIn,
+    TargetDigitRange, TargetEndsWith, TargetHas, TargetIpIn, TargetLenBetween, TargetRegexMatch,
+    TargetStartsWith, TargetToLower, TargetToUpper, TargetTrim, ToLower, ToUpper, Trim,
 };
+use crate::ast::rule::types::quote_str;
 use crate::ast::{group::WplGroup, processor::Base64Decode};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -19,20 +24,38 @@ pub enum WplFun {
     CharsNotHas(CharsNotHas),
     TargetCharsIn(TargetCharsIn),
     CharsIn(CharsIn),
+    TargetRegexMatch(TargetRegexMatch),
+    RegexMatch(RegexMatch),
+    TargetStartsWith(TargetStartsWith),
+    StartsWith(StartsWith),
+    TargetEndsWith(TargetEndsWith),
+    EndsWith(EndsWith),
+    TargetLenBetween(TargetLenBetween),
+    LenBetween(LenBetween),
     // Numeric comparison functions
     TargetDigitHas(TargetDigitHas),
     DigitHas(DigitHas),
     TargetDigitIn(TargetDigitIn),
     DigitIn(DigitIn),
+    TargetDigitRange(TargetDigitRange),
+    DigitRange(DigitRange),
     // IP address comparison
     TargetIpIn(TargetIpIn),
     IpIn(IpIn),
+    TargetCidrIn(TargetCidrIn),
+    CidrIn(CidrIn),
     // Field existence check
     TargetHas(TargetHas),
     Has(Has),
     // Transformation functions
     TransJsonUnescape(JsonUnescape),
     TransBase64Decode(Base64Decode),
+    TargetToLower(TargetToLower),
+    TransToLower(ToLower),
+    TargetToUpper(TargetToUpper),
+    TransToUpper(ToUpper),
+    TargetTrim(TargetTrim),
+    TransTrim(Trim),
 }
 
 #[derive(Debug, Clone, PartialEq, Getters)]
@@ -106,3 +129,106 @@ where
         }
     }
 }
+
+fn fmt_target(target: &Option<SmolStr>) -> &str {
+    target.as_deref().unwrap_or("_")
+}
+
+fn fmt_arr<T: Display>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(|x| x.to_string()).collect();
+    format!("[{}]", items.join(","))
+}
+
+// 与 `parser::wpl_fun` 的语法一一对应，格式化输出可被重新解析
+impl Display for WplFun {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WplFun::SelectTake(x) => write!(f, "take({})", x.target),
+            WplFun::SelectLast(_) => write!(f, "last()"),
+            WplFun::TargetCharsHas(x) => {
+                write!(f, "f_chars_has({}, {})", fmt_target(&x.target), x.value)
+            }
+            WplFun::CharsHas(x) => write!(f, "chars_has({})", x.value),
+            WplFun::TargetCharsNotHas(x) => {
+                write!(f, "f_chars_not_has({}, {})", fmt_target(&x.target), x.value)
+            }
+            WplFun::CharsNotHas(x) => write!(f, "chars_not_has({})", x.value),
+            WplFun::TargetCharsIn(x) => write!(
+                f,
+                "f_chars_in({}, {})",
+                fmt_target(&x.target),
+                fmt_arr(&x.value)
+            ),
+            WplFun::CharsIn(x) => write!(f, "chars_in({})", fmt_arr(&x.value)),
+            WplFun::TargetRegexMatch(x) => {
+                write!(f, "f_regex_match({}, {})", fmt_target(&x.target), x.value)
+            }
+            WplFun::RegexMatch(x) => write!(f, "regex_match({})", x.value),
+            WplFun::TargetStartsWith(x) => write!(
+                f,
+                "f_starts_with({}, {})",
+                fmt_target(&x.target),
+                quote_str(&x.value)
+            ),
+            WplFun::StartsWith(x) => write!(f, "starts_with({})", quote_str(&x.value)),
+            WplFun::TargetEndsWith(x) => write!(
+                f,
+                "f_ends_with({}, {})",
+                fmt_target(&x.target),
+                quote_str(&x.value)
+            ),
+            WplFun::EndsWith(x) => write!(f, "ends_with({})", quote_str(&x.value)),
+            WplFun::TargetLenBetween(x) => write!(
+                f,
+                "f_len_between({}, {}, {})",
+                fmt_target(&x.target),
+                x.min,
+                x.max
+            ),
+            WplFun::LenBetween(x) => write!(f, "len_between({}, {})", x.min, x.max),
+            WplFun::TargetDigitHas(x) => {
+                write!(f, "f_digit_has({}, {})", fmt_target(&x.target), x.value)
+            }
+            WplFun::DigitHas(x) => write!(f, "digit_has({})", x.value),
+            WplFun::TargetDigitIn(x) => write!(
+                f,
+                "f_digit_in({}, {})",
+                fmt_target(&x.target),
+                fmt_arr(&x.value)
+            ),
+            WplFun::DigitIn(x) => write!(f, "digit_in({})", fmt_arr(&x.value)),
+            WplFun::TargetDigitRange(x) => write!(
+                f,
+                "f_digit_range({}, {}, {})",
+                fmt_target(&x.target),
+                x.min,
+                x.max
+            ),
+            WplFun::DigitRange(x) => write!(f, "digit_range({}, {})", x.min, x.max),
+            WplFun::TargetIpIn(x) => write!(
+                f,
+                "f_ip_in({}, {})",
+                fmt_target(&x.target),
+                fmt_arr(&x.value)
+            ),
+            WplFun::IpIn(x) => write!(f, "ip_in({})", fmt_arr(&x.value)),
+            WplFun::TargetCidrIn(x) => write!(
+                f,
+                "f_cidr_in({}, {})",
+                fmt_target(&x.target),
+                fmt_arr(&x.value)
+            ),
+            WplFun::CidrIn(x) => write!(f, "cidr_in({})", fmt_arr(&x.value)),
+            WplFun::TargetHas(x) => write!(f, "f_has({})", fmt_target(&x.target)),
+            WplFun::Has(_) => write!(f, "has()"),
+            WplFun::TransJsonUnescape(_) => write!(f, "json_unescape()"),
+            WplFun::TransBase64Decode(_) => write!(f, "base64_decode()"),
+            WplFun::TargetToLower(x) => write!(f, "f_to_lower({})", fmt_target(&x.target)),
+            WplFun::TransToLower(_) => write!(f, "to_lower()"),
+            WplFun::TargetToUpper(x) => write!(f, "f_to_upper({})", fmt_target(&x.target)),
+            WplFun::TransToUpper(_) => write!(f, "to_upper()"),
+            WplFun::TargetTrim(x) => write!(f, "f_trim({})", fmt_target(&x.target)),
+            WplFun::TransTrim(_) => write!(f, "trim()"),
+        }
+    }
+}
@@ -12,10 +12,8 @@ pub struct WplRuleMeta {
 impl From<&WplRule> for WplRuleMeta {
     fn from(value: &WplRule) -> Self {
         let mut tags = Vec::new();
-        match &value.statement {
-            WplStatementType::Express(x) => {
-                Self::export_tags(&mut tags, &x.tags);
-            }
+        if let WplStatementType::Express(x) = &value.statement {
+            Self::export_tags(&mut tags, &x.tags);
         }
         Self {
             name: value.name.clone(),
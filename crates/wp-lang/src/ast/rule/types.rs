@@ -27,6 +27,81 @@ pub struct WplRule {
 #[derive(Debug, PartialEq, Clone)]
 pub enum WplStatementType {
     Express(WplExpress),
+    /// 命名字段组：`fields <name> { (..), (..) }`，规则中以 `@<name>` 引用
+    Fields(WplExpress),
+    /// 引入其他包的字段组：`use <pkg>/<name> [as <alias>]`
+    Use(WplUse),
+    /// 规则样例测试：`test <rule> { sample = "..." expect { .. } }`
+    Test(WplTestCase),
+}
+
+/// `use` 引入项；所在 `WplRule` 的名称即包内可见的别名
+#[derive(Debug, PartialEq, Clone)]
+pub struct WplUse {
+    pub pkg: SmolStr,
+    pub name: SmolStr,
+}
+
+impl WplUse {
+    pub fn new<S: Into<SmolStr>>(pkg: S, name: S) -> Self {
+        Self {
+            pkg: pkg.into(),
+            name: name.into(),
+        }
+    }
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.pkg, self.name)
+    }
+}
+
+/// 样例测试；所在 `WplRule` 的名称即被测规则名
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct WplTestCase {
+    pub sample: String,
+    pub expect: Vec<WplExpectField>,
+}
+
+/// 期望字段：`<type>:<name> = "<value>"`，类型与取值可省略
+#[derive(Debug, PartialEq, Clone)]
+pub struct WplExpectField {
+    pub meta: Option<SmolStr>,
+    pub name: SmolStr,
+    pub value: Option<String>,
+}
+
+impl Display for WplExpectField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(meta) = &self.meta {
+            write!(f, "{}:", meta)?;
+        }
+        write!(f, "{}", self.name)?;
+        if let Some(value) = &self.value {
+            write!(f, " = {}", quote_str(value))?;
+        }
+        Ok(())
+    }
+}
+
+/// 输出为可被 WPL 重新读取的字符串字面量
+pub(crate) fn quote_str(value: &str) -> String {
+    let raw_ok = !value.contains("\"#") && !value.contains('\n');
+    if (value.contains('"') || value.contains('\\')) && raw_ok {
+        return format!("r#\"{}\"#", value);
+    }
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
 }
 
 impl DebugFormat for WplStatementType {
@@ -35,8 +110,38 @@ impl DebugFormat for WplStatementType {
         W: ?Sized + Write + DepIndent,
     {
         match self {
-            WplStatementType::Express(define) => define.write(w),
+            WplStatementType::Express(define) | WplStatementType::Fields(define) => define.write(w),
+            WplStatementType::Use(item) => write!(w, "{}", item.path()),
+            WplStatementType::Test(case) => case.write(w),
+        }
+    }
+}
+
+impl DebugFormat for WplTestCase {
+    fn write<W>(&self, w: &mut W) -> std::io::Result<()>
+    where
+        W: ?Sized + Write + DepIndent,
+    {
+        let depth = w.add_indent();
+        self.write_indent(w, depth)?;
+        write!(w, "sample = {}", quote_str(self.sample.as_str()))?;
+        self.write_new_line(w)?;
+        self.write_indent(w, depth)?;
+        write!(w, "expect ")?;
+        self.write_open_brace(w)?;
+        self.write_new_line(w)?;
+        for (idx, item) in self.expect.iter().enumerate() {
+            self.write_indent(w, depth + 1)?;
+            write!(w, "{}", item)?;
+            if idx + 1 < self.expect.len() {
+                write!(w, ",")?;
+            }
+            self.write_new_line(w)?;
         }
+        self.write_indent(w, depth)?;
+        self.write_close_brace(w)?;
+        w.sub_indent();
+        Ok(())
     }
 }
 
@@ -62,20 +167,24 @@ impl From<Vec<WPLField>> for WPLStatement {
  */
 
 impl WplStatementType {
-    pub fn first_group(&self) -> Option<&WplGroup> {
+    /// 可执行的规则表达式；字段组声明与 `use` 返回 None
+    pub fn express(&self) -> Option<&WplExpress> {
         match self {
-            WplStatementType::Express(rule) => rule.group.first(),
+            WplStatementType::Express(rule) => Some(rule),
+            _ => None,
         }
     }
+    pub fn first_group(&self) -> Option<&WplGroup> {
+        self.express().and_then(|rule| rule.group.first())
+    }
     pub fn first_field(&self) -> Option<&WplField> {
-        match self {
-            WplStatementType::Express(rule) => rule.group.first().and_then(|x| x.first()),
-        }
+        self.first_group().and_then(|x| x.first())
     }
 
     pub fn tags(&self) -> &Option<AnnFun> {
         match self {
-            WplStatementType::Express(rule) => &rule.tags,
+            WplStatementType::Express(rule) | WplStatementType::Fields(rule) => &rule.tags,
+            WplStatementType::Use(_) | WplStatementType::Test(_) => &None,
         }
     }
 }
@@ -116,6 +225,9 @@ impl DebugFormat for WplExpress {
                 write!(w, "|")?;
             }
             write!(w, "{}|", pipe)?;
+            if index + 1 == self.pipe_process.len() && !self.group.is_empty() {
+                self.write_new_line(w)?;
+            }
         }
 
         for (index, field) in self.group.iter().enumerate() {
@@ -154,13 +266,10 @@ impl New1<Vec<WplField>> for WplExpress {
 
 impl WplRule {
     pub fn add_tags(mut self, tags: Option<AnnFun>) -> Self {
-        match self.statement {
-            WplStatementType::Express(mut define) => {
-                define.tags = tags;
-                self.statement = WplStatementType::Express(define);
-                self
-            }
+        if let WplStatementType::Express(define) = &mut self.statement {
+            define.tags = tags;
         }
+        self
     }
 }
 
@@ -170,6 +279,20 @@ impl DebugFormat for WplRule {
         W: ?Sized + Write + DepIndent,
     {
         let depth = w.add_indent();
+        let keyword = match &self.statement {
+            WplStatementType::Express(_) => "rule",
+            WplStatementType::Fields(_) => "fields",
+            WplStatementType::Test(_) => "test",
+            WplStatementType::Use(item) => {
+                self.write_indent(w, depth)?;
+                write!(w, "use {}", item.path())?;
+                if item.name != self.name {
+                    write!(w, " as {}", self.name)?;
+                }
+                w.sub_indent();
+                return Ok(());
+            }
+        };
 
         if let Some(tags) = &self.statement.tags() {
             self.write_indent(w, depth)?;
@@ -177,7 +300,7 @@ impl DebugFormat for WplRule {
         }
         self.write_indent(w, depth)?;
 
-        write!(w, "rule {} ", &self.name)?;
+        write!(w, "{} {} ", keyword, &self.name)?;
         self.write_open_brace(w)?;
         self.write_new_line(w)?;
         self.statement.write(w)?;
@@ -221,13 +344,11 @@ impl WplRule {
 impl MergeTags for VecDeque<WplRule> {
     fn merge_tags(&mut self, other_tags: &Option<AnnFun>) {
         for r in self.iter_mut() {
-            match &mut r.statement {
-                WplStatementType::Express(define) => {
-                    if let Some(tags) = &mut define.tags {
-                        tags.merge_tags(other_tags);
-                    } else {
-                        define.tags = other_tags.clone()
-                    }
+            if let WplStatementType::Express(define) = &mut r.statement {
+                if let Some(tags) = &mut define.tags {
+                    tags.merge_tags(other_tags);
+                } else {
+                    define.tags = other_tags.clone()
                 }
             }
         }
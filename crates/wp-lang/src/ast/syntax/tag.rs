@@ -6,6 +6,7 @@ use winnow::stream::Accumulate;
 
 use crate::ast::WplTag;
 use crate::ast::debug::{DebugFormat, DepIndent};
+use crate::ast::rule::types::quote_str;
 use crate::parser::MergeTags;
 
 pub type TagKvs = BTreeMap<SmolStr, SmolStr>;
@@ -15,11 +16,14 @@ pub type CopyRaw = (SmolStr, SmolStr);
 pub enum AnnEnum {
     Tags(TagKvs),
     Copy(CopyRaw),
+    Source(TagKvs),
 }
 #[derive(Debug, PartialEq, Default, Clone)]
 pub struct AnnFun {
     pub tags: TagKvs,
     pub copy_raw: Option<CopyRaw>,
+    /// 来源选择：`src_key` 或事件标签名 -> 通配模式
+    pub source: TagKvs,
 }
 
 impl MergeTags for AnnFun {
@@ -34,6 +38,11 @@ impl MergeTags for AnnFun {
             if self.copy_raw.is_none() {
                 self.copy_raw = atags.copy_raw.clone()
             }
+            for (other_k, other_v) in &atags.source {
+                if !self.source.contains_key(other_k) {
+                    self.source.insert(other_k.clone(), other_v.clone());
+                }
+            }
         }
     }
 }
@@ -53,43 +62,29 @@ impl DebugFormat for AnnFun {
     where
         W: ?Sized + Write + DepIndent,
     {
-        write!(w, "#[tag")?;
-        let tag_count = self.tags.len();
-
-        for (index, t) in self.tags.iter().enumerate() {
-            if tag_count == 1 && index == 0 {
-                self.write_open_parenthesis(w)?;
-                write!(w, "{}:\"{}\"", t.0, t.1)?;
-                self.write_close_parenthesis(w)?;
-
-                write!(w, "]")?;
-                self.write_new_line(w)?;
-                return Ok(());
-            }
-            if index == 0 {
-                self.write_open_parenthesis(w)?;
-            }
-            write!(w, "{}:\"{}\"", t.0, t.1)?;
-
-            if index == tag_count - 1 {
-                self.write_close_parenthesis(w)?;
-            } else {
-                write!(w, ", ")?;
-            }
+        let mut items = Vec::new();
+        if !self.tags.is_empty() {
+            items.push(format!("tag({})", fmt_kvs(&self.tags)));
         }
-
         if let Some((ck, cv)) = &self.copy_raw {
-            write!(w, ", copy_raw")?;
-            self.write_open_parenthesis(w)?;
-            write!(w, "{}:\"{}\"", ck, cv)?;
-            self.write_close_parenthesis(w)?;
+            items.push(format!("copy_raw({}:{})", ck, quote_str(cv)));
         }
-        write!(w, "]")?;
+        if !self.source.is_empty() {
+            items.push(format!("source({})", fmt_kvs(&self.source)));
+        }
+        write!(w, "#[{}]", items.join(", "))?;
         self.write_new_line(w)?;
         Ok(())
     }
 }
 
+fn fmt_kvs(kvs: &TagKvs) -> String {
+    kvs.iter()
+        .map(|(k, v)| format!("{}:{}", k, quote_str(v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 impl Accumulate<()> for AnnFun {
     fn initial(_: Option<usize>) -> Self {
         AnnFun::default()
@@ -30,6 +30,8 @@ impl From<&str> for SepEnum {
             SepEnum::End
         } else if value == "\\s" {
             SepEnum::Str(" ".into())
+        } else if value == "\\t" {
+            SepEnum::Str("\t".into())
         } else {
             SepEnum::Str(value.into())
         }
@@ -42,6 +44,8 @@ impl From<String> for SepEnum {
             SepEnum::End
         } else if value == "\\s" {
             SepEnum::Str(" ".into())
+        } else if value == "\\t" {
+            SepEnum::Str("\t".into())
         } else {
             SepEnum::Str(value.into())
         }
@@ -54,6 +58,8 @@ impl From<SmolStr> for SepEnum {
             SepEnum::End
         } else if value == "\\s" {
             SepEnum::Str(" ".into())
+        } else if value == "\\t" {
+            SepEnum::Str("\t".into())
         } else {
             SepEnum::Str(value)
         }
@@ -275,12 +281,15 @@ impl WplSep {
 
 impl Display for WplFmt<&WplSep> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if !self.0.infer {
-            for c in self.0.sep_str().chars() {
-                if c != ' ' {
+        // 每个字符以 `\` 前缀输出，`\0` 表示读到行尾
+        match (&self.0.cur_val, self.0.infer) {
+            (Some(SepEnum::End), false) => write!(f, "\\0")?,
+            (Some(SepEnum::Str(sep)), false) => {
+                for c in sep.chars() {
                     write!(f, "\\{}", c)?;
                 }
             }
+            _ => {}
         }
         Ok(())
     }
@@ -2,6 +2,11 @@ use std::fmt::Debug;
 
 use thiserror::Error;
 
+use crate::ast::{WplField, WplPackage, WplTestCase};
+use crate::eval::grok_lib;
+use crate::eval::{ParseExplain, WplEvaluator};
+use crate::parser::datatype::GROK;
+
 #[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
 pub enum FailureAction {
     #[default]
@@ -57,6 +62,165 @@ impl Checker {
             println!("[ck-step {} :] {}", level, format_args);
         }
     }
+    /// 检查包内 grok 字段引用的模式均已定义
+    pub fn check_grok(&self, pkg: &WplPackage) -> Result<(), CheckFail> {
+        let unknown = unknown_grok_patterns(pkg);
+        if unknown.is_empty() {
+            self.suc_notice(format_args!("grok patterns of {} ok", pkg.name));
+            return Ok(());
+        }
+        for (rule, name) in &unknown {
+            self.fail_action(format_args!(
+                "unknown grok pattern: {} (rule: {}/{})",
+                name, pkg.name, rule
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// 执行包内样例测试，失败时输出差异；返回 (通过数, 总数)
+    pub fn check_samples(&self, pkg: &WplPackage) -> Result<(usize, usize), CheckFail> {
+        let outcomes = run_sample_tests(pkg);
+        let total = outcomes.len();
+        let mut passed = 0;
+        for outcome in &outcomes {
+            if outcome.passed() {
+                passed += 1;
+                self.suc_notice(format_args!("sample {} ok", outcome.title()));
+                continue;
+            }
+            println!("FAIL {}", outcome.title());
+            for diff in &outcome.diffs {
+                println!("  {}", diff);
+            }
+            self.fail_action(format_args!("sample {} failed", outcome.title()))?;
+        }
+        Ok((passed, total))
+    }
+}
+
+/// 单个样例测试的结果，`diffs` 为空表示通过
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleOutcome {
+    pub pkg: String,
+    pub rule: String,
+    /// 同一规则下的样例序号（从 1 开始）
+    pub index: usize,
+    pub diffs: Vec<String>,
+}
+
+impl SampleOutcome {
+    pub fn passed(&self) -> bool {
+        self.diffs.is_empty()
+    }
+    pub fn title(&self) -> String {
+        format!("{}/{}#{}", self.pkg, self.rule, self.index)
+    }
+}
+
+/// 逐个执行包内 `test` 样例；包需已完成字段组引用展开
+pub fn run_sample_tests(pkg: &WplPackage) -> Vec<SampleOutcome> {
+    let mut outcomes: Vec<SampleOutcome> = Vec::new();
+    for (rule, case) in pkg.tests() {
+        let index = outcomes.iter().filter(|x| x.rule == rule.as_str()).count() + 1;
+        outcomes.push(SampleOutcome {
+            pkg: pkg.name.to_string(),
+            rule: rule.to_string(),
+            index,
+            diffs: sample_diffs(pkg, rule.as_str(), case),
+        });
+    }
+    outcomes
+}
+
+/// 以 explain 模式解析单条样本；包需已完成字段组引用展开
+pub fn explain_sample(pkg: &WplPackage, rule: &str, sample: &str) -> Result<ParseExplain, String> {
+    rule_evaluator(pkg, rule)?
+        .explain(sample)
+        .map_err(|e| format!("preorder pipe failed: {}", e))
+}
+
+fn rule_evaluator(pkg: &WplPackage, rule: &str) -> Result<WplEvaluator, String> {
+    let Some(express) = pkg
+        .rules
+        .iter()
+        .find(|x| x.name == rule)
+        .and_then(|x| x.statement.express())
+    else {
+        return Err(format!("rule '{}' not found", rule));
+    };
+    WplEvaluator::from(express, None).map_err(|e| format!("build rule failed: {}", e))
+}
+
+fn sample_diffs(pkg: &WplPackage, rule: &str, case: &WplTestCase) -> Vec<String> {
+    let evaluator = match rule_evaluator(pkg, rule) {
+        Ok(evaluator) => evaluator,
+        Err(e) => return vec![e],
+    };
+    let (record, residue) = match evaluator.proc(case.sample.as_str(), 0) {
+        Ok(result) => result,
+        Err(e) => return vec![format!("parse failed: {}", e)],
+    };
+    let mut diffs = Vec::new();
+    if !residue.is_empty() {
+        diffs.push(format!("residue: {:?}", residue));
+    }
+    for expect in &case.expect {
+        let Some(field) = record.field(expect.name.as_str()) else {
+            diffs.push(format!("- {}\n  + <missing>", expect));
+            continue;
+        };
+        let meta = field.get_meta().to_string();
+        let value = field.get_value().to_string();
+        let meta_ok = expect.meta.as_ref().is_none_or(|x| x.as_str() == meta);
+        let value_ok = expect.value.as_ref().is_none_or(|x| *x == value);
+        if !(meta_ok && value_ok) {
+            diffs.push(format!(
+                "- {}\n  + {}:{} = {:?}",
+                expect, meta, expect.name, value
+            ));
+        }
+    }
+    diffs
+}
+
+/// 包内 grok 字段（含子字段）引用的未知模式：(rule, pattern)
+pub fn unknown_grok_patterns(pkg: &WplPackage) -> Vec<(String, String)> {
+    let lib = grok_lib();
+    let mut unknown = Vec::new();
+    for rule in &pkg.rules {
+        let Some(express) = rule.statement.express() else {
+            continue;
+        };
+        let mut exprs = Vec::new();
+        for group in &express.group {
+            for field in &group.fields {
+                collect_grok_exprs(field, &mut exprs);
+            }
+        }
+        for expr in exprs {
+            for name in lib.unknown_patterns(expr.as_str()) {
+                let item = (rule.name.to_string(), name);
+                if !unknown.contains(&item) {
+                    unknown.push(item);
+                }
+            }
+        }
+    }
+    unknown
+}
+
+fn collect_grok_exprs(field: &WplField, out: &mut Vec<String>) {
+    if field.meta_name == GROK
+        && let Some(content) = &field.content
+    {
+        out.push(content.clone());
+    }
+    if let Some(subs) = &field.sub_fields {
+        for (_, sub) in subs.conf_items().exact_iter() {
+            collect_grok_exprs(sub, out);
+        }
+    }
 }
 
 mod std_trait_impl {
@@ -104,3 +268,88 @@ mod std_trait_impl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WplCode;
+    use orion_error::TestAssert;
+
+    #[test]
+    fn test_check_unknown_grok() {
+        let code = WplCode::try_from((
+            "test.wpl".into(),
+            r#"package x {
+  rule a { (grok("%{IP:ip} %{NO_SUCH_A:x}")) }
+  rule b { (chars, json(grok("%{INT:n} %{NO_SUCH_B:y} %{NO_SUCH_A:z}")@msg)) }
+  rule c { (grok("%{COMBINEDAPACHELOG}")) }
+}"#,
+        ))
+        .assert();
+        let pkg = code.parse_pkg().assert();
+        assert_eq!(
+            unknown_grok_patterns(&pkg),
+            vec![
+                ("a".to_string(), "NO_SUCH_A".to_string()),
+                ("b".to_string(), "NO_SUCH_B".to_string()),
+                ("b".to_string(), "NO_SUCH_A".to_string()),
+            ]
+        );
+        assert!(Checker::new(true, 0).check_grok(&pkg).is_err());
+        assert!(Checker::new(false, 0).check_grok(&pkg).is_ok());
+    }
+
+    #[test]
+    fn test_run_sample_tests() {
+        let code = WplCode::try_from((
+            "test.wpl".into(),
+            r##"package nginx {
+  rule access { (ip:sip, digit:status, chars:path) }
+  test access {
+    sample = "10.0.0.1 200 /index.html"
+    expect { ip:sip = "10.0.0.1", digit:status = "200", path }
+  }
+  test access {
+    sample = r#"10.0.0.2 404 /a"#
+    expect { chars:sip, status = "200", agent, }
+  }
+  test access {
+    sample = "oops"
+  }
+  test missing {
+    sample = "x"
+  }
+}"##,
+        ))
+        .assert();
+        let pkg = code.parse_pkg().assert();
+        let outcomes = run_sample_tests(&pkg);
+        assert_eq!(outcomes.len(), 4);
+        assert!(outcomes[0].passed());
+        assert_eq!(outcomes[1].title(), "nginx/access#2");
+        assert_eq!(
+            outcomes[1].diffs,
+            vec![
+                "- chars:sip\n  + ip:sip = \"10.0.0.2\"".to_string(),
+                "- status = \"200\"\n  + digit:status = \"404\"".to_string(),
+                "- agent\n  + <missing>".to_string(),
+            ]
+        );
+        assert!(outcomes[2].diffs[0].starts_with("parse failed"));
+        assert_eq!(outcomes[3].diffs, vec!["rule 'missing' not found"]);
+        assert_eq!(Checker::new(false, 0).check_samples(&pkg).assert(), (1, 4));
+        assert!(Checker::new(true, 0).check_samples(&pkg).is_err());
+        // 格式化输出可被重新读取
+        let text = pkg.to_string();
+        let again = WplCode::try_from(("again.wpl".into(), text.as_str()))
+            .assert()
+            .parse_pkg()
+            .assert();
+        assert_eq!(run_sample_tests(&again), outcomes);
+
+        let explain = explain_sample(&pkg, "access", "oops").assert();
+        assert!(!explain.is_match());
+        assert!(explain.render().contains("ip:sip [0..0] fail"));
+        assert!(explain_sample(&pkg, "missing", "x").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use bytes::Bytes;
+use orion_error::{ErrorOwe, ErrorWith};
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use wp_parse_api::{PipeProcessor, RawData, WparseResult};
+
+/// 解压输出上限（防解压炸弹），默认 64 MiB。
+/// 引擎启动时按 `[performance].decompress_max_bytes` 调用 [`set_max_decompressed_bytes`]；
+/// 未配置时可由环境变量 `WP_DECOMPRESS_MAX_BYTES` 覆盖。
+pub const DEFAULT_MAX_DECOMPRESSED_BYTES: usize = 64 * 1024 * 1024;
+
+// 0 表示尚未初始化
+static MAX_DECOMPRESSED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_max_decompressed_bytes(limit: usize) {
+    MAX_DECOMPRESSED_BYTES.store(limit.max(1), Ordering::Relaxed);
+}
+
+pub fn max_decompressed_bytes() -> usize {
+    match MAX_DECOMPRESSED_BYTES.load(Ordering::Relaxed) {
+        0 => {
+            let limit = std::env::var("WP_DECOMPRESS_MAX_BYTES")
+                .ok()
+                .and_then(|s| s.parse::<usize>().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(DEFAULT_MAX_DECOMPRESSED_BYTES);
+            MAX_DECOMPRESSED_BYTES.store(limit, Ordering::Relaxed);
+            limit
+        }
+        limit => limit,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Gzip,
+    Zlib,
+    Zstd,
+}
+
+#[derive(Debug)]
+pub struct DecompressProc {
+    codec: Codec,
+}
+
+impl DecompressProc {
+    pub fn new(codec: Codec) -> Self {
+        Self { codec }
+    }
+}
+
+/// 解压并在输出超过 `limit` 字节时报错
+fn decompress(codec: Codec, input: &[u8], limit: usize) -> io::Result<Vec<u8>> {
+    let reader: Box<dyn Read + '_> = match codec {
+        Codec::Gzip => Box::new(flate2::read::MultiGzDecoder::new(input)),
+        Codec::Zlib => Box::new(flate2::read::ZlibDecoder::new(input)),
+        Codec::Zstd => Box::new(zstd::stream::read::Decoder::new(input)?),
+    };
+    let mut out = Vec::new();
+    reader.take(limit as u64 + 1).read_to_end(&mut out)?;
+    if out.len() > limit {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("decompressed size exceeds {} bytes", limit),
+        ));
+    }
+    Ok(out)
+}
+
+impl PipeProcessor for DecompressProc {
+    /// Decompresses the payload while preserving the input container type.
+    /// String inputs yield a string when the output is valid UTF-8, bytes otherwise.
+    fn process(&self, data: RawData) -> WparseResult<RawData> {
+        let limit = max_decompressed_bytes();
+        match data {
+            RawData::String(s) => {
+                let out = decompress(self.codec, s.as_bytes(), limit)
+                    .owe_data()
+                    .want(self.name())?;
+                match String::from_utf8(out) {
+                    Ok(vstring) => Ok(RawData::from_string(vstring)),
+                    Err(e) => Ok(RawData::Bytes(Bytes::from(e.into_bytes()))),
+                }
+            }
+            RawData::Bytes(b) => {
+                let out = decompress(self.codec, b.as_ref(), limit)
+                    .owe_data()
+                    .want(self.name())?;
+                Ok(RawData::Bytes(Bytes::from(out)))
+            }
+            RawData::ArcBytes(b) => {
+                let out = decompress(self.codec, b.as_ref(), limit)
+                    .owe_data()
+                    .want(self.name())?;
+                Ok(RawData::ArcBytes(Arc::new(out)))
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self.codec {
+            Codec::Gzip => "decode/gzip",
+            Codec::Zlib => "decode/zlib",
+            Codec::Zstd => "decode/zstd",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::AnyResult;
+    use flate2::Compression;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use std::io::Write;
+
+    use super::*;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    #[test]
+    fn test_decompress_codecs() -> AnyResult<()> {
+        let plain = b"<13>Oct 11 22:14:15 host app: hello";
+
+        let data = RawData::Bytes(Bytes::from(gzip(plain)));
+        let out = DecompressProc::new(Codec::Gzip).process(data)?;
+        assert!(matches!(out, RawData::Bytes(_)));
+        assert_eq!(
+            crate::eval::builtins::raw_to_utf8_string(&out).as_bytes(),
+            plain
+        );
+
+        let mut enc = ZlibEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(plain)?;
+        let data = RawData::ArcBytes(Arc::new(enc.finish()?));
+        let out = DecompressProc::new(Codec::Zlib).process(data)?;
+        assert!(matches!(out, RawData::ArcBytes(_)));
+        assert_eq!(
+            crate::eval::builtins::raw_to_utf8_string(&out).as_bytes(),
+            plain
+        );
+
+        let data = RawData::Bytes(Bytes::from(zstd::encode_all(&plain[..], 0)?));
+        let out = DecompressProc::new(Codec::Zstd).process(data)?;
+        assert_eq!(
+            crate::eval::builtins::raw_to_utf8_string(&out).as_bytes(),
+            plain
+        );
+
+        let bad = RawData::Bytes(Bytes::from_static(b"not compressed"));
+        assert!(DecompressProc::new(Codec::Gzip).process(bad).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompress_limit() {
+        let bomb = gzip(&vec![b'a'; 4096]);
+        assert_eq!(decompress(Codec::Gzip, &bomb, 4096).unwrap().len(), 4096);
+        assert!(decompress(Codec::Gzip, &bomb, 4095).is_err());
+
+        // 超过配置上限的整条输入报错，而不是截断后继续
+        set_max_decompressed_bytes(1024);
+        let out = DecompressProc::new(Codec::Gzip).process(RawData::Bytes(Bytes::from(bomb)));
+        set_max_decompressed_bytes(DEFAULT_MAX_DECOMPRESSED_BYTES);
+        assert!(out.is_err());
+    }
+}
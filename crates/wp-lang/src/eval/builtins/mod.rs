@@ -4,12 +4,15 @@ use std::sync::{Arc, Once};
 use wp_parse_api::{PipeHold, RawData};
 
 pub mod base64;
+pub mod compress;
 pub mod hex;
 mod pipe_fun;
+pub mod proto;
 pub mod quotation;
 pub mod registry;
 
 use base64::Base64Proc;
+use compress::{Codec, DecompressProc};
 use hex::HexProc;
 use quotation::EscQuotaProc;
 
@@ -37,6 +40,18 @@ fn decode_hex_stage() -> PipeHold {
     Arc::new(HexProc)
 }
 
+fn decode_gzip_stage() -> PipeHold {
+    Arc::new(DecompressProc::new(Codec::Gzip))
+}
+
+fn decode_zlib_stage() -> PipeHold {
+    Arc::new(DecompressProc::new(Codec::Zlib))
+}
+
+fn decode_zstd_stage() -> PipeHold {
+    Arc::new(DecompressProc::new(Codec::Zstd))
+}
+
 fn unquote_unescape_stage() -> PipeHold {
     Arc::new(EscQuotaProc)
 }
@@ -46,6 +61,9 @@ pub fn ensure_builtin_pipe_units() {
     BUILTIN_PIPE_INIT.call_once(|| {
         registry::register_pipe_unit("decode/base64", decode_base64_stage);
         registry::register_pipe_unit("decode/hex", decode_hex_stage);
+        registry::register_pipe_unit("decode/gzip", decode_gzip_stage);
+        registry::register_pipe_unit("decode/zlib", decode_zlib_stage);
+        registry::register_pipe_unit("decode/zstd", decode_zstd_stage);
         registry::register_pipe_unit("unquote/unescape", unquote_unescape_stage);
     });
 }
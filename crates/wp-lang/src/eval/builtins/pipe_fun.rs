@@ -1,8 +1,10 @@
 use crate::ast::WplFun;
 use crate::ast::processor::{
-    Base64Decode, CharsHas, CharsIn, CharsNotHas, DigitHas, DigitIn, Has, IpIn, JsonUnescape,
-    SelectLast, TakeField, TargetCharsHas, TargetCharsIn, TargetCharsNotHas, TargetDigitHas,
-    TargetDigitIn, TargetHas, TargetIpIn,
+    Base64Decode, CharsHas, CharsIn, CharsNotHas, CidrIn, DigitHas, DigitIn, DigitRange, EndsWith,
+    Has, IpIn, JsonUnescape, LenBetween, RegexMatch, SelectLast, StartsWith, TakeField,
+    TargetCharsHas, TargetCharsIn, TargetCharsNotHas, TargetCidrIn, TargetDigitHas, TargetDigitIn,
+    TargetDigitRange, TargetEndsWith, TargetHas, TargetIpIn, TargetLenBetween, TargetRegexMatch,
+    TargetStartsWith, TargetToLower, TargetToUpper, TargetTrim, ToLower, ToUpper, Trim,
 };
 use crate::eval::runtime::field_pipe::{FieldIndex, FieldPipe, FieldSelector, FieldSelectorSpec};
 use base64::Engine;
@@ -253,6 +255,186 @@ impl FieldPipe for IpIn {
     }
 }
 
+// 字符值谓词：不存在或非字符类型视为不匹配
+#[inline]
+fn chars_check(
+    field: Option<&mut DataField>,
+    desc: &'static str,
+    pred: impl Fn(&str) -> bool,
+) -> WResult<()> {
+    if let Some(item) = field
+        && let Value::Chars(value) = item.get_value()
+        && pred(value.as_str())
+    {
+        return Ok(());
+    }
+    fail.context(ctx_desc(desc)).parse_next(&mut "")
+}
+
+// 字符值改写：不存在或非字符类型视为失败
+#[inline]
+fn chars_rewrite(
+    field: Option<&mut DataField>,
+    desc: &'static str,
+    f: impl Fn(&str) -> String,
+) -> WResult<()> {
+    if let Some(item) = field
+        && let Value::Chars(value) = item.get_value_mut()
+    {
+        *value = f(value.as_str()).into();
+        return Ok(());
+    }
+    fail.context(ctx_desc(desc)).parse_next(&mut "")
+}
+
+impl FieldPipe for TargetRegexMatch {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_check(field, "<pipe> | not match", |v| self.value.0.is_match(v))
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for RegexMatch {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_check(field, "<pipe> | not match", |v| self.value.0.is_match(v))
+    }
+}
+
+impl FieldPipe for TargetStartsWith {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_check(field, "<pipe> | not match", |v| {
+            v.starts_with(self.value.as_str())
+        })
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for StartsWith {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_check(field, "<pipe> | not match", |v| {
+            v.starts_with(self.value.as_str())
+        })
+    }
+}
+
+impl FieldPipe for TargetEndsWith {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_check(field, "<pipe> | not match", |v| {
+            v.ends_with(self.value.as_str())
+        })
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for EndsWith {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_check(field, "<pipe> | not match", |v| {
+            v.ends_with(self.value.as_str())
+        })
+    }
+}
+
+impl FieldPipe for TargetLenBetween {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_check(field, "<pipe> | out of range", |v| {
+            (self.min..=self.max).contains(&v.chars().count())
+        })
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for LenBetween {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_check(field, "<pipe> | out of range", |v| {
+            (self.min..=self.max).contains(&v.chars().count())
+        })
+    }
+}
+
+impl FieldPipe for TargetDigitRange {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        if let Some(item) = field
+            && let Value::Digit(value) = item.get_value()
+            && (self.min..=self.max).contains(value)
+        {
+            return Ok(());
+        }
+        fail.context(ctx_desc("<pipe> | out of range"))
+            .parse_next(&mut "")
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for DigitRange {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        if let Some(item) = field
+            && let Value::Digit(value) = item.get_value()
+            && (self.min..=self.max).contains(value)
+        {
+            return Ok(());
+        }
+        fail.context(ctx_desc("<pipe> | out of range"))
+            .parse_next(&mut "")
+    }
+}
+
+impl FieldPipe for TargetCidrIn {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        if let Some(item) = field
+            && let Value::IpAddr(value) = item.get_value()
+            && self.value.iter().any(|net| net.contains(value))
+        {
+            return Ok(());
+        }
+        fail.context(ctx_desc("<pipe> | not in"))
+            .parse_next(&mut "")
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for CidrIn {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        if let Some(item) = field
+            && let Value::IpAddr(value) = item.get_value()
+            && self.value.iter().any(|net| net.contains(value))
+        {
+            return Ok(());
+        }
+        fail.context(ctx_desc("<pipe> | not in"))
+            .parse_next(&mut "")
+    }
+}
+
 impl FieldPipe for TargetHas {
     #[inline]
     fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
@@ -313,6 +495,60 @@ impl FieldPipe for Base64Decode {
     }
 }
 
+impl FieldPipe for TargetToLower {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_rewrite(field, "to_lower", str::to_lowercase)
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for ToLower {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_rewrite(field, "to_lower", str::to_lowercase)
+    }
+}
+
+impl FieldPipe for TargetToUpper {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_rewrite(field, "to_upper", str::to_uppercase)
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for ToUpper {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_rewrite(field, "to_upper", str::to_uppercase)
+    }
+}
+
+impl FieldPipe for TargetTrim {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_rewrite(field, "trim", |v| v.trim().to_string())
+    }
+
+    fn auto_select<'a>(&'a self) -> Option<FieldSelectorSpec<'a>> {
+        self.target.as_deref().map(FieldSelectorSpec::Take)
+    }
+}
+
+impl FieldPipe for Trim {
+    #[inline]
+    fn process(&self, field: Option<&mut DataField>) -> WResult<()> {
+        chars_rewrite(field, "trim", |v| v.trim().to_string())
+    }
+}
+
 impl WplFun {
     pub fn as_field_pipe(&self) -> Option<&dyn FieldPipe> {
         match self {
@@ -323,16 +559,34 @@ impl WplFun {
             WplFun::CharsNotHas(fun) => Some(fun),
             WplFun::TargetCharsIn(fun) => Some(fun),
             WplFun::CharsIn(fun) => Some(fun),
+            WplFun::TargetRegexMatch(fun) => Some(fun),
+            WplFun::RegexMatch(fun) => Some(fun),
+            WplFun::TargetStartsWith(fun) => Some(fun),
+            WplFun::StartsWith(fun) => Some(fun),
+            WplFun::TargetEndsWith(fun) => Some(fun),
+            WplFun::EndsWith(fun) => Some(fun),
+            WplFun::TargetLenBetween(fun) => Some(fun),
+            WplFun::LenBetween(fun) => Some(fun),
             WplFun::TargetDigitHas(fun) => Some(fun),
             WplFun::DigitHas(fun) => Some(fun),
             WplFun::TargetDigitIn(fun) => Some(fun),
             WplFun::DigitIn(fun) => Some(fun),
+            WplFun::TargetDigitRange(fun) => Some(fun),
+            WplFun::DigitRange(fun) => Some(fun),
             WplFun::TargetIpIn(fun) => Some(fun),
             WplFun::IpIn(fun) => Some(fun),
+            WplFun::TargetCidrIn(fun) => Some(fun),
+            WplFun::CidrIn(fun) => Some(fun),
             WplFun::TargetHas(fun) => Some(fun),
             WplFun::Has(fun) => Some(fun),
             WplFun::TransJsonUnescape(fun) => Some(fun),
             WplFun::TransBase64Decode(fun) => Some(fun),
+            WplFun::TargetToLower(fun) => Some(fun),
+            WplFun::TransToLower(fun) => Some(fun),
+            WplFun::TargetToUpper(fun) => Some(fun),
+            WplFun::TransToUpper(fun) => Some(fun),
+            WplFun::TargetTrim(fun) => Some(fun),
+            WplFun::TransTrim(fun) => Some(fun),
         }
     }
 
@@ -353,6 +607,15 @@ impl WplFun {
             WplFun::TargetDigitIn(fun) => fun.auto_select(),
             WplFun::TargetIpIn(fun) => fun.auto_select(),
             WplFun::TargetHas(fun) => fun.auto_select(),
+            WplFun::TargetRegexMatch(fun) => fun.auto_select(),
+            WplFun::TargetStartsWith(fun) => fun.auto_select(),
+            WplFun::TargetEndsWith(fun) => fun.auto_select(),
+            WplFun::TargetLenBetween(fun) => fun.auto_select(),
+            WplFun::TargetDigitRange(fun) => fun.auto_select(),
+            WplFun::TargetCidrIn(fun) => fun.auto_select(),
+            WplFun::TargetToLower(fun) => fun.auto_select(),
+            WplFun::TargetToUpper(fun) => fun.auto_select(),
+            WplFun::TargetTrim(fun) => fun.auto_select(),
             _ => None,
         }
     }
@@ -450,6 +713,55 @@ mod tests {
         }
     }
 
+    fn chars(name: &str, v: &str) -> DataField {
+        DataField::from_chars(name.to_string(), v.to_string())
+    }
+
+    #[test]
+    fn match_funs_check_chars_values() {
+        use crate::parser::wpl_fun::wpl_fun;
+
+        let mut field = chars("msg", "GET /index.html");
+        let ok = |code: &str, field: &mut DataField| {
+            let fun = wpl_fun.parse(code).expect("parse fun");
+            fun.as_field_pipe()
+                .expect("field pipe")
+                .process(Some(field))
+                .is_ok()
+        };
+        assert!(ok(r#"regex_match("^(GET|POST) /")"#, &mut field));
+        assert!(!ok(r#"regex_match("^POST")"#, &mut field));
+        assert!(ok(r#"starts_with("GET ")"#, &mut field));
+        assert!(ok("ends_with(.html)", &mut field));
+        assert!(!ok("ends_with(.css)", &mut field));
+        assert!(ok("len_between(1, 15)", &mut field));
+        assert!(!ok("len_between(1, 14)", &mut field));
+
+        let mut status = DataField::from_digit("status".to_string(), 204);
+        assert!(ok("digit_range(200, 299)", &mut status));
+        assert!(!ok("digit_range(300, 399)", &mut status));
+        // 类型不符视为不匹配
+        assert!(!ok(r#"starts_with("2")"#, &mut status));
+
+        let mut sip = DataField::from_ip("sip".to_string(), "10.1.2.3".parse().unwrap());
+        assert!(ok("cidr_in([192.168.0.0/16,10.0.0.0/8])", &mut sip));
+        assert!(!ok("cidr_in([10.1.2.4])", &mut sip));
+    }
+
+    #[test]
+    fn trans_funs_rewrite_chars_values() {
+        let mut fields = [chars("method", "  Get ")];
+        Trim {}.process(fields.get_mut(0)).expect("trim");
+        ToUpper {}.process(fields.get_mut(0)).expect("upper");
+        assert_eq!(fields[0].get_value(), &Value::Chars("GET".into()));
+        ToLower {}.process(fields.get_mut(0)).expect("lower");
+        assert_eq!(fields[0].get_value(), &Value::Chars("get".into()));
+        assert!(ToLower {}.process(None).is_err());
+
+        let mut digit = [DataField::from_digit("n".to_string(), 1)];
+        assert!(Trim {}.process(digit.get_mut(0)).is_err());
+    }
+
     #[test]
     fn json_unescape_returns_err_on_invalid_escape() {
         let mut fields = vec![DataField::from_chars(
@@ -0,0 +1,211 @@
+use once_cell::sync::Lazy;
+use orion_error::{ErrorOwe, ErrorWith};
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, SerializeOptions};
+use std::sync::{Arc, RwLock};
+
+use wp_parse_api::{PipeHold, PipeProcessor, RawData, WparseResult};
+
+use crate::parser::error::{WplCodeError, WplCodeReason, WplCodeResult};
+
+/// 预处理单元前缀：`|decode/proto/<package.Message>|`
+pub const PROTO_PIPE_PREFIX: &str = "decode/proto/";
+
+// 已加载的描述符集（protoc --descriptor_set_out 产物），规则加载时按消息全名查找
+static PROTO_POOL: Lazy<RwLock<DescriptorPool>> = Lazy::new(|| RwLock::new(DescriptorPool::new()));
+
+pub fn proto_pool() -> DescriptorPool {
+    PROTO_POOL
+        .read()
+        .map(|x| x.clone())
+        .unwrap_or_else(|e| e.into_inner().clone())
+}
+
+/// 注册一个编译后的描述符集，返回其中的文件数；重复的文件会被跳过
+pub fn register_proto_descriptors(bytes: &[u8]) -> Result<usize, String> {
+    let mut pool = PROTO_POOL.write().unwrap_or_else(|e| e.into_inner());
+    let before = pool.files().len();
+    pool.decode_file_descriptor_set(bytes)
+        .map_err(|e| format!("bad descriptor set: {}", e))?;
+    Ok(pool.files().len() - before)
+}
+
+/// 按名称创建 protobuf 解码单元；非 `decode/proto/` 前缀返回 None
+pub fn create_proto_unit(name: &str) -> WplCodeResult<Option<PipeHold>> {
+    let Some(msg_name) = name.strip_prefix(PROTO_PIPE_PREFIX) else {
+        return Ok(None);
+    };
+    let desc = proto_pool().get_message_by_name(msg_name).ok_or_else(|| {
+        WplCodeError::from(WplCodeReason::UnSupport(format!(
+            "protobuf message '{}' not found in descriptor sets",
+            msg_name
+        )))
+    })?;
+    Ok(Some(Arc::new(ProtoDecodeProc::new(desc))))
+}
+
+/// 将二进制 protobuf 消息解码为 JSON 文本，交由后续 `json` 字段展开
+#[derive(Debug)]
+pub struct ProtoDecodeProc {
+    desc: MessageDescriptor,
+    opts: SerializeOptions,
+}
+
+impl ProtoDecodeProc {
+    pub fn new(desc: MessageDescriptor) -> Self {
+        // 使用 .proto 中的字段名，64 位整数保持数值，枚举输出名称
+        let opts = SerializeOptions::new()
+            .use_proto_field_name(true)
+            .stringify_64_bit_integers(false);
+        Self { desc, opts }
+    }
+
+    fn decode(&self, bytes: &[u8]) -> WparseResult<RawData> {
+        let msg = DynamicMessage::decode(self.desc.clone(), bytes)
+            .owe_data()
+            .want("protobuf decode")
+            .with(self.desc.full_name())?;
+        let mut buf = Vec::with_capacity(bytes.len() * 2);
+        let mut ser = serde_json::Serializer::new(&mut buf);
+        msg.serialize_with_options(&mut ser, &self.opts)
+            .owe_data()
+            .want("protobuf to json")?;
+        let json = String::from_utf8(buf).owe_data().want("protobuf to json")?;
+        Ok(RawData::from_string(json))
+    }
+}
+
+impl PipeProcessor for ProtoDecodeProc {
+    fn process(&self, data: RawData) -> WparseResult<RawData> {
+        match data {
+            RawData::String(s) => self.decode(s.as_bytes()),
+            RawData::Bytes(b) => self.decode(b.as_ref()),
+            RawData::ArcBytes(b) => self.decode(b.as_ref()),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "decode/proto"
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::eval::WplEvaluator;
+    use crate::types::AnyResult;
+    use bytes::Bytes;
+    use prost_reflect::Value;
+    use prost_reflect::prost::Message;
+    use prost_reflect::prost_types::field_descriptor_proto::{Label, Type};
+    use prost_reflect::prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    };
+    use wp_model_core::model::DataField;
+
+    fn field(
+        name: &str,
+        num: i32,
+        ty: Type,
+        label: Label,
+        type_name: Option<&str>,
+    ) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(num),
+            r#type: Some(ty as i32),
+            label: Some(label as i32),
+            type_name: type_name.map(|x| x.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// wptest.LogEntry { string host = 1; int64 code = 2; Source src = 3; repeated string tags = 4; }
+    pub(crate) fn register_test_descriptors() {
+        let file = FileDescriptorProto {
+            name: Some("wptest/log.proto".to_string()),
+            package: Some("wptest".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![
+                DescriptorProto {
+                    name: Some("Source".to_string()),
+                    field: vec![field("ip", 1, Type::String, Label::Optional, None)],
+                    ..Default::default()
+                },
+                DescriptorProto {
+                    name: Some("LogEntry".to_string()),
+                    field: vec![
+                        field("host", 1, Type::String, Label::Optional, None),
+                        field("code", 2, Type::Int64, Label::Optional, None),
+                        field(
+                            "src",
+                            3,
+                            Type::Message,
+                            Label::Optional,
+                            Some(".wptest.Source"),
+                        ),
+                        field("tags", 4, Type::String, Label::Repeated, None),
+                    ],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let set = FileDescriptorSet { file: vec![file] };
+        register_proto_descriptors(&set.encode_to_vec()).expect("register descriptors");
+    }
+
+    fn test_entry() -> Vec<u8> {
+        let pool = proto_pool();
+        let desc = pool.get_message_by_name("wptest.LogEntry").unwrap();
+        let mut src = DynamicMessage::new(pool.get_message_by_name("wptest.Source").unwrap());
+        src.set_field_by_name("ip", Value::String("10.0.0.1".to_string()));
+        let mut msg = DynamicMessage::new(desc);
+        msg.set_field_by_name("host", Value::String("web-01".to_string()));
+        msg.set_field_by_name("code", Value::I64(500));
+        msg.set_field_by_name("src", Value::Message(src));
+        msg.set_field_by_name("tags", Value::List(vec![Value::String("grpc".to_string())]));
+        msg.encode_to_vec()
+    }
+
+    #[test]
+    fn test_proto_decode() -> AnyResult<()> {
+        register_test_descriptors();
+        let unit = create_proto_unit("decode/proto/wptest.LogEntry")?.unwrap();
+        let out = unit.process(RawData::Bytes(Bytes::from(test_entry())))?;
+        let json: serde_json::Value =
+            serde_json::from_str(crate::eval::builtins::raw_to_utf8_string(&out).as_str())?;
+        assert_eq!(json["host"], "web-01");
+        assert_eq!(json["code"], 500);
+        assert_eq!(json["src"]["ip"], "10.0.0.1");
+
+        assert!(create_proto_unit("decode/base64")?.is_none());
+        assert!(create_proto_unit("decode/proto/wptest.NoSuch").is_err());
+        assert!(
+            unit.process(RawData::Bytes(Bytes::from_static(b"\xff\xff")))
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_proto_rule() -> AnyResult<()> {
+        register_test_descriptors();
+        let pipe =
+            WplEvaluator::from_code(r#"rule grpc { |decode/proto/wptest.LogEntry|(json) }"#)?;
+        let (tdc, _) = pipe.proc(RawData::Bytes(Bytes::from(test_entry())), 0)?;
+        assert_eq!(
+            tdc.field("host"),
+            Some(&DataField::from_chars("host", "web-01"))
+        );
+        assert_eq!(tdc.field("code"), Some(&DataField::from_digit("code", 500)));
+        assert_eq!(
+            tdc.field("src/ip"),
+            Some(&DataField::from_chars("src/ip", "10.0.0.1"))
+        );
+        assert!(
+            WplEvaluator::from_code(r#"rule grpc { |decode/proto/wptest.Missing|(json) }"#)
+                .is_err()
+        );
+        Ok(())
+    }
+}
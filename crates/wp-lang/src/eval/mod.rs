@@ -15,8 +15,14 @@ where
 }
 
 pub use builtins::PipeLineResult;
+pub use builtins::proto::{proto_pool, register_proto_descriptors};
+pub use runtime::explain::{ExplainStep, ParseExplain, StepKind, StepOutcome};
 pub use runtime::vm_unit::OPTIMIZE_TIMES;
 pub use runtime::vm_unit::{DataResult, WplEvaluator};
 pub use value::ParserFactory;
 pub use value::data_type::DataTypeParser;
+pub use value::parser::base::grok::{GrokLib, grok_lib, register_grok_patterns};
+pub(crate) use value::parser::base::regex::{compile_wpl_regex, take_regex_str};
+pub use value::parser::network::useragent::{UaDb, UserAgent, register_ua_db, ua_db};
+pub(crate) use value::parser::physical::time::TimeFmtConf;
 pub use wp_parse_api::{WparseError, WparseReason, WparseResult};
@@ -0,0 +1,159 @@
+use std::sync::Mutex;
+
+use wp_data_model::cache::FieldQueryCache;
+use wp_knowledge::facade as kdb;
+use wp_knowledge::mem::{SqlNamedParam, ToSqlParams};
+use wp_model_core::model::{DataField, DataType};
+
+use crate::ast::EnrichConf;
+
+/// 每个字段的查询缓存容量
+pub const ENRICH_CACHE_SIZE: usize = 1024;
+
+const KEY_PARAM: &str = ":key";
+
+/// 字段解析完成后，按字段值查询 KnowDB 字典表并追加 `adds` 列。
+/// 每个实例持有独立 LRU 缓存；clone 时重建空缓存。
+pub struct FieldEnricher {
+    conf: EnrichConf,
+    sql: String,
+    cache: Mutex<FieldQueryCache>,
+}
+
+impl FieldEnricher {
+    pub fn new(conf: EnrichConf) -> Self {
+        let cols = conf
+            .adds
+            .iter()
+            .map(|(col, alias)| format!("{} AS {}", col, alias))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {} = {} LIMIT 1",
+            cols, conf.dict, conf.key, KEY_PARAM
+        );
+        Self {
+            conf,
+            sql,
+            cache: Mutex::new(FieldQueryCache::with_capacity(ENRICH_CACHE_SIZE)),
+        }
+    }
+
+    pub fn conf(&self) -> &EnrichConf {
+        &self.conf
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// 以 `key` 字段的值查询，命中时将结果列追加到 `out`；未命中或查询失败不追加
+    pub fn enrich(&self, key: &DataField, out: &mut Vec<DataField>) {
+        // 缓存键只保留值，避免同值不同 meta 造成缓存碎片
+        let c_params = [DataField::new(
+            DataType::default(),
+            KEY_PARAM,
+            key.get_value().clone(),
+        )];
+        let q_params = [SqlNamedParam(c_params[0].clone())];
+        let rows = match self.cache.lock() {
+            Ok(mut cache) => {
+                kdb::cache_query(&self.sql, &c_params, &q_params.to_params(), &mut *cache)
+            }
+            Err(_) => return,
+        };
+        out.extend(rows);
+    }
+}
+
+impl Clone for FieldEnricher {
+    fn clone(&self) -> Self {
+        Self::new(self.conf.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use once_cell::sync::OnceCell;
+    use orion_error::TestAssert;
+    use wp_knowledge::mem::memdb::MemDB;
+    use wp_model_core::model::Value;
+
+    use super::*;
+    use crate::WplEvaluator;
+    use crate::types::AnyResult;
+
+    fn ensure_provider() {
+        static INIT: OnceCell<()> = OnceCell::new();
+        INIT.get_or_init(|| {
+            let db = MemDB::global();
+            db.table_create(
+                "CREATE TABLE IF NOT EXISTS wpl_asset (ip TEXT PRIMARY KEY, owner TEXT, zone TEXT)",
+            )
+            .assert();
+            db.execute(
+                "INSERT OR REPLACE INTO wpl_asset (ip, owner, zone) VALUES ('10.0.0.1', 'ops', 'dmz')",
+            )
+            .assert();
+            let _ = kdb::init_mem_provider(db);
+        });
+    }
+
+    fn asset_conf() -> EnrichConf {
+        EnrichConf {
+            key: "ip".to_string(),
+            dict: "wpl_asset".to_string(),
+            adds: vec![
+                ("owner".to_string(), "asset_owner".to_string()),
+                ("zone".to_string(), "zone".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_enrich_sql() {
+        let enricher = FieldEnricher::new(asset_conf());
+        assert_eq!(
+            enricher.sql(),
+            "SELECT owner AS asset_owner, zone AS zone FROM wpl_asset WHERE ip = :key LIMIT 1"
+        );
+    }
+
+    #[test]
+    fn test_enrich_hit_and_miss() {
+        ensure_provider();
+        let enricher = FieldEnricher::new(asset_conf());
+
+        let mut out = Vec::new();
+        enricher.enrich(&DataField::from_chars("src_ip", "10.0.0.1"), &mut out);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].get_name(), "asset_owner");
+        assert_eq!(out[0].get_value(), &Value::from("ops"));
+        assert_eq!(out[1].get_name(), "zone");
+        assert_eq!(out[1].get_value(), &Value::from("dmz"));
+
+        // 二次查询走缓存，结果一致
+        let mut cached = Vec::new();
+        enricher.enrich(&DataField::from_chars("src_ip", "10.0.0.1"), &mut cached);
+        assert_eq!(cached, out);
+
+        let mut miss = Vec::new();
+        enricher.enrich(&DataField::from_chars("src_ip", "10.0.0.9"), &mut miss);
+        assert!(miss.is_empty());
+    }
+
+    #[test]
+    fn test_enrich_in_rule() -> AnyResult<()> {
+        ensure_provider();
+        let ppl = WplEvaluator::from_code(
+            "rule x { (ip:src_ip | enrich(wpl_asset, ip, owner:asset_owner), chars:user) }",
+        )?;
+        let (record, _) = ppl.proc("10.0.0.1 admin", 0)?;
+        assert_eq!(record.get_value("asset_owner"), Some(&Value::from("ops")));
+        assert_eq!(record.get_value("user"), Some(&Value::from("admin")));
+
+        let (record, _) = ppl.proc("10.0.0.9 admin", 0)?;
+        assert!(record.get_value("asset_owner").is_none());
+        Ok(())
+    }
+}
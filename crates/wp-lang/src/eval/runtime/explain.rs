@@ -0,0 +1,278 @@
+//! 解析解释（explain）模式
+//!
+//! 开启后在当前线程记录每个组、每个字段（含 alt/opt/some_of 的分支尝试）消费的字节区间、
+//! 所用解析器与失败原因，并渲染为标注后的输入行，用于排查规则为何未命中。
+//! 未开启时各记录点仅做一次线程局部检查。
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::ops::Range;
+
+use winnow::error::{ContextError, ErrMode};
+use wp_model_core::model::DataRecord;
+
+thread_local! {
+    static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+struct Recorder {
+    base: usize,
+    len: usize,
+    depth: usize,
+    steps: Vec<ExplainStep>,
+}
+
+impl Recorder {
+    // 仅当 data 是原始输入的切片时才能换算出位置；派生数据（如 json 内部值）返回 None
+    fn offset(&self, data: &str) -> Option<usize> {
+        let ptr = data.as_ptr() as usize;
+        (ptr >= self.base && ptr + data.len() <= self.base + self.len).then(|| ptr - self.base)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    Group,
+    Field,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepOutcome {
+    Ok,
+    /// 可选字段解析失败，已忽略
+    Skip(String),
+    Fail(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplainStep {
+    pub depth: usize,
+    pub kind: StepKind,
+    /// 组为 `#1 alt`，字段为 `ip:sip`
+    pub label: String,
+    /// 相对输入的字节区间；在派生数据上解析时为 None
+    pub span: Option<Range<usize>>,
+    pub outcome: StepOutcome,
+}
+
+pub(crate) struct Probe {
+    slot: usize,
+    start: Option<usize>,
+}
+
+pub(crate) fn begin<F: FnOnce() -> String>(kind: StepKind, data: &str, label: F) -> Option<Probe> {
+    RECORDER.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let rec = cell.as_mut()?;
+        let start = rec.offset(data);
+        rec.steps.push(ExplainStep {
+            depth: rec.depth,
+            kind,
+            label: label(),
+            span: start.map(|x| x..x),
+            outcome: StepOutcome::Ok,
+        });
+        rec.depth += 1;
+        Some(Probe {
+            slot: rec.steps.len() - 1,
+            start,
+        })
+    })
+}
+
+pub(crate) fn finish(
+    probe: Option<Probe>,
+    data: &str,
+    err: Option<&ErrMode<ContextError>>,
+    skipped: bool,
+) {
+    let Some(probe) = probe else {
+        return;
+    };
+    RECORDER.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let Some(rec) = cell.as_mut() else {
+            return;
+        };
+        let end = rec.offset(data);
+        rec.depth -= 1;
+        let step = &mut rec.steps[probe.slot];
+        if let (Some(start), Some(end)) = (probe.start, end) {
+            step.span = Some(start..end.max(start));
+        }
+        step.outcome = match err {
+            None => StepOutcome::Ok,
+            Some(e) if skipped => StepOutcome::Skip(err_reason(e)),
+            Some(e) => StepOutcome::Fail(err_reason(e)),
+        };
+    })
+}
+
+/// 在记录开启的情况下执行 `f`，返回其结果与全部记录步骤
+pub(crate) fn record<R>(input: &str, f: impl FnOnce() -> R) -> (R, Vec<ExplainStep>) {
+    RECORDER.with(|cell| {
+        *cell.borrow_mut() = Some(Recorder {
+            base: input.as_ptr() as usize,
+            len: input.len(),
+            depth: 0,
+            steps: Vec::new(),
+        })
+    });
+    let result = f();
+    let steps = RECORDER
+        .with(|cell| cell.borrow_mut().take())
+        .map(|x| x.steps)
+        .unwrap_or_default();
+    (result, steps)
+}
+
+pub(crate) fn err_reason(err: &ErrMode<ContextError>) -> String {
+    match err {
+        ErrMode::Backtrack(e) | ErrMode::Cut(e) => {
+            let mut ctx: Vec<String> = e.context().map(|x| x.to_string()).collect();
+            ctx.dedup();
+            if ctx.is_empty() {
+                "not match".to_string()
+            } else {
+                ctx.join(" < ")
+            }
+        }
+        ErrMode::Incomplete(_) => "incomplete input".to_string(),
+    }
+}
+
+/// 单条样本的解析过程
+#[derive(Debug, Clone)]
+pub struct ParseExplain {
+    /// 预处理管道之后实际参与解析的输入
+    pub input: String,
+    pub steps: Vec<ExplainStep>,
+    /// 成功时为解析结果与剩余数据，失败时为停止位置与原因
+    pub result: Result<(DataRecord, String), (usize, String)>,
+}
+
+impl ParseExplain {
+    pub fn is_match(&self) -> bool {
+        matches!(&self.result, Ok((_, residue)) if residue.is_empty())
+    }
+
+    /// 渲染为标注视图：输入行下方逐字段标出消费区间（`^` 成功，`x` 失败/跳过），
+    /// 随后列出组与分支的尝试过程及最终结果
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{}", self.input);
+        for step in &self.steps {
+            let Some(span) = step.span.clone().filter(|_| step.kind == StepKind::Field) else {
+                continue;
+            };
+            let col = self.column(span.start);
+            let width = self.column(span.end) - col;
+            let (mark, note) = match &step.outcome {
+                StepOutcome::Ok => ('^', String::new()),
+                StepOutcome::Skip(reason) => ('x', format!(" (skip: {})", reason)),
+                StepOutcome::Fail(reason) => ('x', format!(" (fail: {})", reason)),
+            };
+            let _ = writeln!(
+                out,
+                "{}{} {}{}",
+                " ".repeat(col),
+                mark.to_string().repeat(width.max(1)),
+                step.label,
+                note
+            );
+        }
+        out.push('\n');
+        for step in &self.steps {
+            let span = step
+                .span
+                .as_ref()
+                .map(|x| format!("[{}..{}]", x.start, x.end))
+                .unwrap_or_else(|| "[derived]".to_string());
+            let outcome = match &step.outcome {
+                StepOutcome::Ok => "ok".to_string(),
+                StepOutcome::Skip(reason) => format!("skip: {}", reason),
+                StepOutcome::Fail(reason) => format!("fail: {}", reason),
+            };
+            let _ = writeln!(
+                out,
+                "{}{} {} {}",
+                "  ".repeat(step.depth),
+                step.label,
+                span,
+                outcome
+            );
+        }
+        match &self.result {
+            Ok((_, residue)) if residue.is_empty() => out.push_str("result: matched\n"),
+            Ok((_, residue)) => {
+                let _ = writeln!(out, "result: matched with residue {:?}", residue);
+            }
+            Err((pos, reason)) => {
+                let _ = writeln!(out, "result: failed at byte {}: {}", pos, reason);
+            }
+        }
+        out
+    }
+
+    // 字节偏移换算为显示列（按字符计）
+    fn column(&self, offset: usize) -> usize {
+        self.input
+            .get(..offset)
+            .map(|x| x.chars().count())
+            .unwrap_or(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WplEvaluator;
+    use crate::types::AnyResult;
+
+    #[test]
+    fn test_explain_alt_branch() -> AnyResult<()> {
+        let ppl = WplEvaluator::from_code(r#"rule x { alt(ip:sip,digit:id),(_,chars:user) }"#)?;
+        let explain = ppl.explain("2002 - admin")?;
+        assert!(explain.is_match());
+        let brief: Vec<(usize, &str, Option<Range<usize>>, bool)> = explain
+            .steps
+            .iter()
+            .map(|x| {
+                let ok = x.outcome == StepOutcome::Ok;
+                (x.depth, x.label.as_str(), x.span.clone(), ok)
+            })
+            .collect();
+        assert_eq!(
+            brief,
+            vec![
+                (0, "#1 alt", Some(0..5), true),
+                (1, "ip:sip", Some(0..4), false),
+                (1, "digit:id", Some(0..5), true),
+                (0, "#2 seq", Some(5..12), true),
+                (1, "_", Some(5..7), true),
+                (1, "chars:user", Some(7..12), true),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_fail_render() -> AnyResult<()> {
+        let ppl = WplEvaluator::from_code(r#"rule x { (ip:sip, digit:port) }"#)?;
+        let explain = ppl.explain("10.0.0.1 http")?;
+        let view = explain.render();
+        assert!(!explain.is_match());
+        assert!(matches!(explain.result, Err((pos, _)) if pos >= 8));
+        let lines: Vec<&str> = view.lines().collect();
+        assert_eq!(lines[0], "10.0.0.1 http");
+        assert_eq!(lines[1], "^^^^^^^^^ ip:sip");
+        assert_eq!(lines[2], "         x digit:port (fail: expected <digit>)");
+        assert!(view.contains("result: failed at byte"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_disabled_by_default() {
+        assert!(begin(StepKind::Field, "abc", || "x".to_string()).is_none());
+    }
+}
@@ -15,6 +15,8 @@ use wp_model_core::model::FNameStr;
 use wp_model_core::model::{DataField, DataType};
 use wp_parser::WResult as ModalResult;
 
+use super::enrich::FieldEnricher;
+use super::explain::{self, StepKind};
 use super::pipe_exec::PipeExecutor;
 use super::subunit::SubUnitManager;
 
@@ -24,6 +26,7 @@ pub struct FieldEvalUnit {
     conf: WplField,
     parser: ParserHold,
     pipe_exec: PipeExecutor,
+    enrichers: Vec<FieldEnricher>,
     sub_units: SubUnitManager,
     next: Option<Box<FieldEvalUnit>>,
     pub group_enum: WplGroupType,
@@ -33,6 +36,7 @@ impl FieldEvalUnit {
     pub fn new(index: usize, conf: WplField, parser: ParserHold, group_enum: WplGroupType) -> Self {
         Self {
             index,
+            enrichers: build_enrichers(&conf),
             conf,
             parser,
             pipe_exec: PipeExecutor::new(),
@@ -49,10 +53,15 @@ impl FieldEvalUnit {
     ) -> WplCodeResult<Self> {
         let next = if let DataType::Array(next_name) = meta.clone() {
             let next_meta = DataType::from(next_name.as_str()).unwrap_or(DataType::Auto);
+            // 富化只作用于外层字段，数组元素不重复查询
+            let elem_conf = WplField {
+                enriches: Vec::new(),
+                ..conf.clone()
+            };
             Some(Box::new(Self::create_next(
                 index,
                 next_meta,
-                conf.clone(),
+                elem_conf,
                 group_enum.clone(),
             )?))
             /*Some(Box::new(Self::create_next(
@@ -65,11 +74,12 @@ impl FieldEvalUnit {
         } else {
             None
         };
-        let parser = ParserFactory::create(&meta)?;
+        let parser = ParserFactory::create_named(&meta, &conf)?;
         let mut cur_conf = conf.clone();
         cur_conf.meta_type = meta;
         let ins = Self {
             index,
+            enrichers: build_enrichers(&cur_conf),
             conf: cur_conf,
             parser,
             pipe_exec: PipeExecutor::new(),
@@ -124,22 +134,55 @@ impl FieldEvalUnit {
         out: &mut Vec<DataField>,
     ) -> ModalResult<()> {
         let sep = self.conf.resolve_sep_ref(upper_sep);
+        let probe = explain::begin(StepKind::Field, data, || self.explain_label(&run_key));
 
+        let start = out.len();
         let data_rst = self
             .parser()
             .parse(self, sep.as_ref(), data, run_key.clone(), out);
 
         match data_rst {
-            Ok(_) => self.pipe_exec.execute(out),
-            Err(e) => {
-                if self.conf.is_opt {
-                    Ok(())
-                } else {
-                    Err(e)
+            Ok(_) => {
+                let rst = self.pipe_exec.execute(out);
+                if rst.is_ok() {
+                    self.enrich(start, out);
                 }
+                explain::finish(probe, data, rst.as_ref().err(), false);
+                rst
+            }
+            Err(e) => {
+                explain::finish(probe, data, Some(&e), self.conf.is_opt);
+                if self.conf.is_opt { Ok(()) } else { Err(e) }
+            }
+        }
+    }
+
+    // 以本字段解析出的首个值作为查询键，追加字典列
+    fn enrich(&self, start: usize, out: &mut Vec<DataField>) {
+        if self.enrichers.is_empty() {
+            return;
+        }
+        if let Some(key) = out.get(start).cloned() {
+            for enricher in &self.enrichers {
+                enricher.enrich(&key, out);
             }
         }
     }
+
+    fn explain_label(&self, run_key: &Option<FNameStr>) -> String {
+        match run_key.as_ref().or(self.conf.name.as_ref()) {
+            Some(name) => format!("{}:{}", self.conf.meta_name, name),
+            None => self.conf.meta_name.to_string(),
+        }
+    }
+}
+
+fn build_enrichers(conf: &WplField) -> Vec<FieldEnricher> {
+    conf.enriches
+        .iter()
+        .cloned()
+        .map(FieldEnricher::new)
+        .collect()
 }
 
 #[cfg(test)]
@@ -8,6 +8,7 @@ use wp_parser::WResult as ModalResult;
 
 use crate::ast::WplSep;
 use crate::ast::group::{GroupSeq, WplGroupType};
+use crate::eval::runtime::explain::{self, StepKind};
 use crate::eval::runtime::field::FieldEvalUnit;
 use crate::eval::runtime::vm_unit::StopWatch;
 
@@ -97,6 +98,20 @@ impl LogicProc for GroupSeq {
 
 impl WplEvalGroup {
     pub fn proc(&self, sep: &WplSep, data: &mut &str, out: &mut Vec<DataField>) -> ModalResult<()> {
+        let probe = explain::begin(StepKind::Group, data, || {
+            format!("#{} {}", self.index, self.rule)
+        });
+        let rst = self.proc_rule(sep, data, out);
+        explain::finish(probe, data, rst.as_ref().err(), false);
+        rst
+    }
+
+    fn proc_rule(
+        &self,
+        sep: &WplSep,
+        data: &mut &str,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
         match &self.rule {
             WplGroupType::Opt(x) => trace("<opt><group>", move |data: &mut &str| {
                 x.process(self, sep, data, out)
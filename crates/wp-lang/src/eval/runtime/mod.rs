@@ -1,3 +1,5 @@
+pub mod enrich;
+pub mod explain;
 pub mod field;
 pub mod field_pipe;
 pub mod group;
@@ -1,11 +1,13 @@
+use crate::ast::WplExpress;
 use crate::ast::WplPipe;
 use crate::ast::group::{WplGroup, WplGroupType};
-use crate::ast::{WplExpress, WplStatementType};
 use crate::ast::{WplField, WplSep};
 use crate::eval::builtins::{self, PipeLineResult, raw_to_utf8_string};
+use crate::eval::runtime::explain::{self, ParseExplain};
 use crate::eval::runtime::field::FieldEvalUnit;
 use crate::eval::runtime::field_pipe::PipeEnum;
 use crate::eval::runtime::group::WplEvalGroup;
+use smol_str::SmolStr;
 use std::borrow::Cow;
 use wp_parse_api::{PipeHold, RawData, WparseError, WparseReason};
 
@@ -15,7 +17,7 @@ use anyhow::Result;
 use orion_error::{ErrorWith, ToStructError, UvsDataFrom};
 use orion_overload::new::New3;
 use wp_log::{debug_data, trace_data};
-use wp_model_core::model::DataRecord;
+use wp_model_core::model::{DataRecord, DataType};
 use wp_parser::Parser;
 use wp_parser::WResult as ModalResult;
 
@@ -56,6 +58,7 @@ impl IntoRawData for &str {
 pub struct WplEvaluator {
     preorder: Vec<PipeHold>,
     group_units: Vec<WplEvalGroup>,
+    prefix: Option<SmolStr>,
 }
 unsafe impl Send for WplEvaluator {}
 
@@ -122,6 +125,30 @@ impl WplEvaluator {
             }
         }
     }
+
+    /// 解析单条样本并记录每个组/字段消费的字节区间、所选解析器与失败原因
+    pub fn explain<D>(&self, data: D) -> Result<ParseExplain, WparseError>
+    where
+        D: IntoRawData,
+    {
+        let mut working_raw: RawData = data.into_raw();
+        if !self.preorder.is_empty() {
+            working_raw = self.pipe_proc(working_raw)?;
+        }
+        let input = raw_to_utf8_string(&working_raw);
+        let mut data: &str = input.as_str();
+        let (rst, steps) = explain::record(&input, || self.parse_groups(&mut data));
+        let result = match rst {
+            Ok(log) => Ok((log, data.to_string())),
+            Err(e) => Err((input.len() - data.len(), explain::err_reason(&e))),
+        };
+        Ok(ParseExplain {
+            input,
+            steps,
+            result,
+        })
+    }
+
     pub fn from_code(code: &str) -> Result<Self, WplCodeError> {
         let mut cur_code = code;
         let rule = wpl_rule.parse_next(&mut cur_code).map_err(
@@ -131,8 +158,13 @@ impl WplEvaluator {
                     .with_detail(err.to_string())
             }, //ParseCodeError::new(err.to_string())
         )?;
-        let WplStatementType::Express(rule_define) = rule.statement;
-        Self::from(&rule_define, None)
+        let Some(rule_define) = rule.statement.express() else {
+            return Err(WplCodeError::from(WplCodeReason::UnSupport(format!(
+                "'{}' is not a rule",
+                rule.name
+            ))));
+        };
+        Self::from(rule_define, None)
     }
     pub fn from(dy_lang: &WplExpress, inject: Option<&WplExpress>) -> Result<Self, WplCodeError> {
         let mut target_dpl = WplEvaluator {
@@ -142,16 +174,61 @@ impl WplEvaluator {
             Self::assemble_ins(inject, &mut target_dpl)?;
         }
         Self::assemble_ins(dy_lang, &mut target_dpl)?;
+        target_dpl.prefix = Self::derive_prefix(inject.into_iter().chain(Some(dy_lang)));
         Ok(target_dpl)
     }
 
+    /// 输入（去除前导空白后）必须具备的字面量前缀，供规则分发索引使用；
+    /// 无法确定时返回 None，调用方需回退为逐条尝试
+    pub fn dispatch_prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    fn derive_prefix<'a>(express: impl Iterator<Item = &'a WplExpress>) -> Option<SmolStr> {
+        for express in express {
+            // 预处理管道会改写输入，原始数据上无法推导前缀
+            if !express.pipe_process.is_empty() {
+                return None;
+            }
+            if let Some(group) = express.group.first() {
+                return Self::group_prefix(group);
+            }
+        }
+        None
+    }
+
+    fn group_prefix(group: &WplGroup) -> Option<SmolStr> {
+        if !matches!(group.meta, WplGroupType::Seq(_)) || group.refer.is_some() {
+            return None;
+        }
+        let first = group.fields.first()?;
+        let literal = matches!(first.meta_type, DataType::Symbol | DataType::PeekSymbol)
+            && !first.is_opt
+            && !first.continuous
+            && first.length.is_none()
+            && first.fmt_conf.scope_beg.is_none();
+        if !literal {
+            return None;
+        }
+        first
+            .content
+            .as_deref()
+            .filter(|c| !c.is_empty())
+            .map(SmolStr::from)
+    }
+
     fn assemble_ins(
         express: &WplExpress,
         target_dpl: &mut WplEvaluator,
     ) -> Result<(), WplCodeError> {
         builtins::ensure_builtin_pipe_units();
         for proc in &express.pipe_process {
-            if let Some(pipe_unit) = builtins::registry::create_pipe_unit(proc) {
+            // decode/proto/<message> 按描述符集中的消息创建，其余按名称查注册表
+            let pipe_unit = match builtins::proto::create_proto_unit(proc)? {
+                Some(unit) => Some(unit),
+                None => builtins::registry::create_pipe_unit(proc),
+            };
+            if let Some(pipe_unit) = pipe_unit {
                 target_dpl.preorder.push(pipe_unit);
             } else {
                 return Err(WplCodeError::from(WplCodeReason::UnSupport(format!(
@@ -167,6 +244,12 @@ impl WplEvaluator {
         Ok(())
     }
     fn assemble_group(index: usize, group: &WplGroup) -> Result<WplEvalGroup, WplCodeError> {
+        if let Some(name) = &group.refer {
+            return Err(WplCodeError::from(WplCodeReason::Reference(format!(
+                "fields '@{}' not resolved",
+                name
+            ))));
+        }
         let mut p_group =
             WplEvalGroup::new(index, group.meta.clone(), group.base_group_sep.clone());
         for (idx, conf) in group.fields.iter().enumerate() {
@@ -620,4 +703,20 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_dispatch_prefix() -> AnyResult<()> {
+        let pipe = WplEvaluator::from_code("rule x { (symbol(<134>), time, chars:host) }")?;
+        assert_eq!(pipe.dispatch_prefix(), Some("<134>"));
+        let pipe = WplEvaluator::from_code("rule x { (peek_symbol(CEF:), chars) }")?;
+        assert_eq!(pipe.dispatch_prefix(), Some("CEF:"));
+        // 首字段不是字面量、首组非顺序组、带预处理管道均无法推导
+        let pipe = WplEvaluator::from_code("rule x { (ip, symbol(GET)) }")?;
+        assert_eq!(pipe.dispatch_prefix(), None);
+        let pipe = WplEvaluator::from_code("rule x { alt(symbol(a), symbol(b)) }")?;
+        assert_eq!(pipe.dispatch_prefix(), None);
+        let pipe = WplEvaluator::from_code("rule x { |decode/base64|(symbol(abc)) }")?;
+        assert_eq!(pipe.dispatch_prefix(), None);
+        Ok(())
+    }
 }
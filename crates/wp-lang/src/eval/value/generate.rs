@@ -12,7 +12,7 @@ mod test {
     use crate::generator::FieldGenConf;
     use wp_model_core::model::DataType;
 
-    use crate::ast::{WplRule, WplSep, WplStatementType};
+    use crate::ast::{WplRule, WplSep};
     use crate::generator::{FmtFieldVec, GenChannel};
 
     fn gen_one_line(
@@ -21,7 +21,9 @@ mod test {
         rules: &HashMap<String, FieldGenConf>,
     ) -> AnyResult<FmtFieldVec> {
         let mut fieldset = FmtFieldVec::new();
-        let WplStatementType::Express(rule) = &log_line.statement;
+        let Some(rule) = log_line.statement.express() else {
+            return Ok(fieldset);
+        };
         for group in &rule.group {
             for field in &group.fields {
                 let rule = field.name.clone().and_then(|name| rules.get(name.as_str()));
@@ -0,0 +1,170 @@
+use super::super::prelude::*;
+use wp_model_core::model::FNameStr;
+
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::take_regex_str;
+use crate::eval::value::parse_def::PatternParser;
+use crate::eval::value::parser::protocol::take_sub_value;
+
+pub mod patterns;
+
+pub use patterns::{GrokLib, GrokRegex, grok_lib, register_grok_patterns};
+
+/// grok 字段：`grok("%{IP:client} %{WORD:method} %{INT:code:int}")`
+/// 模式引用在加载时展开为正则，`:int`/`:float` 决定输出类型
+pub struct GrokP {
+    grok: GrokRegex,
+}
+
+impl GrokP {
+    pub fn new(grok: GrokRegex) -> Self {
+        Self { grok }
+    }
+}
+
+/// 使用全局模式库编译字段内容中的 grok 表达式
+pub fn compile_wpl_grok(content: &str) -> Result<GrokRegex, String> {
+    let expr = take_grok_expr(content)?;
+    grok_lib().compile(expr.as_str())
+}
+
+/// 读取 grok 表达式字面量，写法同 regex：`"..."`、`r#"..."#`
+fn take_grok_expr(content: &str) -> Result<String, String> {
+    let mut data = content.trim();
+    let expr = take_regex_str
+        .parse_next(&mut data)
+        .map_err(|_| format!("bad grok literal: {}", content))?;
+    if !data.trim().is_empty() {
+        return Err(format!("bad grok literal: {}", content));
+    }
+    Ok(expr)
+}
+
+impl PatternParser for GrokP {
+    fn pattern_parse(
+        &self,
+        fpu: &FieldEvalUnit,
+        _ups_sep: &WplSep,
+        data: &mut &str,
+        _name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
+        let Some(caps) = self.grok.re.captures(data) else {
+            return fail.context(ctx_desc("grok not match")).parse_next(data);
+        };
+        for cap in &self.grok.captures {
+            if let Some(m) = caps.name(cap.group.as_str()) {
+                take_sub_value(fpu, cap.name.as_str(), m.as_str(), &cap.meta, out)?;
+            }
+        }
+        let end = caps.get(0).map(|m| m.end()).unwrap_or_default();
+        *data = &data[end..];
+        Ok(())
+    }
+
+    fn patten_gen(
+        &self,
+        _gen: &mut GenChannel,
+        _f_conf: &WplField,
+        _g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        // 模式字段无法反推样本，交由生成端报告并跳过
+        Err(anyhow::anyhow!(
+            "grok field does not support sample generation"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use orion_error::TestAssert;
+    use std::net::{IpAddr, Ipv4Addr};
+    use wp_model_core::model::DataRecord;
+
+    #[test]
+    fn test_grok_parse() {
+        let mut data = "10.0.0.8 GET /index.html 200 0.043 rest";
+        let conf = WplField::try_parse(
+            r#"grok("%{IP:client} %{WORD:method} %{URIPATHPARAM:path} %{INT:code:int} %{NUMBER:cost:float}")"#,
+        )
+        .assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "rest");
+        assert_eq!(
+            out,
+            vec![
+                DataField::from_chars("client", "10.0.0.8"),
+                DataField::from_chars("method", "GET"),
+                DataField::from_chars("path", "/index.html"),
+                DataField::from_digit("code", 200),
+                DataField::from_float("cost", 0.043),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grok_apache_sub_fields() -> AnyResult<()> {
+        let rule =
+            r#"rule apache { (grok("%{COMBINEDAPACHELOG}")(ip@clientip, time/clf@timestamp:ts)) }"#;
+        let data = r#"192.168.1.20 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08""#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(data, 0)?;
+        assert_eq!(left, "");
+        assert_eq!(
+            tdc.field("clientip"),
+            Some(&DataField::from_ip(
+                "clientip",
+                IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20))
+            ))
+        );
+        assert!(tdc.field("ts").is_some());
+        assert_eq!(
+            tdc.field("response"),
+            Some(&DataField::from_digit("response", 200))
+        );
+        assert_eq!(
+            tdc.field("agent"),
+            Some(&DataField::from_chars("agent", "\"Mozilla/4.08\""))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_grok_gen_unsupported() {
+        let conf = WplField::try_parse(r#"grok("%{INT:code}")"#).assert();
+        ParserTUnit::from_auto(conf).verify_gen_unsupported();
+    }
+
+    #[test]
+    fn test_grok_registered_patterns() {
+        register_grok_patterns(
+            "test",
+            "WPTEST_KV %{WORD:wptest_key}=%{WPTEST_VAL:wptest_val:int}\nWPTEST_VAL \\d+",
+        )
+        .assert();
+        let mut data = "count=42";
+        let conf = WplField::try_parse(r#"grok("%{WPTEST_KV}")"#).assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("wptest_val"),
+            Some(&DataField::from_digit("wptest_val", 42))
+        );
+    }
+
+    #[test]
+    fn test_grok_fail() {
+        let conf = WplField::try_parse(r#"grok("%{INT:n:int}")"#).assert();
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut "abc");
+        assert!(WplField::try_parse("grok").is_err());
+        assert!(compile_wpl_grok(r#""%{NO_SUCH_PATTERN:x}""#).is_err());
+        assert!(WplEvaluator::from_code(r#"rule x { (grok("%{NO_SUCH_PATTERN:x}")) }"#).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard};
+use wp_model_core::model::{DataType, FNameStr};
+
+// 内置模式（与模式目录同格式：每行 `NAME regex`），兼容 regex crate，无环视/原子组
+const BUILTIN_PATTERNS: &str = r##"
+USERNAME [a-zA-Z0-9._-]+
+USER %{USERNAME}
+EMAILLOCALPART [a-zA-Z0-9!#$%&'*+\-/=?^_`{|}~]+(?:\.[a-zA-Z0-9!#$%&'*+\-/=?^_`{|}~]+)*
+EMAILADDRESS %{EMAILLOCALPART}@%{HOSTNAME}
+INT [+-]?[0-9]+
+BASE10NUM [+-]?(?:[0-9]+(?:\.[0-9]*)?|\.[0-9]+)
+NUMBER %{BASE10NUM}
+BASE16NUM [+-]?(?:0[xX])?[0-9A-Fa-f]+
+POSINT [1-9][0-9]*
+NONNEGINT [0-9]+
+WORD \b\w+\b
+NOTSPACE \S+
+SPACE \s*
+DATA .*?
+GREEDYDATA .*
+QUOTEDSTRING "(?:[^"\\]|\\.)*"|'(?:[^'\\]|\\.)*'
+QS %{QUOTEDSTRING}
+UUID [A-Fa-f0-9]{8}-(?:[A-Fa-f0-9]{4}-){3}[A-Fa-f0-9]{12}
+CISCOMAC (?:[A-Fa-f0-9]{4}\.){2}[A-Fa-f0-9]{4}
+WINDOWSMAC (?:[A-Fa-f0-9]{2}-){5}[A-Fa-f0-9]{2}
+COMMONMAC (?:[A-Fa-f0-9]{2}:){5}[A-Fa-f0-9]{2}
+MAC %{CISCOMAC}|%{WINDOWSMAC}|%{COMMONMAC}
+IPV4 (?:(?:25[0-5]|2[0-4][0-9]|[01]?[0-9]{1,2})\.){3}(?:25[0-5]|2[0-4][0-9]|[01]?[0-9]{1,2})\b
+IPV6 [0-9A-Fa-f]{0,4}(?::[0-9A-Fa-f]{0,4}){2,7}(?:%[0-9A-Za-z]+)?
+IP %{IPV6}|%{IPV4}
+HOSTNAME \b[0-9A-Za-z][0-9A-Za-z-]{0,62}(?:\.[0-9A-Za-z][0-9A-Za-z-]{0,62})*\.?
+IPORHOST %{IP}|%{HOSTNAME}
+HOSTPORT %{IPORHOST}:%{POSINT}
+UNIXPATH (?:/[\w%!$@:.,+~-]*)+
+WINPATH (?:[A-Za-z]+:|\\)(?:\\[^\\?*]*)+
+PATH %{UNIXPATH}|%{WINPATH}
+URIPROTO [A-Za-z][A-Za-z0-9+\-.]*
+URIHOST %{IPORHOST}(?::%{POSINT})?
+URIPATH (?:/[A-Za-z0-9$.+!*'(){},~:;=@#%&_\-]*)+
+URIPARAM \?[A-Za-z0-9$.+!*'|(){},~@#%&/=:;_?\-\[\]<>]*
+URIPATHPARAM %{URIPATH}(?:%{URIPARAM})?
+URI %{URIPROTO}://(?:%{USER}(?::[^@]*)?@)?(?:%{URIHOST})?(?:%{URIPATHPARAM})?
+MONTH \b(?:Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|June?|July?|Aug(?:ust)?|Sep(?:tember)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)\b
+MONTHNUM 1[0-2]|0?[1-9]
+MONTHDAY 3[01]|[12][0-9]|0?[1-9]
+DAY Mon(?:day)?|Tue(?:sday)?|Wed(?:nesday)?|Thu(?:rsday)?|Fri(?:day)?|Sat(?:urday)?|Sun(?:day)?
+YEAR (?:\d\d){1,2}
+HOUR 2[0123]|[01]?[0-9]
+MINUTE [0-5][0-9]
+SECOND (?:[0-5]?[0-9]|60)(?:[:.,][0-9]+)?
+TIME %{HOUR}:%{MINUTE}(?::%{SECOND})?
+DATE_US %{MONTHNUM}[/-]%{MONTHDAY}[/-]%{YEAR}
+DATE_EU %{MONTHDAY}[./-]%{MONTHNUM}[./-]%{YEAR}
+DATE %{DATE_US}|%{DATE_EU}
+DATESTAMP %{DATE}[- ]%{TIME}
+ISO8601_TIMEZONE Z|[+-]%{HOUR}(?::?%{MINUTE})
+TIMESTAMP_ISO8601 %{YEAR}-%{MONTHNUM}-%{MONTHDAY}[T ]%{HOUR}:?%{MINUTE}(?::?%{SECOND})?(?:%{ISO8601_TIMEZONE})?
+HTTPDATE %{MONTHDAY}/%{MONTH}/%{YEAR}:%{TIME} %{INT}
+SYSLOGTIMESTAMP %{MONTH} +%{MONTHDAY} %{TIME}
+PROG [\x21-\x5a\x5c\x5e-\x7e]+
+SYSLOGPROG %{PROG:program}(?:\[%{POSINT:pid:int}\])?
+SYSLOGHOST %{IPORHOST}
+LOGLEVEL [Aa]lert|ALERT|[Tt]race|TRACE|[Dd]ebug|DEBUG|[Nn]otice|NOTICE|[Ii]nfo|INFO|[Ww]arn(?:ing)?|WARN(?:ING)?|[Ee]rr(?:or)?|ERR(?:OR)?|[Cc]rit(?:ical)?|CRIT(?:ICAL)?|[Ff]atal|FATAL|[Ss]evere|SEVERE|[Ee]merg(?:ency)?|EMERG(?:ENCY)?
+HTTPDUSER %{EMAILADDRESS}|%{USER}
+COMMONAPACHELOG %{IPORHOST:clientip} %{HTTPDUSER:ident} %{USER:auth} \[%{HTTPDATE:timestamp}\] "(?:%{WORD:verb} %{NOTSPACE:request}(?: HTTP/%{NUMBER:httpversion})?|%{DATA:rawrequest})" %{NUMBER:response:int} (?:%{NUMBER:bytes:int}|-)
+COMBINEDAPACHELOG %{COMMONAPACHELOG} %{QS:referrer} %{QS:agent}
+"##;
+
+// 模式引用：%{NAME}、%{NAME:field}、%{NAME:field:int|float|string}
+static GROK_REF: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"%\{(\w+)(?::([\w.@/\[\]-]+))?(?::(\w+))?\}").expect("grok ref regex")
+});
+
+// 模式展开的最大嵌套深度
+const GROK_MAX_DEPTH: usize = 64;
+
+static GROK_LIB: Lazy<RwLock<GrokLib>> = Lazy::new(|| RwLock::new(GrokLib::default()));
+
+/// 全局模式库：内置模式 + `[models].grok` 目录加载的模式
+pub fn grok_lib() -> RwLockReadGuard<'static, GrokLib> {
+    GROK_LIB.read().expect("grok pattern lib poisoned")
+}
+
+/// 注册一段模式定义 (`NAME regex` 每行一条)，同名模式覆盖已有定义
+pub fn register_grok_patterns(src: &str, text: &str) -> Result<usize, String> {
+    let defs = GrokLib::parse_defs(src, text)?;
+    let cnt = defs.len();
+    let mut lib = GROK_LIB.write().expect("grok pattern lib poisoned");
+    lib.patterns.extend(defs);
+    Ok(cnt)
+}
+
+/// grok 捕获：regex 内部分组名 -> 输出字段名与类型
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrokCapture {
+    pub group: String,
+    pub name: FNameStr,
+    pub meta: DataType,
+}
+
+/// 展开后的 grok 表达式
+#[derive(Debug, Clone)]
+pub struct GrokRegex {
+    pub re: Regex,
+    pub captures: Vec<GrokCapture>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GrokLib {
+    patterns: HashMap<String, String>,
+}
+
+impl Default for GrokLib {
+    fn default() -> Self {
+        let patterns = Self::parse_defs("builtin", BUILTIN_PATTERNS)
+            .expect("builtin grok patterns")
+            .into_iter()
+            .collect();
+        Self { patterns }
+    }
+}
+
+impl GrokLib {
+    fn parse_defs(src: &str, text: &str) -> Result<Vec<(String, String)>, String> {
+        let mut defs = Vec::new();
+        for (no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name, pattern)) = line.split_once(char::is_whitespace) else {
+                return Err(format!("{}:{} grok pattern without regex", src, no + 1));
+            };
+            if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!(
+                    "{}:{} bad grok pattern name: {}",
+                    src,
+                    no + 1,
+                    name
+                ));
+            }
+            defs.push((name.to_string(), pattern.trim_start().to_string()));
+        }
+        Ok(defs)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.patterns.contains_key(name)
+    }
+
+    /// 表达式（含嵌套引用）中未定义的模式名，按出现顺序去重
+    pub fn unknown_patterns(&self, expr: &str) -> Vec<String> {
+        let mut unknown = Vec::new();
+        let mut visited = Vec::new();
+        self.collect_unknown(expr, &mut visited, &mut unknown);
+        unknown
+    }
+
+    fn collect_unknown(&self, expr: &str, visited: &mut Vec<String>, unknown: &mut Vec<String>) {
+        for caps in GROK_REF.captures_iter(expr) {
+            let name = &caps[1];
+            match self.patterns.get(name) {
+                Some(pattern) => {
+                    if !visited.iter().any(|x| x == name) {
+                        visited.push(name.to_string());
+                        self.collect_unknown(pattern, visited, unknown);
+                    }
+                }
+                None => {
+                    if !unknown.iter().any(|x| x == name) {
+                        unknown.push(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    /// 展开模式引用并编译，匹配锚定在当前位置
+    pub fn compile(&self, expr: &str) -> Result<GrokRegex, String> {
+        let mut captures = Vec::new();
+        let mut stack = Vec::new();
+        let pattern = self.expand(expr, &mut stack, &mut captures)?;
+        let re = Regex::new(format!("^(?:{})", pattern).as_str())
+            .map_err(|e| format!("bad grok pattern {}: {}", expr, e))?;
+        // 内联的 (?P<name>...) 分组按 chars 输出
+        for name in re.capture_names().flatten() {
+            if !captures.iter().any(|x| x.group == name) {
+                captures.push(GrokCapture {
+                    group: name.to_string(),
+                    name: FNameStr::from(name),
+                    meta: DataType::Chars,
+                });
+            }
+        }
+        Ok(GrokRegex { re, captures })
+    }
+
+    fn expand(
+        &self,
+        expr: &str,
+        stack: &mut Vec<String>,
+        captures: &mut Vec<GrokCapture>,
+    ) -> Result<String, String> {
+        if stack.len() > GROK_MAX_DEPTH {
+            return Err(format!(
+                "grok pattern nested too deep: {}",
+                stack.join(" -> ")
+            ));
+        }
+        let mut buf = String::with_capacity(expr.len());
+        let mut last = 0;
+        for caps in GROK_REF.captures_iter(expr) {
+            let all = caps.get(0).expect("grok ref");
+            buf.push_str(&expr[last..all.start()]);
+            last = all.end();
+            let name = &caps[1];
+            let Some(pattern) = self.patterns.get(name) else {
+                return Err(format!("unknown grok pattern: {}", name));
+            };
+            if stack.iter().any(|x| x == name) {
+                return Err(format!(
+                    "grok pattern cycle: {} -> {}",
+                    stack.join(" -> "),
+                    name
+                ));
+            }
+            stack.push(name.to_string());
+            let inner = self.expand(pattern, stack, captures)?;
+            stack.pop();
+            match caps.get(2) {
+                Some(field) => {
+                    let meta = grok_meta(caps.get(3).map(|x| x.as_str()))?;
+                    let group = format!("__grok{}", captures.len());
+                    buf.push_str(format!("(?P<{}>{})", group, inner).as_str());
+                    captures.push(GrokCapture {
+                        group,
+                        name: FNameStr::from(field.as_str()),
+                        meta,
+                    });
+                }
+                None => {
+                    buf.push_str(format!("(?:{})", inner).as_str());
+                }
+            }
+        }
+        buf.push_str(&expr[last..]);
+        Ok(buf)
+    }
+}
+
+// grok 类型后缀到 DataType
+fn grok_meta(kind: Option<&str>) -> Result<DataType, String> {
+    match kind {
+        None | Some("string") => Ok(DataType::Chars),
+        Some("int") | Some("long") => Ok(DataType::Digit),
+        Some("float") | Some("double") => Ok(DataType::Float),
+        Some(x) => Err(format!("unsupported grok type: {}", x)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lib_with(text: &str) -> GrokLib {
+        let mut lib = GrokLib::default();
+        lib.patterns
+            .extend(GrokLib::parse_defs("test", text).expect("defs"));
+        lib
+    }
+
+    #[test]
+    fn test_grok_builtin_compile() {
+        let lib = GrokLib::default();
+        for name in lib.patterns.keys() {
+            let expr = format!("%{{{}}}", name);
+            assert!(lib.compile(expr.as_str()).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_grok_nested_and_types() {
+        let lib = lib_with(
+            "PAIR %{WORD:key}=%{INT:val:int}\n# comment\n\nLINE %{PAIR} %{NUMBER:cost:float}",
+        );
+        let grok = lib.compile("%{LINE}").expect("compile");
+        assert_eq!(
+            grok.captures
+                .iter()
+                .map(|x| (x.name.as_str(), x.meta.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("key", DataType::Chars),
+                ("val", DataType::Digit),
+                ("cost", DataType::Float),
+            ]
+        );
+        let caps = grok.re.captures("a=12 0.5").expect("match");
+        assert_eq!(&caps[grok.captures[1].group.as_str()], "12");
+        assert_eq!(&caps[grok.captures[2].group.as_str()], "0.5");
+    }
+
+    #[test]
+    fn test_grok_errors() {
+        let lib = lib_with("A x%{B}\nB y%{A}\nC %{NOPE} %{A} %{NOPE2} %{NOPE}");
+        let err = lib.compile("%{A}").expect_err("cycle");
+        assert!(err.contains("cycle"), "{}", err);
+        let err = lib.compile("%{MISSING}").expect_err("unknown");
+        assert!(err.contains("unknown grok pattern: MISSING"), "{}", err);
+        assert!(lib.compile("%{INT:x:bool}").is_err());
+        assert_eq!(lib.unknown_patterns("%{C} %{A}"), vec!["NOPE", "NOPE2"]);
+        assert!(lib.unknown_patterns("%{IP} %{C}x").len() == 2);
+        assert!(GrokLib::parse_defs("bad", "ONLYNAME").is_err());
+        assert!(GrokLib::parse_defs("bad", "BAD-NAME x").is_err());
+    }
+}
@@ -11,8 +11,10 @@ use wp_model_core::model::DigitValue;
 mod bool;
 mod chars;
 pub mod digit;
+pub mod grok;
 pub mod hex;
 mod ignore;
+pub mod regex;
 mod symbol;
 
 #[derive(Eq, PartialEq, Debug, Default)]
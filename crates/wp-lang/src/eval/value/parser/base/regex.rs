@@ -0,0 +1,184 @@
+use super::super::prelude::*;
+use regex::Regex;
+use winnow::token::take_till;
+use wp_model_core::model::FNameStr;
+
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parse_def::PatternParser;
+use crate::eval::value::parser::protocol::take_sub_value;
+use crate::parser::utils::quot_r_str;
+
+/// 正则字段：`regex(r#"(?P<ip>\S+) (?P<code>\d+)"#)`，每个命名分组输出为一个子字段
+pub struct RegexP {
+    re: Regex,
+    names: Vec<String>,
+}
+
+impl RegexP {
+    pub fn new(re: Regex) -> Self {
+        let names = re.capture_names().flatten().map(String::from).collect();
+        Self { re, names }
+    }
+}
+
+/// 读取正则字面量：`r#"..."#`、`r"..."` 原样保留；`"..."` 仅处理 `\"`
+pub fn take_regex_str(data: &mut &str) -> ModalResult<String> {
+    if data.starts_with('r') {
+        return quot_r_str.map(String::from).parse_next(data);
+    }
+    literal('"')
+        .context(ctx_desc("regex <beg>\""))
+        .parse_next(data)?;
+    let mut buf = String::new();
+    loop {
+        let part = take_till(0.., |c| c == '"' || c == '\\').parse_next(data)?;
+        buf.push_str(part);
+        if data.starts_with("\\\"") {
+            buf.push('"');
+            *data = &data[2..];
+        } else if data.starts_with('\\') {
+            buf.push('\\');
+            *data = &data[1..];
+            if let Some(c) = data.chars().next() {
+                buf.push(c);
+                *data = &data[c.len_utf8()..];
+            }
+        } else {
+            break;
+        }
+    }
+    literal('"')
+        .context(ctx_desc("regex <end>\""))
+        .parse_next(data)?;
+    Ok(buf)
+}
+
+/// 编译字段内容中的正则，匹配锚定在当前位置
+pub fn compile_wpl_regex(content: &str) -> Result<Regex, String> {
+    let mut data = content.trim();
+    let pattern = take_regex_str
+        .parse_next(&mut data)
+        .map_err(|_| format!("bad regex literal: {}", content))?;
+    if !data.trim().is_empty() {
+        return Err(format!("bad regex literal: {}", content));
+    }
+    Regex::new(format!("^(?:{})", pattern).as_str())
+        .map_err(|e| format!("bad regex pattern {}: {}", pattern, e))
+}
+
+impl PatternParser for RegexP {
+    fn pattern_parse(
+        &self,
+        fpu: &FieldEvalUnit,
+        _ups_sep: &WplSep,
+        data: &mut &str,
+        _name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
+        let Some(caps) = self.re.captures(data) else {
+            return fail.context(ctx_desc("regex not match")).parse_next(data);
+        };
+        for name in &self.names {
+            if let Some(m) = caps.name(name) {
+                take_sub_value(fpu, name, m.as_str(), &DataType::Chars, out)?;
+            }
+        }
+        let end = caps.get(0).map(|m| m.end()).unwrap_or_default();
+        *data = &data[end..];
+        Ok(())
+    }
+
+    fn patten_gen(
+        &self,
+        _gen: &mut GenChannel,
+        _f_conf: &WplField,
+        _g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        // 模式字段无法反推样本，交由生成端报告并跳过
+        Err(anyhow::anyhow!(
+            "regex field does not support sample generation"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WplCode;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use orion_error::TestAssert;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn test_regex_gen_unsupported() {
+        let conf = WplField::try_parse(r#"regex("(?P<code>\d+)")"#).assert();
+        ParserTUnit::from_auto(conf).verify_gen_unsupported();
+    }
+
+    #[test]
+    fn test_regex_named_groups() {
+        let mut data = "user=alice from 10.1.1.2 port 22 rest";
+        let conf =
+            WplField::try_parse(r##"regex(r#"user=(?P<user>\w+) from (?P<src>\S+) port \d+"#)"##)
+                .assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "rest");
+        assert_eq!(
+            out,
+            vec![
+                DataField::from_chars("user", "alice"),
+                DataField::from_chars("src", "10.1.1.2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_regex_sub_fields() -> AnyResult<()> {
+        let rule = r##"rule x { (regex("(?P<src>[\d.]+):(?P<port>\d+) \"(?P<msg>[^\"]*)\"")(ip@src, digit@port:sport), chars:tail) }"##;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(r#"10.0.0.1:443 "denied by policy" end"#, 0)?;
+        assert_eq!(left, "");
+        assert_eq!(
+            tdc.field("src"),
+            Some(&DataField::from_ip(
+                "src",
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))
+            ))
+        );
+        assert_eq!(
+            tdc.field("sport"),
+            Some(&DataField::from_digit("sport", 443))
+        );
+        assert_eq!(
+            tdc.field("msg"),
+            Some(&DataField::from_chars("msg", "denied by policy"))
+        );
+        assert_eq!(
+            tdc.field("tail"),
+            Some(&DataField::from_chars("tail", "end"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_regex_not_match() {
+        let conf = WplField::try_parse(r##"regex(r#"(?P<code>\d+)"#)"##).assert();
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut "abc 123");
+    }
+
+    #[test]
+    fn test_regex_bad_pattern() {
+        assert!(WplField::try_parse(r#"regex("(?P<a>\d+")"#).is_err());
+        assert!(WplField::try_parse("regex").is_err());
+        let code = WplCode::try_from((
+            "test.wpl".into(),
+            "package x {\n  rule y {\n    (regex(\"(?P<a>[\")) \n  }\n}",
+        ))
+        .assert();
+        let err = code.parse_pkg().expect_err("bad regex must fail");
+        assert!(err.to_string().contains("line 3"), "{}", err);
+    }
+}
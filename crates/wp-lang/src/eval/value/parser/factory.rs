@@ -1,21 +1,31 @@
 use orion_error::{ContextRecord, ErrorOwe, ErrorWith, WithContext};
 
+use crate::ast::WplField;
 use crate::eval::value::parse_def::{Hold, ParserHold};
 use crate::eval::value::parser::base::digit::{DigitP, FloatP};
+use crate::eval::value::parser::base::grok::{GrokP, compile_wpl_grok};
 use crate::eval::value::parser::base::hex::HexDigitP;
+use crate::eval::value::parser::base::regex::{RegexP, compile_wpl_regex};
 use crate::eval::value::parser::base::*;
 use crate::eval::value::parser::compute::device::SnP;
 use crate::eval::value::parser::network::http;
 use crate::eval::value::parser::network::net::{IpNetP, IpPSR};
+use crate::eval::value::parser::network::useragent::{UserAgentP, ua_db};
 use crate::eval::value::parser::physical::time::{
-    TimeCLF, TimeISOP, TimeP, TimeRFC2822, TimeRFC3339, TimeStampPSR,
+    TimeCLF, TimeFmtConf, TimeFmtP, TimeISOP, TimeP, TimeRFC2822, TimeRFC3339, TimeStampPSR,
 };
 use crate::eval::value::parser::protocol::array::ArrayP;
 use crate::eval::value::parser::protocol::base64::Base64P;
+use crate::eval::value::parser::protocol::cef::CefP;
+use crate::eval::value::parser::protocol::csv::CsvP;
 use crate::eval::value::parser::protocol::json::JsonP;
 use crate::eval::value::parser::protocol::json_exact::ExactJsonP;
 use crate::eval::value::parser::protocol::keyval::KeyValP;
+use crate::eval::value::parser::protocol::leef::LeefP;
 use crate::eval::value::parser::protocol::proto_text::ProtoTextP;
+use crate::eval::value::parser::protocol::syslog::SyslogP;
+use crate::eval::value::parser::protocol::xml::XmlP;
+use crate::parser::datatype::{CEF, CSV, GROK, HTTP_UA, LEEF, REGEX, SYSLOG, TIME_FMT, XML};
 use crate::parser::error::{WplCodeError, WplCodeReason, WplCodeResult};
 use wp_model_core::model::DataType;
 
@@ -70,6 +80,55 @@ impl ParserFactory {
         }
     }
 
+    /// Parsers for WPL protocol types (see `WPL_PROTO_TYPES`), keyed by meta name.
+    /// Field content (format, pattern) is compiled here, once per rule load.
+    pub fn create_proto(conf: &WplField) -> WplCodeResult<Option<ParserHold>> {
+        let content = conf.content.as_deref().unwrap_or_default();
+        let hold: ParserHold = match conf.meta_name.as_str() {
+            CEF => Hold::new(CefP::default()),
+            LEEF => Hold::new(LeefP::default()),
+            XML => Hold::new(XmlP::default()),
+            CSV => Hold::new(CsvP::default()),
+            SYSLOG => Hold::new(SyslogP::default()),
+            TIME_FMT => Hold::new(TimeFmtP::new(
+                TimeFmtConf::parse(content)
+                    .map_err(|e| WplCodeError::from(WplCodeReason::Syntax(e)))?,
+            )),
+            REGEX => Hold::new(RegexP::new(
+                compile_wpl_regex(content)
+                    .map_err(|e| WplCodeError::from(WplCodeReason::Syntax(e)))?,
+            )),
+            GROK => Hold::new(GrokP::new(
+                compile_wpl_grok(content)
+                    .map_err(|e| WplCodeError::from(WplCodeReason::Syntax(e)))?,
+            )),
+            HTTP_UA => Hold::new(UserAgentP::new(ua_db())),
+            _ => return Ok(None),
+        };
+        Ok(Some(hold))
+    }
+
+    /// Create the parser of a field; WPL protocol types are carried as `DataType::Obj`
+    /// and resolved by `meta_name`.
+    pub fn create_named(meta: &DataType, conf: &WplField) -> WplCodeResult<ParserHold> {
+        if *meta == DataType::Obj
+            && let Some(hold) = Self::create_proto(conf)?
+        {
+            return Ok(hold);
+        }
+        Self::create(meta)
+    }
+
+    /// Create a parser from the WPL type name of a field (builtin `DataType` name or protocol type).
+    pub fn create_by_conf(conf: &WplField) -> WplCodeResult<ParserHold> {
+        if let Some(hold) = Self::create_proto(conf)? {
+            return Ok(hold);
+        }
+        let meta_name = conf.meta_name.as_str();
+        let meta = DataType::from(meta_name).owe(WplCodeReason::UnSupport(meta_name.into()))?;
+        Self::create(&meta)
+    }
+
     fn create_l1(meta: &DataType) -> WplCodeResult<ParserHold> {
         let mut ctx = WithContext::want("create parser");
         ctx.record("meta", meta.to_string());
@@ -0,0 +1,395 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::sync::{Arc, RwLock};
+
+// 内置精简库（uap-core regexes.yaml 格式），项目可用完整的 uap-core 库覆盖
+const BUILTIN_UAP_DB: &str = r##"
+user_agent_parsers:
+  - regex: '(Googlebot|bingbot|Baiduspider|YandexBot|DuckDuckBot|AhrefsBot|SemrushBot|Bytespider)/(\d+)\.(\d+)'
+  - regex: '(curl|Wget|python-requests|Go-http-client|okhttp|PostmanRuntime)/(\d+)(?:\.(\d+))?(?:\.(\d+))?'
+  - regex: '(MicroMessenger)/(\d+)(?:\.(\d+))?(?:\.(\d+))?'
+    family_replacement: 'WeChat'
+  - regex: '(Edge|Edg|EdgA|EdgiOS)/(\d+)(?:\.(\d+))?(?:\.(\d+))?'
+    family_replacement: 'Edge'
+  - regex: '(OPR|OPiOS)/(\d+)(?:\.(\d+))?(?:\.(\d+))?'
+    family_replacement: 'Opera'
+  - regex: '(SamsungBrowser)/(\d+)(?:\.(\d+))?'
+    family_replacement: 'Samsung Internet'
+  - regex: '(UCBrowser)/(\d+)(?:\.(\d+))?(?:\.(\d+))?'
+    family_replacement: 'UC Browser'
+  - regex: '(CriOS)/(\d+)(?:\.(\d+))?(?:\.(\d+))?'
+    family_replacement: 'Chrome Mobile iOS'
+  - regex: '(FxiOS)/(\d+)(?:\.(\d+))?'
+    family_replacement: 'Firefox iOS'
+  - regex: '(Chrome)/(\d+)(?:\.(\d+))?(?:\.(\d+))? Mobile'
+    family_replacement: 'Chrome Mobile'
+  - regex: '(Chromium|Chrome)/(\d+)(?:\.(\d+))?(?:\.(\d+))?'
+  - regex: '(Firefox)/(\d+)(?:\.(\d+))?(?:\.(\d+))?'
+  - regex: '(Version)/(\d+)(?:\.(\d+))?(?:\.(\d+))? Mobile/\S+ Safari/'
+    family_replacement: 'Mobile Safari'
+  - regex: '(Version)/(\d+)(?:\.(\d+))?(?:\.(\d+))? Safari/'
+    family_replacement: 'Safari'
+  - regex: '(MSIE) (\d+)\.(\d+)'
+    family_replacement: 'IE'
+  - regex: '(Trident)/7\.0.*rv:(\d+)\.(\d+)'
+    family_replacement: 'IE'
+os_parsers:
+  - regex: '(Windows Phone)(?: OS)? (\d+)\.(\d+)'
+  - regex: 'Windows NT 10\.0'
+    os_replacement: 'Windows'
+    os_v1_replacement: '10'
+  - regex: 'Windows NT 6\.3'
+    os_replacement: 'Windows'
+    os_v1_replacement: '8'
+    os_v2_replacement: '1'
+  - regex: 'Windows NT 6\.2'
+    os_replacement: 'Windows'
+    os_v1_replacement: '8'
+  - regex: 'Windows NT 6\.1'
+    os_replacement: 'Windows'
+    os_v1_replacement: '7'
+  - regex: 'Windows NT 5\.1'
+    os_replacement: 'Windows'
+    os_v1_replacement: 'XP'
+  - regex: '(Android)[ /](\d+)(?:\.(\d+))?(?:\.(\d+))?'
+  - regex: '(CPU OS|iPhone OS|CPU iPhone OS) (\d+)_(\d+)(?:_(\d+))?'
+    os_replacement: 'iOS'
+  - regex: '(Mac OS X)[ _](\d+)[_.](\d+)(?:[_.](\d+))?'
+  - regex: '(CrOS) \S+ (\d+)\.(\d+)(?:\.(\d+))?'
+    os_replacement: 'Chrome OS'
+  - regex: '(Ubuntu|Debian|Fedora|CentOS)'
+  - regex: '(Linux)'
+device_parsers:
+  - regex: '(bot|spider|crawl|slurp)'
+    regex_flag: 'i'
+    device_replacement: 'Spider'
+    brand_replacement: 'Spider'
+    model_replacement: 'Desktop'
+  - regex: '(iPad)'
+    device_replacement: 'iPad'
+    brand_replacement: 'Apple'
+    model_replacement: 'iPad'
+  - regex: '(iPhone)'
+    device_replacement: 'iPhone'
+    brand_replacement: 'Apple'
+    model_replacement: 'iPhone'
+  - regex: '(Macintosh)'
+    device_replacement: 'Mac'
+    brand_replacement: 'Apple'
+    model_replacement: 'Mac'
+  - regex: '; *(SM-[A-Z0-9]+)'
+    device_replacement: 'Samsung $1'
+    brand_replacement: 'Samsung'
+    model_replacement: '$1'
+  - regex: 'Android [\d.]+; *([^;)]+?)(?: Build/[^;)]+)?\)'
+    brand_replacement: 'Generic_Android'
+"##;
+
+const OTHER: &str = "Other";
+
+#[derive(Debug, Default, Deserialize)]
+struct UapYaml {
+    #[serde(default)]
+    user_agent_parsers: Vec<UaRuleConf>,
+    #[serde(default)]
+    os_parsers: Vec<OsRuleConf>,
+    #[serde(default)]
+    device_parsers: Vec<DeviceRuleConf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UaRuleConf {
+    regex: String,
+    family_replacement: Option<String>,
+    v1_replacement: Option<String>,
+    v2_replacement: Option<String>,
+    v3_replacement: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsRuleConf {
+    regex: String,
+    os_replacement: Option<String>,
+    os_v1_replacement: Option<String>,
+    os_v2_replacement: Option<String>,
+    os_v3_replacement: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceRuleConf {
+    regex: String,
+    regex_flag: Option<String>,
+    device_replacement: Option<String>,
+    brand_replacement: Option<String>,
+    model_replacement: Option<String>,
+}
+
+// 一条规则：正则 + 各输出项的替换模板（None 时取对应分组）
+#[derive(Debug)]
+struct UapRule {
+    re: Regex,
+    replaces: [Option<String>; 4],
+}
+
+impl UapRule {
+    fn new(regex: &str, flag: Option<&str>, replaces: [Option<String>; 4]) -> Option<Self> {
+        let pattern = match flag {
+            Some("i") => format!("(?i){}", regex),
+            _ => regex.to_string(),
+        };
+        match Regex::new(pattern.as_str()) {
+            Ok(re) => Some(Self { re, replaces }),
+            Err(e) => {
+                // uap-core 中少量正则超出 regex crate 语法，跳过
+                warn!("skip uap regex {}: {}", regex, e);
+                None
+            }
+        }
+    }
+
+    // 第 idx 项：替换模板 ($1..$9) 或第 group 个分组
+    fn item(&self, caps: &Captures, idx: usize, group: Option<usize>) -> Option<String> {
+        let val = match &self.replaces[idx] {
+            Some(tpl) => expand_groups(tpl, caps),
+            None => caps.get(group?).map(|m| m.as_str().to_string())?,
+        };
+        let val = val.trim();
+        (!val.is_empty()).then(|| val.to_string())
+    }
+}
+
+fn expand_groups(tpl: &str, caps: &Captures) -> String {
+    let mut out = String::with_capacity(tpl.len());
+    let mut chars = tpl.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().and_then(|x| x.to_digit(10))) {
+            ('$', Some(n)) => {
+                chars.next();
+                out.push_str(caps.get(n as usize).map(|m| m.as_str()).unwrap_or(""));
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// user-agent 拆解结果，未识别项为 `Other`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserAgent {
+    pub browser_family: String,
+    pub browser_version: Option<String>,
+    pub os_family: String,
+    pub os_version: Option<String>,
+    pub device_family: String,
+    pub device_brand: Option<String>,
+    pub device_model: Option<String>,
+    pub device_class: &'static str,
+}
+
+/// uap-core 格式的 user-agent 正则库
+#[derive(Debug)]
+pub struct UaDb {
+    ua: Vec<UapRule>,
+    os: Vec<UapRule>,
+    device: Vec<UapRule>,
+}
+
+impl UaDb {
+    pub fn from_yaml(text: &str) -> Result<Self, String> {
+        let conf: UapYaml =
+            serde_yaml::from_str(text).map_err(|e| format!("bad uap regexes yaml: {}", e))?;
+        let ua = conf
+            .user_agent_parsers
+            .into_iter()
+            .filter_map(|x| {
+                UapRule::new(
+                    &x.regex,
+                    None,
+                    [
+                        x.family_replacement,
+                        x.v1_replacement,
+                        x.v2_replacement,
+                        x.v3_replacement,
+                    ],
+                )
+            })
+            .collect();
+        let os = conf
+            .os_parsers
+            .into_iter()
+            .filter_map(|x| {
+                UapRule::new(
+                    &x.regex,
+                    None,
+                    [
+                        x.os_replacement,
+                        x.os_v1_replacement,
+                        x.os_v2_replacement,
+                        x.os_v3_replacement,
+                    ],
+                )
+            })
+            .collect();
+        let device = conf
+            .device_parsers
+            .into_iter()
+            .filter_map(|x| {
+                UapRule::new(
+                    &x.regex,
+                    x.regex_flag.as_deref(),
+                    [
+                        x.device_replacement,
+                        x.brand_replacement,
+                        x.model_replacement,
+                        None,
+                    ],
+                )
+            })
+            .collect();
+        Ok(Self { ua, os, device })
+    }
+
+    // 首个匹配的规则：family 取替换或分组 1，版本依次取分组 2..4
+    fn family_version(rules: &[UapRule], agent: &str) -> (String, Option<String>) {
+        for rule in rules {
+            if let Some(caps) = rule.re.captures(agent) {
+                let family = rule
+                    .item(&caps, 0, Some(1))
+                    .unwrap_or_else(|| OTHER.to_string());
+                let parts = (1..4)
+                    .map_while(|i| rule.item(&caps, i, Some(i + 1)))
+                    .collect::<Vec<_>>();
+                let version = (!parts.is_empty()).then(|| parts.join("."));
+                return (family, version);
+            }
+        }
+        (OTHER.to_string(), None)
+    }
+
+    pub fn parse(&self, agent: &str) -> UserAgent {
+        let (browser_family, browser_version) = Self::family_version(&self.ua, agent);
+        let (os_family, os_version) = Self::family_version(&self.os, agent);
+        let (device_family, device_brand, device_model) = self
+            .device
+            .iter()
+            .find_map(|rule| {
+                rule.re.captures(agent).map(|caps| {
+                    (
+                        rule.item(&caps, 0, Some(1))
+                            .unwrap_or_else(|| OTHER.to_string()),
+                        rule.item(&caps, 1, None),
+                        rule.item(&caps, 2, Some(1)),
+                    )
+                })
+            })
+            .unwrap_or_else(|| (OTHER.to_string(), None, None));
+        let device_class = device_class(agent, &os_family, &device_family);
+        UserAgent {
+            browser_family,
+            browser_version,
+            os_family,
+            os_version,
+            device_family,
+            device_brand,
+            device_model,
+            device_class,
+        }
+    }
+}
+
+// uap-core 不给出设备大类，按设备/系统/UA 关键字归类
+fn device_class(agent: &str, os: &str, device: &str) -> &'static str {
+    if device == "Spider" {
+        "bot"
+    } else if device.contains("iPad")
+        || agent.contains("Tablet")
+        || (os == "Android" && !agent.contains("Mobile"))
+    {
+        "tablet"
+    } else if agent.contains("Mobi")
+        || matches!(os, "iOS" | "Android" | "Windows Phone" | "BlackBerry OS")
+    {
+        "mobile"
+    } else {
+        "desktop"
+    }
+}
+
+static UA_DB: Lazy<RwLock<Arc<UaDb>>> = Lazy::new(|| {
+    RwLock::new(Arc::new(
+        UaDb::from_yaml(BUILTIN_UAP_DB).expect("builtin uap db"),
+    ))
+});
+
+/// 当前 user-agent 库，规则加载时取用
+pub fn ua_db() -> Arc<UaDb> {
+    UA_DB.read().expect("uap db poisoned").clone()
+}
+
+/// 以 uap-core regexes.yaml 替换当前库
+pub fn register_ua_db(text: &str) -> Result<(), String> {
+    let db = UaDb::from_yaml(text)?;
+    *UA_DB.write().expect("uap db poisoned") = Arc::new(db);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uap_builtin() {
+        let db = UaDb::from_yaml(BUILTIN_UAP_DB).expect("db");
+        let ua = db.parse("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.6099.109 Safari/537.36");
+        assert_eq!(ua.browser_family, "Chrome");
+        assert_eq!(ua.browser_version.as_deref(), Some("120.0.6099"));
+        assert_eq!(ua.os_family, "Windows");
+        assert_eq!(ua.os_version.as_deref(), Some("10"));
+        assert_eq!(ua.device_family, "Other");
+        assert_eq!(ua.device_class, "desktop");
+
+        let ua = db.parse("Mozilla/5.0 (iPhone; CPU iPhone OS 17_1_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1.2 Mobile/15E148 Safari/604.1");
+        assert_eq!(ua.browser_family, "Mobile Safari");
+        assert_eq!(ua.browser_version.as_deref(), Some("17.1.2"));
+        assert_eq!(ua.os_family, "iOS");
+        assert_eq!(ua.os_version.as_deref(), Some("17.1.2"));
+        assert_eq!(ua.device_brand.as_deref(), Some("Apple"));
+        assert_eq!(ua.device_class, "mobile");
+
+        let ua = db.parse("Mozilla/5.0 (Linux; Android 13; SM-X700) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0.0.0 Safari/537.36");
+        assert_eq!(ua.device_family, "Samsung SM-X700");
+        assert_eq!(ua.device_model.as_deref(), Some("SM-X700"));
+        assert_eq!(ua.device_class, "tablet");
+
+        let ua =
+            db.parse("Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)");
+        assert_eq!(ua.browser_family, "Googlebot");
+        assert_eq!(ua.device_class, "bot");
+
+        let ua = db.parse("something unknown");
+        assert_eq!(ua.browser_family, "Other");
+        assert_eq!(ua.browser_version, None);
+    }
+
+    #[test]
+    fn test_uap_yaml_load() {
+        let db = UaDb::from_yaml(
+            r#"
+user_agent_parsers:
+  - regex: '(MyApp)/(\d+)\.(\d+)'
+    family_replacement: 'My $1'
+    v2_replacement: 'x'
+  - regex: '(?<!bad)lookbehind'
+os_parsers: []
+"#,
+        )
+        .expect("db");
+        assert_eq!(db.ua.len(), 1);
+        let ua = db.parse("MyApp/3.4");
+        assert_eq!(ua.browser_family, "My MyApp");
+        assert_eq!(ua.browser_version.as_deref(), Some("3.x"));
+        assert_eq!(ua.os_family, "Other");
+        assert!(UaDb::from_yaml("user_agent_parsers: 3").is_err());
+    }
+}
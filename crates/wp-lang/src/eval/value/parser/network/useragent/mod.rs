@@ -0,0 +1,160 @@
+use super::super::prelude::*;
+use std::sync::Arc;
+use wp_model_core::model::FNameStr;
+
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parse_def::PatternParser;
+use crate::eval::value::parser::protocol::take_sub_value;
+use crate::parser::utils::take_to_end;
+
+pub mod db;
+
+pub use db::{UaDb, UserAgent, register_ua_db, ua_db};
+
+/// user-agent 拆解：`http/ua` 输出 browser/os/device 各子字段
+pub struct UserAgentP {
+    db: Arc<UaDb>,
+}
+
+impl UserAgentP {
+    pub fn new(db: Arc<UaDb>) -> Self {
+        Self { db }
+    }
+}
+
+impl Default for UserAgentP {
+    fn default() -> Self {
+        Self::new(ua_db())
+    }
+}
+
+impl PatternParser for UserAgentP {
+    fn pattern_parse(
+        &self,
+        fpu: &FieldEvalUnit,
+        ups_sep: &WplSep,
+        data: &mut &str,
+        _name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
+        multispace0.parse_next(data)?;
+        // UA 含空格，默认读到行尾（通常配合引号作用域）；显式声明分隔符时读到分隔符
+        let agent = if fpu.conf().separator.is_some() {
+            ups_sep.read_until_sep(data)?
+        } else {
+            take_to_end.parse_next(data)?.to_string()
+        };
+        let agent = agent.trim();
+        if agent.is_empty() {
+            return fail.context(ctx_desc("http/ua <agent>")).parse_next(data);
+        }
+        let ua = self.db.parse(agent);
+        let items = [
+            ("browser_family", Some(ua.browser_family.as_str())),
+            ("browser_version", ua.browser_version.as_deref()),
+            ("os_family", Some(ua.os_family.as_str())),
+            ("os_version", ua.os_version.as_deref()),
+            ("device_family", Some(ua.device_family.as_str())),
+            ("device_brand", ua.device_brand.as_deref()),
+            ("device_model", ua.device_model.as_deref()),
+            ("device_class", Some(ua.device_class)),
+        ];
+        for (key, val) in items {
+            if let Some(val) = val {
+                take_sub_value(fpu, key, val, &DataType::Chars, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn patten_gen(
+        &self,
+        _gen: &mut GenChannel,
+        f_conf: &WplField,
+        _g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        Ok(DataField::from_chars(
+            f_conf.safe_name(),
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Safari/605.1.15",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use orion_error::TestAssert;
+    use wp_model_core::model::DataRecord;
+
+    #[test]
+    fn test_ua_parse() {
+        let mut data = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.2210.91";
+        let conf = WplField::try_parse("http/ua").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "");
+        assert_eq!(
+            out,
+            vec![
+                DataField::from_chars("browser_family", "Edge"),
+                DataField::from_chars("browser_version", "120.0.2210"),
+                DataField::from_chars("os_family", "Windows"),
+                DataField::from_chars("os_version", "10"),
+                DataField::from_chars("device_family", "Other"),
+                DataField::from_chars("device_class", "desktop"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ua_rule() -> AnyResult<()> {
+        let rule = r#"rule nginx { (ip:sip, http/ua(chars@browser_family:browser, chars@device_class:device)", digit:status) }"#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(
+            r#"10.0.0.1 "Mozilla/5.0 (iPad; CPU OS 16_6 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/16.6 Mobile/15E148 Safari/604.1" 200"#,
+            0,
+        )?;
+        assert_eq!(left, "");
+        assert_eq!(
+            tdc.field("browser"),
+            Some(&DataField::from_chars("browser", "Mobile Safari"))
+        );
+        assert_eq!(
+            tdc.field("device"),
+            Some(&DataField::from_chars("device", "tablet"))
+        );
+        assert_eq!(
+            tdc.field("os_version"),
+            Some(&DataField::from_chars("os_version", "16.6"))
+        );
+        assert_eq!(
+            tdc.field("status"),
+            Some(&DataField::from_digit("status", 200))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ua_gen_parse() -> AnyResult<()> {
+        let conf = WplField::try_parse("http/ua").assert();
+        let mut gnc = GenChannel::new();
+        let field = UserAgentP::default().patten_gen(&mut gnc, &conf, None)?;
+        let line = field.get_chars().unwrap_or_default().to_string();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut line.as_str())
+            .assert();
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("browser_family"),
+            Some(&DataField::from_chars("browser_family", "Safari"))
+        );
+        assert_eq!(
+            record.field("device_brand"),
+            Some(&DataField::from_chars("device_brand", "Apple"))
+        );
+        Ok(())
+    }
+}
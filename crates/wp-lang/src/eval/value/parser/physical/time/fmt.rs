@@ -0,0 +1,284 @@
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parse_def::PatternParser;
+use crate::generator::{FieldGenConf, GenChannel};
+use crate::parser::utils::quot_str;
+use crate::types::AnyResult;
+use crate::winnow::Parser;
+use chrono::format::{Fixed, Item, Numeric, Parsed, StrftimeItems, parse_and_remainder};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, TimeDelta, Utc};
+use std::fmt::Write as _;
+use winnow::ascii::multispace0;
+use winnow::combinator::{fail, opt, preceded};
+use wp_model_core::model::{DataField, FNameStr, Value};
+use wp_parser::WResult;
+use wp_parser::symbol::ctx_desc;
+
+/// `time/fmt("<strftime>"[, "<tz>"])` 的参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeFmtConf {
+    fmt: String,
+    tz: Option<FixedOffset>,
+    has_year: bool,
+}
+
+impl TimeFmtConf {
+    /// 解析字段内容 `"<fmt>"[, "<tz>"]`，格式非法时返回错误说明
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut data = content;
+        let (fmt, tz) = (
+            preceded(multispace0, quot_str),
+            opt(preceded((multispace0, ',', multispace0), quot_str)),
+            multispace0,
+        )
+            .map(|x| (x.0, x.1))
+            .parse_next(&mut data)
+            .map_err(|_| format!("bad time/fmt args: {}", content))?;
+        if !data.is_empty() {
+            return Err(format!("bad time/fmt args: {}", content));
+        }
+        let items = StrftimeItems::new(fmt).collect::<Vec<_>>();
+        if items.is_empty() || items.contains(&Item::Error) {
+            return Err(format!("bad strftime format: {}", fmt));
+        }
+        let has_year = items.iter().any(|x| {
+            matches!(
+                x,
+                Item::Numeric(
+                    Numeric::Year
+                        | Numeric::YearMod100
+                        | Numeric::IsoYear
+                        | Numeric::IsoYearMod100
+                        | Numeric::Timestamp,
+                    _
+                ) | Item::Fixed(Fixed::RFC2822 | Fixed::RFC3339)
+            )
+        });
+        let tz = tz.map(parse_tz).transpose()?;
+        Ok(Self {
+            fmt: fmt.to_string(),
+            tz,
+            has_year,
+        })
+    }
+
+    fn now(&self) -> NaiveDateTime {
+        match &self.tz {
+            Some(tz) => Utc::now().with_timezone(tz).naive_local(),
+            None => chrono::Local::now().naive_local(),
+        }
+    }
+
+    pub fn parse_time(&self, data: &mut &str) -> WResult<NaiveDateTime> {
+        let mut parsed = Parsed::new();
+        let Ok(remain) = parse_and_remainder(&mut parsed, data, StrftimeItems::new(&self.fmt))
+        else {
+            return fail
+                .context(ctx_desc("time/fmt not match"))
+                .parse_next(data);
+        };
+        let now = self.now();
+        if !self.has_year && parsed.set_year(now.year() as i64).is_err() {
+            return fail
+                .context(ctx_desc("time/fmt year fail"))
+                .parse_next(data);
+        }
+        let time = if parsed.offset().is_some() {
+            parsed.to_datetime().ok().map(|dt| match &self.tz {
+                Some(tz) => dt.with_timezone(tz).naive_local(),
+                None => dt.naive_local(),
+            })
+        } else {
+            parsed.to_naive_datetime_with_offset(0).ok()
+        };
+        let Some(mut time) = time else {
+            return fail
+                .context(ctx_desc("time/fmt incomplete time"))
+                .parse_next(data);
+        };
+        // 无年份 (syslog 风格)：超出当前时间一天以上视为去年，处理跨年
+        if !self.has_year
+            && time > now + TimeDelta::days(1)
+            && let Some(last_year) = time.with_year(time.year() - 1)
+        {
+            time = last_year;
+        }
+        *data = remain;
+        Ok(time)
+    }
+
+    pub fn format(&self, time: NaiveDateTime) -> AnyResult<String> {
+        let offset = self
+            .tz
+            .unwrap_or(FixedOffset::east_opt(0).expect("zero offset"));
+        let dt: DateTime<FixedOffset> = time
+            .and_local_timezone(offset)
+            .single()
+            .unwrap_or_else(|| time.and_utc().fixed_offset());
+        let mut buf = String::new();
+        write!(buf, "{}", dt.format(&self.fmt))?;
+        Ok(buf)
+    }
+}
+
+fn parse_tz(tz: &str) -> Result<FixedOffset, String> {
+    match tz {
+        "Z" | "UTC" | "utc" => Ok(FixedOffset::east_opt(0).expect("zero offset")),
+        _ => tz
+            .parse::<FixedOffset>()
+            .map_err(|_| format!("bad time zone: {}", tz)),
+    }
+}
+
+/// 按 strftime 格式解析时间：`time/fmt("%Y%m%d %H%M%S")`
+pub struct TimeFmtP {
+    conf: TimeFmtConf,
+}
+
+impl TimeFmtP {
+    pub fn new(conf: TimeFmtConf) -> Self {
+        Self { conf }
+    }
+}
+
+impl PatternParser for TimeFmtP {
+    fn pattern_parse(
+        &self,
+        _fpu: &FieldEvalUnit,
+        _ups_sep: &crate::ast::WplSep,
+        data: &mut &str,
+        name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> WResult<()> {
+        let time = self.conf.parse_time(data)?;
+        out.push(DataField::from_time(name, time));
+        Ok(())
+    }
+
+    fn patten_gen(
+        &self,
+        gnc: &mut GenChannel,
+        f_conf: &crate::ast::WplField,
+        g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        let field = super::gen_time(gnc, f_conf, g_conf)?;
+        match field.get_value() {
+            Value::Time(time) => Ok(DataField::from_chars(
+                f_conf.safe_name(),
+                self.conf.format(*time)?,
+            )),
+            _ => Ok(field),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::WplField;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use chrono::NaiveDate;
+    use orion_error::TestAssert;
+
+    fn dt(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, s)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_time_fmt_parse() {
+        let conf = WplField::try_parse(r#"time/fmt("%Y%m%d %H%M%S")"#).assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut "20230515 092244")
+            .assert();
+        assert_eq!(
+            out[0],
+            DataField::from_time("time/fmt", dt(2023, 5, 15, 9, 22, 44))
+        );
+
+        let conf = WplField::try_parse(r#"time/fmt("%d/%m/%Y-%H.%M.%S"):t"#).assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut "15/05/2023-09.22.44")
+            .assert();
+        assert_eq!(
+            out[0],
+            DataField::from_time("t", dt(2023, 5, 15, 9, 22, 44))
+        );
+
+        let conf = WplField::try_parse(r#"time/fmt("%Y年%m月%d日 %H时%M分%S秒"):t"#).assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut "2023年05月15日 09时22分44秒")
+            .assert();
+        assert_eq!(
+            out[0],
+            DataField::from_time("t", dt(2023, 5, 15, 9, 22, 44))
+        );
+    }
+
+    #[test]
+    fn test_time_fmt_tz() {
+        let conf = WplField::try_parse(r#"time/fmt("%Y-%m-%d %H:%M:%S %z", "+08:00"):t"#).assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut "2023-05-15 01:22:44 +0000")
+            .assert();
+        assert_eq!(
+            out[0],
+            DataField::from_time("t", dt(2023, 5, 15, 9, 22, 44))
+        );
+    }
+
+    #[test]
+    fn test_time_fmt_year_less() {
+        let conf = TimeFmtConf::parse(r#""%b %d %H:%M:%S""#).assert();
+        let now = conf.now();
+        let time = conf.parse_time(&mut "Jan 01 00:00:01").assert();
+        assert_eq!(time, dt(now.year(), 1, 1, 0, 0, 1));
+        // 未来时间推断为去年
+        let time = conf.parse_time(&mut "Dec 31 23:59:59").assert();
+        if now < dt(now.year(), 12, 30, 23, 59, 59) {
+            assert_eq!(time.year(), now.year() - 1);
+        }
+    }
+
+    #[test]
+    fn test_time_fmt_rule() -> AnyResult<()> {
+        let rule = r#"rule x { (symbol(<134>), time/fmt("%b %e %H:%M:%S"):ts, chars:host) }"#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc("<134> Oct 11 22:14:15 fw01", 0)?;
+        assert_eq!(left, "");
+        assert!(matches!(
+            tdc.field("ts").map(|x| x.get_value()),
+            Some(Value::Time(_))
+        ));
+        assert_eq!(
+            tdc.field("host"),
+            Some(&DataField::from_chars("host", "fw01"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_fmt_bad() {
+        assert!(WplField::try_parse(r#"time/fmt("%Y-%Q")"#).is_err());
+        assert!(WplField::try_parse(r#"time/fmt("%Y", "+99:99")"#).is_err());
+        assert!(WplField::try_parse("time/fmt").is_err());
+        let conf = WplField::try_parse(r#"time/fmt("%Y%m%d")"#).assert();
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut "2023-05-15");
+    }
+
+    #[test]
+    fn test_time_fmt_gen_parse() -> AnyResult<()> {
+        let conf = WplField::try_parse(r#"time/fmt("%d/%m/%Y-%H.%M.%S")"#).assert();
+        let mut gnc = GenChannel::new();
+        let fmt_conf =
+            TimeFmtConf::parse(conf.content.as_deref().unwrap_or_default()).expect("fmt");
+        let field = TimeFmtP::new(fmt_conf).patten_gen(&mut gnc, &conf, None)?;
+        let line = field.get_chars().unwrap_or_default().to_string();
+        ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut line.as_str())
+            .assert();
+        Ok(())
+    }
+}
@@ -1,12 +1,14 @@
-// time module split: CLF (Common Log Format), RFC (3339/2822/ISO), and TIMESTAMP
+// time module split: CLF (Common Log Format), RFC (3339/2822/ISO), TIMESTAMP and custom FMT
 // This module orchestrates submodules and exposes the public surface.
 
 mod clf;
 mod common; // shared helpers (parse_fixed, fast_apache_dt)
+mod fmt; // strftime style custom format
 mod rfc; // RFC3339 / RFC2822 / flexible time parser
 mod timestamp; // unix timestamp family (s/ms/us) // Common Log Format fast path
 
 pub use clf::TimeCLF;
+pub use fmt::{TimeFmtConf, TimeFmtP};
 pub use rfc::{TimeISOP, TimeP, TimeRFC2822, TimeRFC3339, parse_time};
 pub use timestamp::TimeStampPSR;
 use wp_model_core::model::DataField;
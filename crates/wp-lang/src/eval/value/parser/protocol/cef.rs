@@ -0,0 +1,343 @@
+use super::super::prelude::*;
+use rand::Rng;
+use std::net::Ipv4Addr;
+use wp_model_core::model::FNameStr;
+
+use crate::derive_base_prs;
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parse_def::PatternParser;
+use crate::eval::value::parser::physical::foundation::gen_chars;
+use crate::eval::value::parser::protocol::take_sub_value;
+use crate::parser::utils::take_to_end;
+
+derive_base_prs!(CefP);
+
+// CEF:Version|Device Vendor|Device Product|Device Version|Signature ID|Name|Severity|Extension
+const CEF_HEADERS: [(&str, DataType); 7] = [
+    ("version", DataType::Digit),
+    ("device_vendor", DataType::Chars),
+    ("device_product", DataType::Chars),
+    ("device_version", DataType::Chars),
+    ("signature_id", DataType::Chars),
+    ("name", DataType::Chars),
+    ("severity", DataType::Digit),
+];
+
+impl PatternParser for CefP {
+    fn pattern_parse(
+        &self,
+        fpu: &FieldEvalUnit,
+        ups_sep: &WplSep,
+        data: &mut &str,
+        _name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
+        multispace0.parse_next(data)?;
+        literal("CEF:")
+            .context(ctx_desc("cef <beg> 'CEF:'"))
+            .parse_next(data)?;
+        for (i, (key, meta)) in CEF_HEADERS.iter().enumerate() {
+            let val = take_header(data, i + 1 == CEF_HEADERS.len())?;
+            take_sub_value(fpu, key, val.as_str(), meta, out)?;
+        }
+        // 扩展部分包含空格，默认读到行尾；字段显式声明分隔符时读到分隔符
+        let ext = if fpu.conf().separator.is_some() {
+            ups_sep.read_until_sep(data)?
+        } else {
+            take_to_end.parse_next(data)?.to_string()
+        };
+        parse_extension(fpu, ext.as_str(), out)
+    }
+
+    fn patten_gen(
+        &self,
+        gnc: &mut GenChannel,
+        f_conf: &WplField,
+        _g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        let vendor = gen_chars(gnc, 6, true);
+        let product = gen_chars(gnc, 8, false);
+        let sig_id = gnc.rng.random_range(100..1000);
+        let name = format!("{} {}", gen_chars(gnc, 5, false), gen_chars(gnc, 7, false));
+        let severity = gnc.rng.random_range(0..11);
+        let src = Ipv4Addr::from(gnc.rng.random::<u32>());
+        let dst = Ipv4Addr::from(gnc.rng.random::<u32>());
+        let spt = gnc.rng.random_range(1024..65535);
+        let dpt = gnc.rng.random_range(1..1024);
+        let msg = format!("{} {}", gen_chars(gnc, 10, false), gen_chars(gnc, 6, false));
+        let line = format!(
+            "CEF:0|{}|{}|1.0|{}|{}|{}|src={} spt={} dst={} dpt={} msg={}",
+            vendor, product, sig_id, name, severity, src, spt, dst, dpt, msg
+        );
+        Ok(DataField::from_chars(f_conf.safe_name(), line))
+    }
+}
+
+// 读取一个头部字段直到未转义的 '|'，并处理 \| 与 \\ 转义 (LEEF 头部同样适用)
+pub(super) fn take_header(data: &mut &str, last: bool) -> ModalResult<String> {
+    let mut val = String::new();
+    let mut chars = data.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, n @ ('|' | '\\'))) => val.push(n),
+                Some((_, n)) => {
+                    val.push('\\');
+                    val.push(n);
+                }
+                None => val.push('\\'),
+            },
+            '|' => {
+                *data = &data[i + 1..];
+                return Ok(val);
+            }
+            _ => val.push(c),
+        }
+    }
+    if last {
+        // 无扩展部分时 Severity 之后可以没有 '|'
+        *data = "";
+        return Ok(val);
+    }
+    fail.context(ctx_desc("cef header <|>")).parse_next(data)
+}
+
+fn parse_extension(fpu: &FieldEvalUnit, ext: &str, out: &mut Vec<DataField>) -> ModalResult<()> {
+    let mut rest = ext.trim_start();
+    while !rest.is_empty() {
+        let Some(key_len) = ext_key_len(rest) else {
+            return fail
+                .context(ctx_desc("cef extension <key>=<value>"))
+                .parse_next(&mut rest);
+        };
+        let key = &rest[..key_len];
+        rest = &rest[key_len + 1..];
+        let end = ext_value_end(rest);
+        let val = unescape_ext(rest[..end].trim_end());
+        take_sub_value(fpu, key, val.as_str(), &ext_key_meta(key), out)?;
+        rest = rest[end..].trim_start();
+    }
+    Ok(())
+}
+
+#[inline]
+fn is_ext_key_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'.'
+}
+
+// 若 s 以 `key=` 开头，返回 key 的长度
+fn ext_key_len(s: &str) -> Option<usize> {
+    let n = s.bytes().take_while(|b| is_ext_key_byte(*b)).count();
+    (n > 0 && s.as_bytes().get(n) == Some(&b'=')).then_some(n)
+}
+
+// 值可以包含空格：直到出现 ` key=` 才结束
+fn ext_value_end(s: &str) -> usize {
+    let b = s.as_bytes();
+    let mut i = 0;
+    while i < b.len() {
+        match b[i] {
+            b'\\' => i += 2,
+            b' ' if ext_key_len(&s[i + 1..]).is_some() => return i,
+            _ => i += 1,
+        }
+    }
+    b.len()
+}
+
+fn unescape_ext(val: &str) -> String {
+    let mut buf = String::with_capacity(val.len());
+    let mut chars = val.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            buf.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => buf.push('\n'),
+            Some('r') => buf.push('\r'),
+            Some(n @ ('=' | '\\' | '|')) => buf.push(n),
+            Some(n) => {
+                buf.push('\\');
+                buf.push(n);
+            }
+            None => buf.push('\\'),
+        }
+    }
+    buf
+}
+
+// ArcSight 字典中常用键的默认类型，其余保持 chars
+fn ext_key_meta(key: &str) -> DataType {
+    match key {
+        "src"
+        | "dst"
+        | "dvc"
+        | "agt"
+        | "sourceTranslatedAddress"
+        | "destinationTranslatedAddress"
+        | "deviceTranslatedAddress" => DataType::IP,
+        "spt"
+        | "dpt"
+        | "in"
+        | "out"
+        | "cnt"
+        | "cn1"
+        | "cn2"
+        | "cn3"
+        | "fsize"
+        | "oldFileSize"
+        | "spid"
+        | "dpid"
+        | "sourceTranslatedPort"
+        | "destinationTranslatedPort" => DataType::Digit,
+        _ => DataType::Chars,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use crate::types::AnyResult;
+    use orion_error::TestAssert;
+    use std::net::IpAddr;
+    use wp_model_core::model::DataRecord;
+
+    #[test]
+    fn test_cef_header_and_ext() {
+        let mut data = r#"CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232 msg=Detected a threat. No action needed"#;
+        let conf = WplField::try_parse("cef").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "");
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("version"),
+            Some(&DataField::from_digit("version", 0))
+        );
+        assert_eq!(
+            record.field("device_vendor"),
+            Some(&DataField::from_chars("device_vendor", "Security"))
+        );
+        assert_eq!(
+            record.field("name"),
+            Some(&DataField::from_chars("name", "worm successfully stopped"))
+        );
+        assert_eq!(
+            record.field("severity"),
+            Some(&DataField::from_digit("severity", 10))
+        );
+        assert_eq!(
+            record.field("src"),
+            Some(&DataField::from_ip(
+                "src",
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))
+            ))
+        );
+        assert_eq!(
+            record.field("spt"),
+            Some(&DataField::from_digit("spt", 1232))
+        );
+        assert_eq!(
+            record.field("msg"),
+            Some(&DataField::from_chars(
+                "msg",
+                "Detected a threat. No action needed"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cef_escape() {
+        let mut data = r#"CEF:0|security|threat\|manager|1.0|100|detected a \\ in packet|High|act=blocked a \= cs1=a\nb"#;
+        let conf = WplField::try_parse("cef").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("device_product"),
+            Some(&DataField::from_chars("device_product", "threat|manager"))
+        );
+        assert_eq!(
+            record.field("name"),
+            Some(&DataField::from_chars("name", r#"detected a \ in packet"#))
+        );
+        // 非数字的 Severity 保持 chars
+        assert_eq!(
+            record.field("severity"),
+            Some(&DataField::from_chars("severity", "High"))
+        );
+        assert_eq!(
+            record.field("act"),
+            Some(&DataField::from_chars("act", "blocked a ="))
+        );
+        assert_eq!(
+            record.field("cs1"),
+            Some(&DataField::from_chars("cs1", "a\nb"))
+        );
+    }
+
+    #[test]
+    fn test_cef_sub_fields() -> AnyResult<()> {
+        let rule = r#"rule cef { (symbol(<134>), time, chars:host, cef(chars@severity:level, chars@dpt, digit@cn1:hits)) }"#;
+        let data = r#"<134> 2023-05-15 09:22:44 fw01 CEF:1|Vendor|FW|2.0|deny|Traffic denied|Low|dpt=443 cn1=7 suser=bob"#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(data, 0)?;
+        assert_eq!(left, "");
+        assert_eq!(
+            tdc.field("level"),
+            Some(&DataField::from_chars("level", "Low"))
+        );
+        assert_eq!(tdc.field("dpt"), Some(&DataField::from_chars("dpt", "443")));
+        assert_eq!(tdc.field("hits"), Some(&DataField::from_digit("hits", 7)));
+        assert_eq!(
+            tdc.field("suser"),
+            Some(&DataField::from_chars("suser", "bob"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cef_without_ext() {
+        let mut data = "CEF:0|a|b|1|sig|name|3|";
+        let conf = WplField::try_parse("cef").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(out.len(), 7);
+
+        let mut data = "CEF:0|a|b|1|sig|name|3";
+        let conf = WplField::try_parse("cef").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(out.len(), 7);
+    }
+
+    #[test]
+    fn test_cef_fail() {
+        let conf = WplField::try_parse("cef").assert();
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "LEEF:1.0|a|b|c|d|");
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "CEF:0|a|b|c");
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut "CEF:0|a|b|1|sig|name|3|=x");
+    }
+
+    #[test]
+    fn test_cef_gen_parse() -> AnyResult<()> {
+        let conf = WplField::try_parse("cef").assert();
+        let mut gnc = GenChannel::new();
+        let field = CefP::default().patten_gen(&mut gnc, &conf, None)?;
+        let line = field.get_chars().unwrap_or_default().to_string();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut line.as_str())
+            .assert();
+        let record = DataRecord::from(out);
+        assert!(record.field("src").is_some());
+        assert!(record.field("msg").is_some());
+        Ok(())
+    }
+}
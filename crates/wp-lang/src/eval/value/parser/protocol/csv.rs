@@ -0,0 +1,261 @@
+use super::super::prelude::*;
+use rand::Rng;
+use wp_data_fmt::{Csv, DataFormat};
+use wp_model_core::model::{DataRecord, FNameStr};
+
+use crate::derive_base_prs;
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parse_def::PatternParser;
+use crate::eval::value::parser::physical::foundation::gen_chars;
+use crate::eval::value::parser::protocol::take_sub_value;
+
+derive_base_prs!(CsvP);
+
+const CSV_DEFAULT_DELIMITER: &str = ",";
+const CSV_QUOTE: char = '"';
+
+impl CsvP {
+    // 列分隔符复用字段分隔符：csv\; 、csv\\\t；未声明时为 ','
+    fn delimiter(fpu: &FieldEvalUnit, ups_sep: &WplSep) -> String {
+        if fpu.conf().separator.is_some() && !ups_sep.is_to_end() {
+            ups_sep.sep_str().to_string()
+        } else {
+            CSV_DEFAULT_DELIMITER.to_string()
+        }
+    }
+}
+
+impl PatternParser for CsvP {
+    fn pattern_parse(
+        &self,
+        fpu: &FieldEvalUnit,
+        ups_sep: &WplSep,
+        data: &mut &str,
+        _name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
+        let delimiter = Self::delimiter(fpu, ups_sep);
+        // 子字段按声明顺序作为表头：csv(@name, digit@age, ...)
+        let headers: Vec<&str> = fpu
+            .conf()
+            .sub_fields
+            .as_ref()
+            .map(|subs| {
+                subs.conf_items()
+                    .exact_iter()
+                    .map(|(k, _)| k.as_str())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut idx = 0;
+        loop {
+            let value = take_column(data, delimiter.as_str())?;
+            let key = headers
+                .get(idx)
+                .map(|x| x.to_string())
+                .unwrap_or_else(|| format!("col{}", idx));
+            // 未加引号的空列视为缺失值
+            if let Some(val) = value {
+                take_sub_value(fpu, key.as_str(), val.as_str(), &DataType::Chars, out)?;
+            }
+            idx += 1;
+            if data.starts_with(delimiter.as_str()) {
+                *data = &data[delimiter.len()..];
+            } else {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn patten_gen(
+        &self,
+        gnc: &mut GenChannel,
+        f_conf: &WplField,
+        _g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        let delimiter = f_conf
+            .separator
+            .as_ref()
+            .filter(|sep| !sep.is_to_end())
+            .and_then(|sep| sep.sep_str().chars().next())
+            .unwrap_or(',');
+        let record = DataRecord::from(vec![
+            DataField::from_digit("id", gnc.rng.random_range(1..100000)),
+            DataField::from_chars("name", gen_chars(gnc, 6, true)),
+            DataField::from_chars(
+                "desc",
+                format!(
+                    "{}{} \"{}\"",
+                    gen_chars(gnc, 5, false),
+                    delimiter,
+                    gen_chars(gnc, 4, false)
+                ),
+            ),
+        ]);
+        let line = Csv::default()
+            .with_delimiter(delimiter)
+            .format_record(&record);
+        Ok(DataField::from_chars(f_conf.safe_name(), line))
+    }
+}
+
+// 读取一列（RFC 4180）：引号列内允许分隔符、换行，"" 表示一个引号
+fn take_column(data: &mut &str, delimiter: &str) -> ModalResult<Option<String>> {
+    if !data.starts_with(CSV_QUOTE) {
+        let end = data
+            .char_indices()
+            .find(|(i, c)| *c == '\n' || *c == '\r' || data[*i..].starts_with(delimiter))
+            .map(|(i, _)| i)
+            .unwrap_or(data.len());
+        let val = &data[..end];
+        *data = &data[end..];
+        return Ok((!val.is_empty()).then(|| val.to_string()));
+    }
+    let mut val = String::new();
+    let mut chars = data.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != CSV_QUOTE {
+            val.push(c);
+            continue;
+        }
+        if let Some((_, CSV_QUOTE)) = chars.peek() {
+            chars.next();
+            val.push(CSV_QUOTE);
+            continue;
+        }
+        let rest = &data[i + 1..];
+        if rest.is_empty()
+            || rest.starts_with(delimiter)
+            || rest.starts_with('\n')
+            || rest.starts_with('\r')
+        {
+            *data = rest;
+            return Ok(Some(val));
+        }
+        *data = rest;
+        return fail
+            .context(ctx_desc("csv <delimiter> after quoted column"))
+            .parse_next(data);
+    }
+    fail.context(ctx_desc("csv unclosed quote"))
+        .parse_next(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::fld_fmt::WplFieldFmt;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use crate::generator::{CSVGenFmt, FmtField};
+    use crate::types::AnyResult;
+    use orion_error::TestAssert;
+
+    #[test]
+    fn test_csv_quoting() {
+        let mut data = r#"1,"Smith, John","say ""hi""",,"",last"#;
+        let conf = WplField::try_parse("csv").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "");
+        assert_eq!(
+            out,
+            vec![
+                DataField::from_chars("col0", "1"),
+                DataField::from_chars("col1", "Smith, John"),
+                DataField::from_chars("col2", r#"say "hi""#),
+                DataField::from_chars("col4", ""),
+                DataField::from_chars("col5", "last"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_header_and_delimiter() -> AnyResult<()> {
+        let rule = r#"rule csv { (csv(digit@id, @user, ip@src:src_ip)\;) }"#;
+        let data = r#"42;"a;b";10.0.0.1;extra"#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(data, 0)?;
+        assert_eq!(left, "");
+        assert_eq!(tdc.field("id"), Some(&DataField::from_digit("id", 42)));
+        assert_eq!(
+            tdc.field("user"),
+            Some(&DataField::from_chars("user", "a;b"))
+        );
+        assert!(tdc.field("src_ip").is_some());
+        assert_eq!(
+            tdc.field("col3"),
+            Some(&DataField::from_chars("col3", "extra"))
+        );
+
+        // TSV
+        let rule = r#"rule tsv { (csv(@a, @b)\\\t) }"#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, _) = pipe.proc("x y\tz", 0)?;
+        assert_eq!(tdc.field("a"), Some(&DataField::from_chars("a", "x y")));
+        assert_eq!(tdc.field("b"), Some(&DataField::from_chars("b", "z")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_multi_line_quote() {
+        let mut data = "a,\"line1\nline2\"\nnext";
+        let conf = WplField::try_parse("csv").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(out[1], DataField::from_chars("col1", "line1\nline2"));
+        assert_eq!(data, "next");
+    }
+
+    #[test]
+    fn test_csv_fail() {
+        let conf = WplField::try_parse("csv").assert();
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut r#"a,"unclosed"#);
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut r#"a,"x"y,b"#);
+    }
+
+    #[test]
+    fn test_csv_gen_fmt_round_trip() -> AnyResult<()> {
+        let fields = vec![
+            DataField::from_digit("id", 7),
+            DataField::from_chars("name", "Doe, Jane"),
+            DataField::from_chars("note", r#"he said "ok""#),
+        ];
+        let fmt_fields = fields
+            .iter()
+            .map(|f| {
+                FmtField::new(
+                    f.get_meta().clone(),
+                    f.clone(),
+                    WplFieldFmt::default(),
+                    WplSep::default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let line = CSVGenFmt(&fmt_fields).to_string();
+        let rule = r#"rule csv { (csv(digit@id, @name, @note)) }"#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(line.as_str(), 0)?;
+        assert_eq!(left, "");
+        for f in fields {
+            assert_eq!(tdc.field(f.get_name()), Some(&f));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_gen_parse() -> AnyResult<()> {
+        let conf = WplField::try_parse(r#"csv\;"#).assert();
+        let mut gnc = GenChannel::new();
+        let field = CsvP::default().patten_gen(&mut gnc, &conf, None)?;
+        let line = field.get_chars().unwrap_or_default().to_string();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut line.as_str())
+            .assert();
+        assert_eq!(out.len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+use super::super::prelude::*;
+use rand::Rng;
+use std::net::Ipv4Addr;
+use wp_model_core::model::FNameStr;
+
+use crate::derive_base_prs;
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parse_def::PatternParser;
+use crate::eval::value::parser::physical::foundation::gen_chars;
+use crate::eval::value::parser::protocol::cef::take_header;
+use crate::eval::value::parser::protocol::take_sub_value;
+use crate::parser::utils::take_to_end;
+
+derive_base_prs!(LeefP);
+
+// LEEF:1.0|Vendor|Product|Version|EventID|Extension
+// LEEF:2.0|Vendor|Product|Version|EventID|DelimiterCharacter|Extension
+const LEEF_HEADERS: [&str; 4] = ["vendor", "product", "product_version", "event_id"];
+
+const LEEF_DEFAULT_DELIMITER: char = '\t';
+
+impl PatternParser for LeefP {
+    fn pattern_parse(
+        &self,
+        fpu: &FieldEvalUnit,
+        ups_sep: &WplSep,
+        data: &mut &str,
+        _name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
+        multispace0.parse_next(data)?;
+        literal("LEEF:")
+            .context(ctx_desc("leef <beg> 'LEEF:'"))
+            .parse_next(data)?;
+        let version = take_header(data, false)?;
+        let is_v2 = match version.as_str() {
+            "1.0" => false,
+            "2.0" => true,
+            _ => {
+                return fail
+                    .context(ctx_desc("leef version <1.0|2.0>"))
+                    .parse_next(data);
+            }
+        };
+        take_sub_value(fpu, "version", version.as_str(), &DataType::Chars, out)?;
+        for (i, key) in LEEF_HEADERS.iter().enumerate() {
+            let val = take_header(data, i + 1 == LEEF_HEADERS.len())?;
+            take_sub_value(fpu, key, val.as_str(), &DataType::Chars, out)?;
+        }
+        let delimiter = if is_v2 {
+            take_delimiter(data)?
+        } else {
+            LEEF_DEFAULT_DELIMITER
+        };
+        let ext = if fpu.conf().separator.is_some() {
+            ups_sep.read_until_sep(data)?
+        } else {
+            take_to_end.parse_next(data)?.to_string()
+        };
+        parse_extension(fpu, ext.as_str(), delimiter, out)
+    }
+
+    fn patten_gen(
+        &self,
+        gnc: &mut GenChannel,
+        f_conf: &WplField,
+        _g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        let vendor = gen_chars(gnc, 6, true);
+        let product = gen_chars(gnc, 8, false);
+        let event_id = gnc.rng.random_range(100..1000);
+        let src = Ipv4Addr::from(gnc.rng.random::<u32>());
+        let dst = Ipv4Addr::from(gnc.rng.random::<u32>());
+        let src_port = gnc.rng.random_range(1024..65535);
+        let dst_port = gnc.rng.random_range(1..1024);
+        let usr = gen_chars(gnc, 6, false);
+        let line = format!(
+            "LEEF:2.0|{}|{}|1.0|{}|^|src={}^dst={}^srcPort={}^dstPort={}^usrName={}",
+            vendor, product, event_id, src, dst, src_port, dst_port, usr
+        );
+        Ok(DataField::from_chars(f_conf.safe_name(), line))
+    }
+}
+
+// LEEF 2.0 的分隔符字段：单个字符或十六进制形式 (x09 / 0x09)，可省略
+fn take_delimiter(data: &mut &str) -> ModalResult<char> {
+    let Some(end) = data.find('|') else {
+        return Ok(LEEF_DEFAULT_DELIMITER);
+    };
+    let seg = &data[..end];
+    if seg.contains('=') {
+        // 省略分隔符字段，直接进入扩展部分
+        return Ok(LEEF_DEFAULT_DELIMITER);
+    }
+    let delimiter = match parse_delimiter(seg) {
+        Some(c) => c,
+        None => {
+            return fail
+                .context(ctx_desc("leef delimiter <char|xHH>"))
+                .parse_next(data);
+        }
+    };
+    *data = &data[end + 1..];
+    Ok(delimiter)
+}
+
+fn parse_delimiter(seg: &str) -> Option<char> {
+    let mut chars = seg.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => Some(LEEF_DEFAULT_DELIMITER),
+        (Some(c), None) => Some(c),
+        _ => {
+            let hex = seg
+                .strip_prefix("0x")
+                .or_else(|| seg.strip_prefix("0X"))
+                .or_else(|| seg.strip_prefix('x'))
+                .or_else(|| seg.strip_prefix('X'))?;
+            u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+        }
+    }
+}
+
+fn parse_extension(
+    fpu: &FieldEvalUnit,
+    ext: &str,
+    delimiter: char,
+    out: &mut Vec<DataField>,
+) -> ModalResult<()> {
+    for item in ext.trim_end_matches(['\r', '\n']).split(delimiter) {
+        if item.trim().is_empty() {
+            continue;
+        }
+        let Some((key, val)) = item.split_once('=') else {
+            return fail
+                .context(ctx_desc("leef attribute <key>=<value>"))
+                .parse_next(&mut &*item);
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            return fail
+                .context(ctx_desc("leef attribute <key>"))
+                .parse_next(&mut &*item);
+        }
+        take_sub_value(fpu, key, val, &attr_key_meta(key), out)?;
+    }
+    Ok(())
+}
+
+// LEEF 预定义属性的默认类型，其余保持 chars
+fn attr_key_meta(key: &str) -> DataType {
+    match key {
+        "devTime" => DataType::Time,
+        "src" | "dst" | "srcPreNAT" | "dstPreNAT" | "srcPostNAT" | "dstPostNAT" | "identSrc" => {
+            DataType::IP
+        }
+        "srcPort" | "dstPort" | "srcPreNATPort" | "dstPreNATPort" | "srcPostNATPort"
+        | "dstPostNATPort" | "sev" | "srcBytes" | "dstBytes" | "srcPackets" | "dstPackets"
+        | "totalPackets" => DataType::Digit,
+        _ => DataType::Chars,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use crate::types::AnyResult;
+    use chrono::NaiveDate;
+    use orion_error::TestAssert;
+    use std::net::IpAddr;
+    use wp_model_core::model::DataRecord;
+
+    #[test]
+    fn test_leef_v1() {
+        let mut data = "LEEF:1.0|Microsoft|MSExchange|2013 SP1|15345|src=10.50.1.1\tdst=2.10.20.20\tspt=1200\tdevTime=Oct 13 2017 10:12:32\tusrName=John Smith";
+        let conf = WplField::try_parse("leef").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "");
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("version"),
+            Some(&DataField::from_chars("version", "1.0"))
+        );
+        assert_eq!(
+            record.field("product_version"),
+            Some(&DataField::from_chars("product_version", "2013 SP1"))
+        );
+        assert_eq!(
+            record.field("event_id"),
+            Some(&DataField::from_chars("event_id", "15345"))
+        );
+        assert_eq!(
+            record.field("src"),
+            Some(&DataField::from_ip(
+                "src",
+                IpAddr::V4(Ipv4Addr::new(10, 50, 1, 1))
+            ))
+        );
+        let dev_time = NaiveDate::from_ymd_opt(2017, 10, 13)
+            .unwrap()
+            .and_hms_opt(10, 12, 32)
+            .unwrap();
+        assert_eq!(
+            record.field("devTime"),
+            Some(&DataField::from_time("devTime", dev_time))
+        );
+        assert_eq!(
+            record.field("usrName"),
+            Some(&DataField::from_chars("usrName", "John Smith"))
+        );
+    }
+
+    #[test]
+    fn test_leef_v2_delimiter() {
+        let conf = WplField::try_parse("leef").assert();
+        let mut data = "LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5^devTime=1508389080000^msg=a|b c";
+        let out = ParserTUnit::from_auto(conf.clone())
+            .verify_parse_suc(&mut data)
+            .assert();
+        let record = DataRecord::from(out);
+        assert_eq!(record.field("sev"), Some(&DataField::from_digit("sev", 5)));
+        assert_eq!(
+            record.field("msg"),
+            Some(&DataField::from_chars("msg", "a|b c"))
+        );
+        let dev_time = chrono::DateTime::from_timestamp_millis(1508389080000)
+            .unwrap()
+            .naive_utc();
+        assert_eq!(
+            record.field("devTime"),
+            Some(&DataField::from_time("devTime", dev_time))
+        );
+
+        // 十六进制分隔符
+        let mut data = "LEEF:2.0|Vendor|Product|1.0|E1|x09|src=10.0.0.1\tdstPort=443";
+        let out = ParserTUnit::from_auto(conf.clone())
+            .verify_parse_suc(&mut data)
+            .assert();
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("dstPort"),
+            Some(&DataField::from_digit("dstPort", 443))
+        );
+
+        let mut data = "LEEF:2.0|Vendor|Product|1.0|E1|0x7C|src=10.0.0.1|dst=10.0.0.2";
+        let out = ParserTUnit::from_auto(conf.clone())
+            .verify_parse_suc(&mut data)
+            .assert();
+        let record = DataRecord::from(out);
+        assert!(record.field("dst").is_some());
+
+        // 省略分隔符字段时使用默认 tab
+        let mut data = "LEEF:2.0|Vendor|Product|1.0|E1|src=10.0.0.1\tusrName=bob";
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("usrName"),
+            Some(&DataField::from_chars("usrName", "bob"))
+        );
+    }
+
+    #[test]
+    fn test_leef_sub_fields() -> AnyResult<()> {
+        let rule = r#"rule leef { (symbol(<13>), chars:host, leef(chars@src:src_ip, digit@cat, chars@event_id:eid)) }"#;
+        let data =
+            "<13> qradar LEEF:2.0|IBM|QRadar|7.5|login|^|src=192.168.1.9^cat=12^usrName=alice";
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(data, 0)?;
+        assert_eq!(left, "");
+        assert_eq!(
+            tdc.field("src_ip"),
+            Some(&DataField::from_chars("src_ip", "192.168.1.9"))
+        );
+        assert_eq!(tdc.field("cat"), Some(&DataField::from_digit("cat", 12)));
+        assert_eq!(
+            tdc.field("eid"),
+            Some(&DataField::from_chars("eid", "login"))
+        );
+        assert_eq!(
+            tdc.field("usrName"),
+            Some(&DataField::from_chars("usrName", "alice"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_leef_fail() {
+        let conf = WplField::try_parse("leef").assert();
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "CEF:0|a|b|c|d|e|1|");
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "LEEF:3.0|a|b|c|d|x=1");
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "LEEF:2.0|a|b|c|d|xZZ|x=1");
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut "LEEF:1.0|a|b|c|d|novalue");
+    }
+
+    #[test]
+    fn test_leef_gen_parse() -> AnyResult<()> {
+        let conf = WplField::try_parse("leef").assert();
+        let mut gnc = GenChannel::new();
+        let field = LeefP::default().patten_gen(&mut gnc, &conf, None)?;
+        let line = field.get_chars().unwrap_or_default().to_string();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut line.as_str())
+            .assert();
+        let record = DataRecord::from(out);
+        assert!(record.field("src").is_some());
+        assert!(record.field("usrName").is_some());
+        Ok(())
+    }
+}
@@ -1,19 +1,26 @@
 use crate::ast::WplSep;
 use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parser::physical::time::parse_time;
 use crate::parser::utils::{quot_r_str, quot_str, window_path};
+use std::net::IpAddr;
 use winnow::ascii::multispace0;
 use winnow::combinator::alt;
-use wp_model_core::model::DataField;
+use wp_model_core::model::{DataField, DataType};
 use wp_parser::Parser;
 use wp_parser::WResult as ModalResult;
 
 pub mod array;
 pub mod base64;
+pub mod cef;
+pub mod csv;
 pub mod json;
 pub mod json_exact;
 mod json_impl;
 pub mod keyval;
+pub mod leef;
 pub mod proto_text;
+pub mod syslog;
+pub mod xml;
 
 pub fn take_sub_tdo(
     fpu: &FieldEvalUnit,
@@ -51,3 +58,55 @@ pub fn take_sub_tdo(
         }
     }
 }
+
+/// Emit an already unescaped protocol value under `key`.
+/// A configured sub field parses the whole value; otherwise the value is typed as `meta`
+/// and kept as chars when it does not fit.
+pub fn take_sub_value(
+    fpu: &FieldEvalUnit,
+    key: &str,
+    val: &str,
+    meta: &DataType,
+    out: &mut Vec<DataField>,
+) -> ModalResult<()> {
+    if let Some(sub_fpu) = fpu.get_sub_fpu(key) {
+        let run_key = sub_fpu.conf().run_key(key);
+        let sep = WplSep::inherited_sep("\\0");
+        let mut val = val;
+        return sub_fpu.parse(&sep, &mut val, run_key, out);
+    }
+    let field = match meta {
+        DataType::Digit => val
+            .parse::<i64>()
+            .ok()
+            .map(|x| DataField::from_digit(key, x)),
+        DataType::Float => val
+            .parse::<f64>()
+            .ok()
+            .map(|x| DataField::from_float(key, x)),
+        DataType::IP => val
+            .parse::<IpAddr>()
+            .ok()
+            .map(|x| DataField::from_ip(key, x)),
+        DataType::Time => parse_time_value(val).map(|x| DataField::from_time(key, x)),
+        _ => None,
+    };
+    out.push(field.unwrap_or_else(|| DataField::from_chars(key, val)));
+    Ok(())
+}
+
+// 协议中的时间值：常见日期格式或 epoch 秒/毫秒
+fn parse_time_value(val: &str) -> Option<chrono::NaiveDateTime> {
+    if !val.is_empty() && val.bytes().all(|b| b.is_ascii_digit()) {
+        let ts = val.parse::<i64>().ok()?;
+        let dt = match val.len() {
+            10 => chrono::DateTime::from_timestamp(ts, 0),
+            13 => chrono::DateTime::from_timestamp_millis(ts),
+            _ => None,
+        };
+        return dt.map(|x| x.naive_utc());
+    }
+    let mut data = val;
+    let time = parse_time(&mut data).ok()?;
+    data.trim().is_empty().then_some(time)
+}
@@ -152,7 +152,9 @@ mod tests {
         let mut values = r#"message_type: 5 skyeye_dns {serial_num: "cc38f5254b86b145e36805689f09a829" access_time: "2023-09-20 18:56:28.605" sip: "192.168.23.100" sport: 48625 dip: "6.6.6.6" dport: 53 dns_type: 0 host: "ck2aapvgwp2ro9vu7c.org" vendor_id: "warppase.ai" device_ip: "10.48.56.215"}"#;
         let mut result = Vec::new();
         let sep = WplSep::default();
-        let WplStatementType::Express(rule) = conf.statement;
+        let WplStatementType::Express(rule) = conf.statement else {
+            panic!("not a rule")
+        };
         for f_conf in rule.group[0].fields.iter() {
             let fpu = FieldEvalUnit::for_test(ProtoTextP::default(), f_conf.clone());
             fpu.parse(&sep, &mut values, None, &mut result).assert();
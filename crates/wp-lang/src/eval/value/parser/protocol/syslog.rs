@@ -0,0 +1,505 @@
+use super::super::prelude::*;
+use chrono::{DateTime, NaiveDateTime};
+use once_cell::sync::Lazy;
+use rand::Rng;
+use wp_model_core::model::FNameStr;
+
+use crate::derive_base_prs;
+use crate::eval::TimeFmtConf;
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parse_def::PatternParser;
+use crate::eval::value::parser::physical::foundation::gen_chars;
+use crate::eval::value::parser::protocol::take_sub_value;
+
+derive_base_prs!(SyslogP);
+
+// RFC3164 时间戳不带年份，按当前时间推断
+static BSD_TIME: Lazy<TimeFmtConf> =
+    Lazy::new(|| TimeFmtConf::parse(r#""%b %e %H:%M:%S""#).expect("syslog time fmt"));
+
+const NIL: &str = "-";
+const BOM: char = '\u{feff}';
+
+impl PatternParser for SyslogP {
+    // 只解析 header，消息体留给后续字段：
+    // RFC5424 <PRI>VERSION TIMESTAMP HOSTNAME APP-NAME PROCID MSGID STRUCTURED-DATA [MSG]
+    // RFC3164 [<PRI>]Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG
+    fn pattern_parse(
+        &self,
+        fpu: &FieldEvalUnit,
+        _ups_sep: &WplSep,
+        data: &mut &str,
+        _name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
+        multispace0.parse_next(data)?;
+        let pri = take_pri(data)?;
+        if let Some(pri) = pri {
+            emit_pri(fpu, pri, out)?;
+        }
+        if pri.is_some() && take_version(data).is_some() {
+            parse_rfc5424(fpu, data, out)
+        } else {
+            parse_rfc3164(fpu, data, out)
+        }
+    }
+
+    fn patten_gen(
+        &self,
+        gnc: &mut GenChannel,
+        f_conf: &WplField,
+        _g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        let pri = gnc.rng.random_range(0..192);
+        let host = gen_chars(gnc, 6, true);
+        let app = gen_chars(gnc, 5, false);
+        let procid = gnc.rng.random_range(1..65535);
+        let event = gnc.rng.random_range(1..1000);
+        let line = format!(
+            r#"<{}>1 2023-10-11T22:14:15.003Z {} {} {} ID{} [origin ip="10.0.0.1"][meta seq="{}"]"#,
+            pri, host, app, procid, event, event
+        );
+        Ok(DataField::from_chars(f_conf.safe_name(), line))
+    }
+}
+
+fn parse_rfc5424(
+    fpu: &FieldEvalUnit,
+    data: &mut &str,
+    out: &mut Vec<DataField>,
+) -> ModalResult<()> {
+    let (ver, rest) = data.split_once(' ').unwrap_or((data, ""));
+    take_sub_value(fpu, "version", ver, &DataType::Digit, out)?;
+    *data = rest;
+    let ts = take_token(data, "syslog <timestamp>")?;
+    if ts != NIL {
+        let time = DateTime::parse_from_rfc3339(ts).ok();
+        emit_time(fpu, ts, time.map(|x| x.naive_local()), out)?;
+    }
+    for key in ["hostname", "app_name", "procid", "msgid"] {
+        let val = take_token(data, "syslog rfc5424 <header>")?;
+        if val != NIL {
+            take_sub_value(fpu, key, val, &DataType::Chars, out)?;
+        }
+    }
+    if data.starts_with(NIL) {
+        *data = &data[NIL.len()..];
+    } else {
+        if !data.starts_with('[') {
+            return fail
+                .context(ctx_desc("syslog <structured-data>"))
+                .parse_next(data);
+        }
+        while data.starts_with('[') {
+            take_sd_element(fpu, data, out)?;
+        }
+    }
+    if let Some(msg) = data.strip_prefix(' ') {
+        *data = msg.strip_prefix(BOM).unwrap_or(msg);
+    }
+    Ok(())
+}
+
+// [SD-ID *(SP PARAM-NAME="PARAM-VALUE")]，值内 \" \\ \] 需转义；输出 sd/<id>/<param>
+fn take_sd_element(
+    fpu: &FieldEvalUnit,
+    data: &mut &str,
+    out: &mut Vec<DataField>,
+) -> ModalResult<()> {
+    *data = &data[1..];
+    let id_end = data.find([' ', ']']).unwrap_or(data.len());
+    let sd_id = &data[..id_end];
+    if sd_id.is_empty() {
+        return fail.context(ctx_desc("syslog <sd-id>")).parse_next(data);
+    }
+    *data = &data[id_end..];
+    let mut has_param = false;
+    loop {
+        if let Some(rest) = data.strip_prefix(']') {
+            *data = rest;
+            break;
+        }
+        literal(' ')
+            .context(ctx_desc("syslog sd-param <sp>"))
+            .parse_next(data)?;
+        let Some((name, rest)) = data.split_once("=\"") else {
+            return fail
+                .context(ctx_desc("syslog sd-param <name>=\"<value>\""))
+                .parse_next(data);
+        };
+        *data = rest;
+        let val = take_param_value(data)?;
+        let key = format!("sd/{}/{}", sd_id, name);
+        take_sub_value(fpu, key.as_str(), val.as_str(), &DataType::Chars, out)?;
+        has_param = true;
+    }
+    if !has_param {
+        let key = format!("sd/{}", sd_id);
+        take_sub_value(fpu, key.as_str(), "", &DataType::Chars, out)?;
+    }
+    Ok(())
+}
+
+fn take_param_value(data: &mut &str) -> ModalResult<String> {
+    let mut val = String::new();
+    let mut chars = data.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                *data = &data[i + 1..];
+                return Ok(val);
+            }
+            '\\' => match chars.next() {
+                Some((_, x @ ('"' | '\\' | ']'))) => val.push(x),
+                Some((_, x)) => {
+                    val.push('\\');
+                    val.push(x);
+                }
+                None => val.push('\\'),
+            },
+            _ => val.push(c),
+        }
+    }
+    fail.context(ctx_desc("syslog sd-param unclosed <\">"))
+        .parse_next(data)
+}
+
+fn parse_rfc3164(
+    fpu: &FieldEvalUnit,
+    data: &mut &str,
+    out: &mut Vec<DataField>,
+) -> ModalResult<()> {
+    let beg = *data;
+    // 兼容 rsyslog 高精度模板：时间戳为 RFC3339
+    let rfc3339 = data
+        .split_once(' ')
+        .and_then(|(ts, rest)| DateTime::parse_from_rfc3339(ts).ok().map(|t| (t, rest)));
+    let time = match rfc3339 {
+        Some((time, rest)) => {
+            *data = rest;
+            time.naive_local()
+        }
+        None => BSD_TIME.parse_time(data)?,
+    };
+    emit_time(fpu, beg[..beg.len() - data.len()].trim(), Some(time), out)?;
+    multispace0.parse_next(data)?;
+    let host = take_token(data, "syslog <hostname>")?;
+    // 无 hostname 时第一个 token 即 TAG
+    if is_tag(host) {
+        return take_tag(fpu, host, out);
+    }
+    take_sub_value(fpu, "hostname", host, &DataType::Chars, out)?;
+    let end = data.find(' ').unwrap_or(data.len());
+    let tag = &data[..end];
+    if is_tag(tag) {
+        *data = data[end..].strip_prefix(' ').unwrap_or(&data[end..]);
+        take_tag(fpu, tag, out)?;
+    }
+    Ok(())
+}
+
+fn is_tag(token: &str) -> bool {
+    token.len() > 1 && token.ends_with(':')
+}
+
+// TAG[PID]:
+fn take_tag(fpu: &FieldEvalUnit, tag: &str, out: &mut Vec<DataField>) -> ModalResult<()> {
+    let tag = tag.trim_end_matches(':');
+    let (app, procid) = match tag.split_once('[') {
+        Some((app, pid)) => (app, pid.strip_suffix(']')),
+        None => (tag, None),
+    };
+    if !app.is_empty() {
+        take_sub_value(fpu, "app_name", app, &DataType::Chars, out)?;
+    }
+    if let Some(pid) = procid {
+        take_sub_value(fpu, "procid", pid, &DataType::Chars, out)?;
+    }
+    Ok(())
+}
+
+// 声明了 timestamp 子字段时按子字段解析原始文本
+fn emit_time(
+    fpu: &FieldEvalUnit,
+    raw: &str,
+    time: Option<NaiveDateTime>,
+    out: &mut Vec<DataField>,
+) -> ModalResult<()> {
+    match time {
+        Some(time) if fpu.get_sub_fpu("timestamp").is_none() => {
+            out.push(DataField::from_time("timestamp", time));
+            Ok(())
+        }
+        _ => take_sub_value(fpu, "timestamp", raw, &DataType::Chars, out),
+    }
+}
+
+fn take_pri(data: &mut &str) -> ModalResult<Option<u16>> {
+    let Some(rest) = data.strip_prefix('<') else {
+        return Ok(None);
+    };
+    let end = rest.find('>').unwrap_or(0);
+    match rest[..end].parse::<u16>() {
+        Ok(pri) if (1..=3).contains(&end) && pri < 192 => {
+            *data = &rest[end + 1..];
+            Ok(Some(pri))
+        }
+        _ => fail.context(ctx_desc("syslog <PRI>")).parse_next(data),
+    }
+}
+
+// RFC5424 在 PRI 后紧跟版本号与空格
+fn take_version(data: &str) -> Option<&str> {
+    let end = data.find(|c: char| !c.is_ascii_digit())?;
+    (end > 0 && end <= 2 && data[end..].starts_with(' ')).then(|| &data[..end])
+}
+
+fn take_token<'a>(data: &mut &'a str, what: &'static str) -> ModalResult<&'a str> {
+    let end = data.find(' ').unwrap_or(data.len());
+    if end == 0 {
+        return fail.context(ctx_desc(what)).parse_next(data);
+    }
+    let token = &data[..end];
+    *data = data[end..].strip_prefix(' ').unwrap_or(&data[end..]);
+    Ok(token)
+}
+
+fn emit_pri(fpu: &FieldEvalUnit, pri: u16, out: &mut Vec<DataField>) -> ModalResult<()> {
+    take_sub_value(fpu, "pri", pri.to_string().as_str(), &DataType::Digit, out)?;
+    take_sub_value(
+        fpu,
+        "facility",
+        facility_name(pri / 8),
+        &DataType::Chars,
+        out,
+    )?;
+    take_sub_value(
+        fpu,
+        "severity",
+        severity_name(pri % 8),
+        &DataType::Chars,
+        out,
+    )
+}
+
+fn facility_name(code: u16) -> &'static str {
+    match code {
+        0 => "kern",
+        1 => "user",
+        2 => "mail",
+        3 => "daemon",
+        4 => "auth",
+        5 => "syslog",
+        6 => "lpr",
+        7 => "news",
+        8 => "uucp",
+        9 => "clock",
+        10 => "authpriv",
+        11 => "ftp",
+        12 => "ntp",
+        13 => "audit",
+        14 => "alert",
+        15 => "cron",
+        16 => "local0",
+        17 => "local1",
+        18 => "local2",
+        19 => "local3",
+        20 => "local4",
+        21 => "local5",
+        22 => "local6",
+        23 => "local7",
+        _ => "unknown",
+    }
+}
+
+fn severity_name(code: u16) -> &'static str {
+    match code {
+        0 => "emerg",
+        1 => "alert",
+        2 => "crit",
+        3 => "err",
+        4 => "warn",
+        5 => "notice",
+        6 => "info",
+        7 => "debug",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use crate::types::AnyResult;
+    use chrono::{Datelike, NaiveDate};
+    use orion_error::TestAssert;
+    use wp_model_core::model::{DataRecord, Value};
+
+    #[test]
+    fn test_syslog_rfc5424() {
+        let line = format!(
+            r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application" eventID="1011"][examplePriority@32473 class="high" note="a \"b\" \]"] {}An application event"#,
+            BOM
+        );
+        let mut data = line.as_str();
+        let conf = WplField::try_parse("syslog").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "An application event");
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("pri"),
+            Some(&DataField::from_digit("pri", 165))
+        );
+        assert_eq!(
+            record.field("facility"),
+            Some(&DataField::from_chars("facility", "local4"))
+        );
+        assert_eq!(
+            record.field("severity"),
+            Some(&DataField::from_chars("severity", "notice"))
+        );
+        assert_eq!(
+            record.field("version"),
+            Some(&DataField::from_digit("version", 1))
+        );
+        let ts = NaiveDate::from_ymd_opt(2003, 10, 11)
+            .unwrap()
+            .and_hms_milli_opt(22, 14, 15, 3)
+            .unwrap();
+        assert_eq!(
+            record.field("timestamp"),
+            Some(&DataField::from_time("timestamp", ts))
+        );
+        assert_eq!(
+            record.field("hostname"),
+            Some(&DataField::from_chars("hostname", "mymachine.example.com"))
+        );
+        assert!(record.field("procid").is_none());
+        assert_eq!(
+            record.field("msgid"),
+            Some(&DataField::from_chars("msgid", "ID47"))
+        );
+        assert_eq!(
+            record.field("sd/exampleSDID@32473/eventID"),
+            Some(&DataField::from_chars(
+                "sd/exampleSDID@32473/eventID",
+                "1011"
+            ))
+        );
+        assert_eq!(
+            record.field("sd/examplePriority@32473/note"),
+            Some(&DataField::from_chars(
+                "sd/examplePriority@32473/note",
+                r#"a "b" ]"#
+            ))
+        );
+
+        let mut data = "<34>1 - - - - - -";
+        let conf = WplField::try_parse("syslog").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(out.len(), 4);
+    }
+
+    #[test]
+    fn test_syslog_rfc3164() {
+        let conf = WplField::try_parse("syslog").assert();
+        let mut data = "<34>Oct  1 22:14:15 mymachine su[230]: 'su root' failed";
+        let out = ParserTUnit::from_auto(conf.clone())
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "'su root' failed");
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("severity"),
+            Some(&DataField::from_chars("severity", "crit"))
+        );
+        assert_eq!(
+            record.field("hostname"),
+            Some(&DataField::from_chars("hostname", "mymachine"))
+        );
+        assert_eq!(
+            record.field("app_name"),
+            Some(&DataField::from_chars("app_name", "su"))
+        );
+        assert_eq!(
+            record.field("procid"),
+            Some(&DataField::from_chars("procid", "230"))
+        );
+        match record.field("timestamp").map(|x| x.get_value()) {
+            Some(Value::Time(t)) => assert_eq!((t.month(), t.day()), (10, 1)),
+            other => panic!("timestamp: {:?}", other),
+        }
+
+        // 无 PRI（文件日志）、无 TAG
+        let mut data = "Oct 11 22:14:15 fw01 kernel message";
+        let out = ParserTUnit::from_auto(conf.clone())
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "kernel message");
+        assert!(DataRecord::from(out).field("pri").is_none());
+
+        // rsyslog 高精度时间戳
+        let mut data = "<13>2023-10-11T22:14:15+08:00 host app: hello";
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "hello");
+        assert!(DataRecord::from(out).field("app_name").is_some());
+    }
+
+    #[test]
+    fn test_syslog_rule_sub_fields() -> AnyResult<()> {
+        let rule = r#"rule x { (syslog(chars@hostname:host, digit@sd/meta/seq:seq, digit@procid:pid), kv(digit@code)) }"#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(
+            r#"<14>1 2023-10-11T22:14:15Z fw01 app 4321 - [meta seq="9"] code=403"#,
+            0,
+        )?;
+        assert_eq!(left, "");
+        assert_eq!(
+            tdc.field("host"),
+            Some(&DataField::from_chars("host", "fw01"))
+        );
+        assert_eq!(tdc.field("seq"), Some(&DataField::from_digit("seq", 9)));
+        assert_eq!(tdc.field("pid"), Some(&DataField::from_digit("pid", 4321)));
+        assert_eq!(tdc.field("code"), Some(&DataField::from_digit("code", 403)));
+
+        // 同一规则处理 3164
+        let (tdc, left) = pipe.proc("<14>Oct 11 22:14:15 fw01 app[77]: code=200", 0)?;
+        assert_eq!(left, "");
+        assert_eq!(tdc.field("pid"), Some(&DataField::from_digit("pid", 77)));
+        assert_eq!(tdc.field("code"), Some(&DataField::from_digit("code", 200)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_syslog_fail() {
+        let conf = WplField::try_parse("syslog").assert();
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "<999>1 - - - - - -");
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut r#"<14>1 - h a p m [x k="v]"#);
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "<14>1 - h a p m oops");
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut "not a syslog line");
+    }
+
+    #[test]
+    fn test_syslog_gen_parse() -> AnyResult<()> {
+        let conf = WplField::try_parse("syslog").assert();
+        let mut gnc = GenChannel::new();
+        let field = SyslogP::default().patten_gen(&mut gnc, &conf, None)?;
+        let line = field.get_chars().unwrap_or_default().to_string();
+        let mut data = line.as_str();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "");
+        let record = DataRecord::from(out);
+        assert!(record.field("sd/origin/ip").is_some());
+        assert!(record.field("msgid").is_some());
+        Ok(())
+    }
+}
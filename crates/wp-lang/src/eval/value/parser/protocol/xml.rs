@@ -0,0 +1,356 @@
+use super::super::prelude::*;
+use rand::Rng;
+use std::collections::HashMap;
+use winnow::token::{take_till, take_until, take_while};
+use wp_model_core::model::FNameStr;
+
+use crate::derive_base_prs;
+use crate::eval::runtime::field::FieldEvalUnit;
+use crate::eval::value::parse_def::PatternParser;
+use crate::eval::value::parser::physical::foundation::gen_chars;
+use crate::eval::value::parser::protocol::take_sub_value;
+
+derive_base_prs!(XmlP);
+
+impl XmlP {
+    // 最大嵌套深度，防止极端输入导致栈溢出
+    const MAX_DEPTH: usize = 128;
+    #[inline]
+    fn max_depth(fpu: &FieldEvalUnit) -> usize {
+        if let Some(cnt) = fpu.conf().field_cnt() {
+            cnt
+        } else if let Some(len) = fpu.conf().length() {
+            *len
+        } else {
+            Self::MAX_DEPTH
+        }
+    }
+}
+
+#[derive(Default, Debug)]
+struct XmlNode {
+    name: String,
+    attrs: Vec<(String, String)>,
+    text: String,
+    children: Vec<XmlNode>,
+}
+
+impl PatternParser for XmlP {
+    fn pattern_parse(
+        &self,
+        fpu: &FieldEvalUnit,
+        _ups_sep: &WplSep,
+        data: &mut &str,
+        _name: FNameStr,
+        out: &mut Vec<DataField>,
+    ) -> ModalResult<()> {
+        skip_misc(data)?;
+        let root = parse_element(data, 1, Self::max_depth(fpu))?;
+        flatten_node(fpu, &root, root.name.as_str(), out)
+    }
+
+    fn patten_gen(
+        &self,
+        gnc: &mut GenChannel,
+        f_conf: &WplField,
+        _g_conf: Option<&FieldGenConf>,
+    ) -> AnyResult<DataField> {
+        let provider = gen_chars(gnc, 8, true);
+        let event_id = gnc.rng.random_range(1000..5000);
+        let user = gen_chars(gnc, 6, false);
+        let line = format!(
+            r#"<Event><System><Provider Name="{}"/><EventID>{}</EventID></System><EventData><Data Name="User">{}</Data></EventData></Event>"#,
+            provider, event_id, user
+        );
+        Ok(DataField::from_chars(f_conf.safe_name(), line))
+    }
+}
+
+// 元素、属性路径：a/b、a/b/@attr；同名兄弟元素按 a/b[0]、a/b[1] 区分
+fn flatten_node(
+    fpu: &FieldEvalUnit,
+    node: &XmlNode,
+    path: &str,
+    out: &mut Vec<DataField>,
+) -> ModalResult<()> {
+    for (k, v) in &node.attrs {
+        let key = format!("{}/@{}", path, k);
+        take_sub_value(fpu, key.as_str(), v, &DataType::Chars, out)?;
+    }
+    let text = node.text.trim();
+    if !text.is_empty() {
+        take_sub_value(fpu, path, text, &DataType::Chars, out)?;
+    }
+    let mut counter: HashMap<&str, (usize, usize)> = HashMap::new();
+    for child in &node.children {
+        counter.entry(child.name.as_str()).or_default().0 += 1;
+    }
+    for child in &node.children {
+        let (total, seen) = counter.entry(child.name.as_str()).or_default();
+        let child_path = if *total > 1 {
+            format!("{}/{}[{}]", path, child.name, seen)
+        } else {
+            format!("{}/{}", path, child.name)
+        };
+        *seen += 1;
+        flatten_node(fpu, child, child_path.as_str(), out)?;
+    }
+    Ok(())
+}
+
+// 跳过 XML 声明、注释、DOCTYPE 等根元素前的内容
+fn skip_misc(data: &mut &str) -> ModalResult<()> {
+    loop {
+        multispace0.parse_next(data)?;
+        if data.starts_with("<?") {
+            (literal("<?"), take_until(0.., "?>"), literal("?>")).parse_next(data)?;
+        } else if data.starts_with("<!--") {
+            skip_comment(data)?;
+        } else if data.starts_with("<!") {
+            (literal("<!"), take_till(0.., '>'), literal(">")).parse_next(data)?;
+        } else {
+            return Ok(());
+        }
+    }
+}
+
+fn skip_comment(data: &mut &str) -> ModalResult<()> {
+    (literal("<!--"), take_until(0.., "-->"), literal("-->"))
+        .context(ctx_desc("xml comment <!-- -->"))
+        .parse_next(data)?;
+    Ok(())
+}
+
+fn take_xml_name<'a>(data: &mut &'a str) -> ModalResult<&'a str> {
+    take_while(1.., |c: char| {
+        c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')
+    })
+    .context(ctx_desc("xml <name>"))
+    .parse_next(data)
+}
+
+fn parse_element(data: &mut &str, depth: usize, max_depth: usize) -> ModalResult<XmlNode> {
+    if depth > max_depth {
+        return fail
+            .context(ctx_desc("xml nested too deep"))
+            .parse_next(&mut "");
+    }
+    literal("<").context(ctx_desc("xml <")).parse_next(data)?;
+    let mut node = XmlNode {
+        name: take_xml_name.parse_next(data)?.to_string(),
+        ..Default::default()
+    };
+    loop {
+        multispace0.parse_next(data)?;
+        if data.starts_with("/>") {
+            literal("/>").parse_next(data)?;
+            return Ok(node);
+        }
+        if data.starts_with('>') {
+            literal(">").parse_next(data)?;
+            break;
+        }
+        let key = take_xml_name.parse_next(data)?;
+        (multispace0, literal("="), multispace0)
+            .context(ctx_desc("xml attribute <key>=<value>"))
+            .parse_next(data)?;
+        let val = alt((
+            delimited(literal("\""), take_till(0.., '"'), literal("\"")),
+            delimited(literal("'"), take_till(0.., '\''), literal("'")),
+        ))
+        .context(ctx_desc("xml attribute quoted value"))
+        .parse_next(data)?;
+        node.attrs.push((key.to_string(), unescape_xml(val)));
+    }
+    loop {
+        if data.is_empty() {
+            return fail
+                .context(ctx_desc("xml unclosed element"))
+                .parse_next(data);
+        }
+        if data.starts_with("</") {
+            literal("</").parse_next(data)?;
+            literal(node.name.as_str())
+                .context(ctx_desc("xml end tag mismatch"))
+                .parse_next(data)?;
+            (multispace0, literal(">")).parse_next(data)?;
+            return Ok(node);
+        } else if data.starts_with("<!--") {
+            skip_comment(data)?;
+        } else if data.starts_with("<![CDATA[") {
+            let cdata = delimited(literal("<![CDATA["), take_until(0.., "]]>"), literal("]]>"))
+                .context(ctx_desc("xml cdata"))
+                .parse_next(data)?;
+            node.text.push_str(cdata);
+        } else if data.starts_with("<?") {
+            (literal("<?"), take_until(0.., "?>"), literal("?>")).parse_next(data)?;
+        } else if data.starts_with('<') {
+            node.children
+                .push(parse_element(data, depth + 1, max_depth)?);
+        } else {
+            let text = take_till(1.., '<').parse_next(data)?;
+            node.text.push_str(unescape_xml(text).as_str());
+        }
+    }
+}
+
+fn unescape_xml(val: &str) -> String {
+    if !val.contains('&') {
+        return val.to_string();
+    }
+    let mut buf = String::with_capacity(val.len());
+    let mut rest = val;
+    while let Some(pos) = rest.find('&') {
+        buf.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let ch = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|x| x.ok())
+                .and_then(char::from_u32),
+        };
+        match ch {
+            Some(c) => {
+                buf.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                buf.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    buf.push_str(rest);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::runtime::vm_unit::WplEvaluator;
+    use crate::eval::value::test_utils::ParserTUnit;
+    use crate::types::AnyResult;
+    use orion_error::TestAssert;
+    use wp_model_core::model::DataRecord;
+
+    #[test]
+    fn test_xml_flatten() {
+        let mut data = r#"<?xml version="1.0"?><Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event"><System><Provider Name="Microsoft-Windows-Security-Auditing" Guid="{54849625}"/><EventID>4624</EventID><!-- logon --><TimeCreated SystemTime="2023-05-15T09:22:44.123Z"/></System><EventData><Data Name="SubjectUserSid">S-1-5-18</Data><Data Name="TargetUserName">bob &amp; alice</Data></EventData></Event> tail"#;
+        let conf = WplField::try_parse("xml").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        assert_eq!(data, "tail");
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("Event/System/Provider/@Name"),
+            Some(&DataField::from_chars(
+                "Event/System/Provider/@Name",
+                "Microsoft-Windows-Security-Auditing"
+            ))
+        );
+        assert_eq!(
+            record.field("Event/System/EventID"),
+            Some(&DataField::from_chars("Event/System/EventID", "4624"))
+        );
+        assert_eq!(
+            record.field("Event/EventData/Data[0]/@Name"),
+            Some(&DataField::from_chars(
+                "Event/EventData/Data[0]/@Name",
+                "SubjectUserSid"
+            ))
+        );
+        assert_eq!(
+            record.field("Event/EventData/Data[1]"),
+            Some(&DataField::from_chars(
+                "Event/EventData/Data[1]",
+                "bob & alice"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_xml_cdata_and_entities() {
+        let mut data = r#"<audit op='login' user="a&quot;b"><msg><![CDATA[<b>raw</b>]]></msg><code>&#65;&#x42;</code></audit>"#;
+        let conf = WplField::try_parse("xml").assert();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut data)
+            .assert();
+        let record = DataRecord::from(out);
+        assert_eq!(
+            record.field("audit/@user"),
+            Some(&DataField::from_chars("audit/@user", "a\"b"))
+        );
+        assert_eq!(
+            record.field("audit/msg"),
+            Some(&DataField::from_chars("audit/msg", "<b>raw</b>"))
+        );
+        assert_eq!(
+            record.field("audit/code"),
+            Some(&DataField::from_chars("audit/code", "AB"))
+        );
+    }
+
+    #[test]
+    fn test_xml_sub_fields() -> AnyResult<()> {
+        let rule = r#"rule wef { (chars:host, xml(digit@Event/System/EventID:event_id, chars@Event/System/Provider/@Name:provider)) }"#;
+        let data = r#"dc01 <Event><System><Provider Name="Security"/><EventID>4625</EventID></System></Event>"#;
+        let pipe = WplEvaluator::from_code(rule)?;
+        let (tdc, left) = pipe.proc(data, 0)?;
+        assert_eq!(left, "");
+        assert_eq!(
+            tdc.field("event_id"),
+            Some(&DataField::from_digit("event_id", 4625))
+        );
+        assert_eq!(
+            tdc.field("provider"),
+            Some(&DataField::from_chars("provider", "Security"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_xml_depth_limit() {
+        let conf = WplField::try_parse("xml").assert();
+        ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut "<a><b><c>1</c></b></a>")
+            .assert();
+        let conf = WplField::try_parse("xml[2]").assert();
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut "<a><b><c>1</c></b></a>");
+    }
+
+    #[test]
+    fn test_xml_fail() {
+        let conf = WplField::try_parse("xml").assert();
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "<a><b>1</a>");
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "<a>1");
+        ParserTUnit::from_auto(conf.clone()).verify_parse_fail(&mut "<a x=1></a>");
+        ParserTUnit::from_auto(conf).verify_parse_fail(&mut "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_xml_gen_parse() -> AnyResult<()> {
+        let conf = WplField::try_parse("xml").assert();
+        let mut gnc = GenChannel::new();
+        let field = XmlP::default().patten_gen(&mut gnc, &conf, None)?;
+        let line = field.get_chars().unwrap_or_default().to_string();
+        let out = ParserTUnit::from_auto(conf)
+            .verify_parse_suc(&mut line.as_str())
+            .assert();
+        let record = DataRecord::from(out);
+        assert!(record.field("Event/System/EventID").is_some());
+        assert!(record.field("Event/EventData/Data").is_some());
+        Ok(())
+    }
+}
@@ -159,6 +159,15 @@ impl ParserTUnit {
     pub fn verify_gen_parse_suc(&mut self) {
         verify_gen_parse(&mut self.env, &self.fpu, self.fpu.conf());
     }
+    // 不支持生成的字段应返回错误而不是 panic
+    pub fn verify_gen_unsupported(&mut self) {
+        let cur_sep = WplSep::default();
+        assert!(
+            self.fpu
+                .generate(&mut self.env.gch, &cur_sep, None)
+                .is_err()
+        );
+    }
 }
 
 pub fn verify_gen_parse(env: &mut ParserTestEnv, fpu: &FieldEvalUnit, conf: &WplField) {
@@ -0,0 +1,521 @@
+//! WPL 源码格式化
+//!
+//! 基于 AST 的 `DebugFormat` 输出规范格式，并将源码中的整行注释重新挂回：
+//! 紧邻语句（`package`/`rule`/`fields`/`test`/`use`，可隔着 `#[...]` 注解）之前的注释作为该语句的前导注释，
+//! 包尾/文件尾注释保留在原处；块注释仅调整首行缩进，其余内容原样输出。
+//! 语句内部（或注解与语句之间）的注释无法原位保留，此时报错而不改写。
+//! 输出前会重新解析并与原 AST 比对，不一致时报错而不改写。
+
+use std::path::PathBuf;
+
+use wp_parser::Parser;
+
+use crate::ast::debug::DebugFormat;
+use crate::ast::{WplCode, WplPackage, WplRule, WplStatementType};
+use crate::parser::error::{WplCodeError, WplCodeReason, WplCodeResult, error_detail};
+use crate::parser::wpl_anno::ann_fun;
+use crate::parser::wpl_pkg::wpl_package_src;
+use crate::parser::wpl_rule::wpl_rule;
+
+const INDENT: &str = "  ";
+const HEADERS: [&str; 5] = ["package", "rule", "fields", "test", "use"];
+
+/// 返回规范格式的源码
+pub fn format_code(code: &str) -> WplCodeResult<String> {
+    let source = WplSource::parse(code)?;
+    let comments = Comments::collect(code, &source)?;
+    let formatted = source.render(&comments);
+    let again = WplSource::parse(&formatted)?;
+    if again != source {
+        return Err(fmt_error(
+            "formatted code is not equivalent to the source".to_string(),
+        ));
+    }
+    Ok(formatted)
+}
+
+/// 源码是否已是规范格式（`--check`）
+pub fn is_formatted(code: &str) -> WplCodeResult<bool> {
+    Ok(format_code(code)? == code)
+}
+
+fn fmt_error(msg: String) -> WplCodeError {
+    WplCodeError::from(WplCodeReason::UnSupport(format!("wpl fmt: {}", msg)))
+}
+
+/// 文件内容：包，或单条规则
+#[derive(Debug)]
+enum WplSource {
+    Package(WplPackage),
+    Rule(WplRule),
+}
+
+impl PartialEq for WplSource {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (WplSource::Package(a), WplSource::Package(b)) => {
+                a.name == b.name
+                    && a.tags == b.tags
+                    && a.rules == b.rules
+                    && canonical_decls(a) == canonical_decls(b)
+            }
+            (WplSource::Rule(a), WplSource::Rule(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+// 输出时样例测试统一移到规则之后，比对时忽略这一顺序变化
+fn canonical_decls(pkg: &WplPackage) -> (Vec<&WplRule>, Vec<&WplRule>) {
+    pkg.decls
+        .iter()
+        .partition(|x| !matches!(x.statement, WplStatementType::Test(_)))
+}
+
+impl WplSource {
+    fn parse(code: &str) -> WplCodeResult<Self> {
+        let pure = WplCode::build(PathBuf::new(), code)?;
+        let pure = pure.get_code().as_str();
+        // 以 rule 开头的文件按单条规则解析，错误信息也取自规则解析
+        let result = if head_keyword(pure) == Some("rule") {
+            wpl_rule.parse(pure).map(WplSource::Rule)
+        } else {
+            wpl_package_src.parse(pure).map(WplSource::Package)
+        };
+        result.map_err(|err| WplCodeError::from(WplCodeReason::Syntax(error_detail(err))))
+    }
+
+    fn render(&self, comments: &Comments) -> String {
+        let mut out = String::new();
+        push_comments(&mut out, &comments.file_head, 0);
+        match self {
+            WplSource::Rule(rule) => {
+                push_comments(&mut out, comments.leading(StmtRef::Rule(0)), 0);
+                for line in rule.to_string().lines() {
+                    out.push_str(line.strip_prefix(INDENT).unwrap_or(line));
+                    out.push('\n');
+                }
+            }
+            WplSource::Package(pkg) => {
+                if let Some(tags) = &pkg.tags {
+                    out.push_str(&tags.fmt_string().unwrap_or_default());
+                }
+                out.push_str(&format!("package {} {{\n", pkg.name));
+                // 声明在前、规则居中、样例测试在后
+                let (tests, decls): (Vec<_>, Vec<_>) = pkg
+                    .decls
+                    .iter()
+                    .enumerate()
+                    .partition(|(_, x)| matches!(x.statement, WplStatementType::Test(_)));
+                let stmts = decls
+                    .into_iter()
+                    .map(|(idx, x)| (StmtRef::Decl(idx), x))
+                    .chain(
+                        pkg.rules
+                            .iter()
+                            .enumerate()
+                            .map(|(idx, x)| (StmtRef::Rule(idx), x)),
+                    )
+                    .chain(tests.into_iter().map(|(idx, x)| (StmtRef::Decl(idx), x)));
+                let mut prev_use = None;
+                for (stmt, rule) in stmts {
+                    let is_use = matches!(rule.statement, WplStatementType::Use(_));
+                    let leading = comments.leading(stmt);
+                    // 连续的 use 不空行
+                    if prev_use.is_some()
+                        && !(is_use && prev_use == Some(true) && leading.is_empty())
+                    {
+                        out.push('\n');
+                    }
+                    push_comments(&mut out, leading, 1);
+                    out.push_str(&rule.to_string());
+                    out.push('\n');
+                    prev_use = Some(is_use);
+                }
+                if !comments.pkg_tail.is_empty() {
+                    out.push('\n');
+                    push_comments(&mut out, &comments.pkg_tail, 1);
+                }
+                out.push_str("}\n");
+            }
+        }
+        push_comments(&mut out, &comments.file_tail, 0);
+        out
+    }
+}
+
+// 多行块注释只缩进首行，后续行原样输出
+fn push_comments(out: &mut String, lines: &[String], depth: usize) {
+    for line in lines {
+        out.push_str(&INDENT.repeat(depth));
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StmtRef {
+    Package,
+    Rule(usize),
+    Decl(usize),
+}
+
+/// 源码行：整行注释（首行已去除缩进，块注释的后续行保持原样）或代码
+enum Line {
+    Comment(String),
+    Code(String),
+}
+
+#[derive(Default)]
+struct Comments {
+    file_head: Vec<String>,
+    stmts: Vec<(StmtRef, Vec<String>)>,
+    pkg_tail: Vec<String>,
+    file_tail: Vec<String>,
+}
+
+impl Comments {
+    fn leading(&self, stmt: StmtRef) -> &[String] {
+        self.stmts
+            .iter()
+            .find(|(x, _)| *x == stmt)
+            .map(|(_, x)| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn add(&mut self, stmt: StmtRef, mut lines: Vec<String>) {
+        match self.stmts.iter_mut().find(|(x, _)| *x == stmt) {
+            Some((_, exist)) => exist.append(&mut lines),
+            None => self.stmts.push((stmt, lines)),
+        }
+    }
+
+    fn collect(code: &str, source: &WplSource) -> WplCodeResult<Self> {
+        let lines = split_lines(code);
+        let heads = stmt_heads(&lines, source)?;
+        let mut comments = Comments::default();
+        let mut current = None;
+        for (pos, line) in lines.iter().enumerate() {
+            let Line::Comment(text) = line else {
+                if let Some(stmt) = heads[pos] {
+                    current = Some(stmt);
+                }
+                continue;
+            };
+            let rest: Vec<&str> = lines[pos + 1..]
+                .iter()
+                .filter_map(|x| match x {
+                    Line::Code(code) => Some(code.as_str()),
+                    Line::Comment(_) => None,
+                })
+                .collect();
+            let next_head = lines[pos + 1..]
+                .iter()
+                .zip(&heads[pos + 1..])
+                .filter(|(x, _)| matches!(x, Line::Code(code) if !is_annotation(code)))
+                .map(|(_, head)| *head)
+                .next()
+                .flatten();
+            let after_anno = lines[..pos]
+                .iter()
+                .rev()
+                .find_map(|x| match x {
+                    Line::Code(code) => Some(is_annotation(code)),
+                    Line::Comment(_) => None,
+                })
+                .unwrap_or(false);
+            let text = vec![text.clone()];
+            match (next_head, current) {
+                (Some(_), _) if after_anno => return Err(misplaced_comment(&text[0])),
+                (Some(StmtRef::Package), _) => comments.file_head.extend(text),
+                (Some(stmt), _) => comments.add(stmt, text),
+                (None, _) if rest.is_empty() => comments.file_tail.extend(text),
+                (None, Some(StmtRef::Package)) => comments.pkg_tail.extend(text),
+                (None, Some(_)) if rest.len() == 1 && source.is_package() => {
+                    comments.pkg_tail.extend(text)
+                }
+                (None, Some(_)) => return Err(misplaced_comment(&text[0])),
+                (None, None) => comments.file_head.extend(text),
+            }
+        }
+        Ok(comments)
+    }
+}
+
+// 语句内部的注释无法挂回原处，拒绝格式化以免移动注释
+fn misplaced_comment(text: &str) -> WplCodeError {
+    fmt_error(format!(
+        "comment inside a statement is not supported, move it above the statement: {}",
+        text.lines().next().unwrap_or_default()
+    ))
+}
+
+impl WplSource {
+    fn is_package(&self) -> bool {
+        matches!(self, WplSource::Package(_))
+    }
+
+    // 与源码顺序对应的语句关键字
+    fn keywords(&self) -> Vec<(&'static str, StmtRef)> {
+        let mut out = Vec::new();
+        match self {
+            WplSource::Rule(_) => out.push(("rule", StmtRef::Rule(0))),
+            WplSource::Package(pkg) => {
+                out.push(("package", StmtRef::Package));
+                for (idx, decl) in pkg.decls.iter().enumerate() {
+                    let keyword = match decl.statement {
+                        WplStatementType::Fields(_) => "fields",
+                        WplStatementType::Test(_) => "test",
+                        WplStatementType::Use(_) => "use",
+                        WplStatementType::Express(_) => "rule",
+                    };
+                    out.push((keyword, StmtRef::Decl(idx)));
+                }
+                out.extend((0..pkg.rules.len()).map(|idx| ("rule", StmtRef::Rule(idx))));
+            }
+        }
+        out
+    }
+}
+
+// 每个代码行若为语句头则给出对应语句；规则与声明各自按出现顺序对应
+fn stmt_heads(lines: &[Line], source: &WplSource) -> WplCodeResult<Vec<Option<StmtRef>>> {
+    let keywords = source.keywords();
+    let mut rules = keywords.iter().filter(|(k, _)| *k == "rule");
+    let mut decls = keywords
+        .iter()
+        .filter(|(k, _)| !matches!(*k, "rule" | "package"));
+    let mut package = keywords.iter().filter(|(k, _)| *k == "package");
+    let mut heads = Vec::with_capacity(lines.len());
+    for line in lines {
+        let head = match line {
+            Line::Code(code) => head_keyword(code),
+            Line::Comment(_) => None,
+        };
+        let stmt = match head {
+            None => None,
+            Some(keyword) => {
+                let found = match keyword {
+                    "package" => package.next(),
+                    "rule" => rules.next(),
+                    _ => decls.next(),
+                };
+                match found {
+                    Some((k, stmt)) if *k == keyword => Some(*stmt),
+                    _ => {
+                        return Err(fmt_error(format!(
+                            "cannot place comments, unexpected `{}` line",
+                            keyword
+                        )));
+                    }
+                }
+            }
+        };
+        heads.push(stmt);
+    }
+    Ok(heads)
+}
+
+fn is_annotation(code: &str) -> bool {
+    code.starts_with("#[") && head_keyword(code).is_none()
+}
+
+// 行首（可在 `#[...]` 之后）的语句关键字
+fn head_keyword(code: &str) -> Option<&'static str> {
+    let mut code = code.trim_start();
+    if code.starts_with("#[") {
+        ann_fun.parse_next(&mut code).ok()?;
+        code = code.trim_start();
+    }
+    HEADERS.into_iter().find(|keyword| {
+        code.strip_prefix(keyword)
+            .and_then(|x| x.chars().next())
+            .is_some_and(char::is_whitespace)
+    })
+}
+
+// 与 CommentParser 相同的注释识别规则：`//` 与 `/* .. */` 只在行首（空白之后）生效，
+// 块注释到以 `*/` 开头的行结束，该行其余部分仍是代码
+fn split_lines(code: &str) -> Vec<Line> {
+    let mut out = Vec::new();
+    let mut block: Option<String> = None;
+    for raw in code.lines() {
+        let line = raw.trim();
+        if let Some(mut text) = block.take() {
+            text.push('\n');
+            match line.strip_prefix("*/") {
+                Some(rest) => {
+                    // 保留 `*/` 及其前面的原始缩进
+                    let end = raw.len() - raw.trim_start().len() + 2;
+                    text.push_str(&raw[..end]);
+                    out.push(Line::Comment(text));
+                    if !rest.trim().is_empty() {
+                        out.push(Line::Code(rest.trim().to_string()));
+                    }
+                }
+                None => {
+                    text.push_str(raw);
+                    block = Some(text);
+                }
+            }
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with("//") {
+            out.push(Line::Comment(line.to_string()));
+        } else if line.starts_with("/*") {
+            block = Some(line.to_string());
+        } else {
+            out.push(Line::Code(line.to_string()));
+        }
+    }
+    if let Some(text) = block {
+        out.push(Line::Comment(text));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orion_error::TestAssert;
+
+    fn assert_stable(code: &str) -> String {
+        let formatted = format_code(code).assert();
+        assert_eq!(format_code(&formatted).assert(), formatted);
+        assert!(is_formatted(&formatted).assert());
+        formatted
+    }
+
+    #[test]
+    fn test_fmt_package() {
+        let code = r##"
+// nginx access log
+#[tag(dev:"nginx", note:r#"a "b""#)]
+package   /nginx/ {
+#[tag(kind:"access")]
+  rule   access {
+ |decode/base64|(ip:sip,_^2,time/clf:time<[,]>,http/request",digit:status\,,chars:msg\0)
+  }
+   // shared fields
+ fields  ua { (chars:agent\s) }
+  test access { sample = "MTAuMC4wLjE=" expect { ip:sip = "10.0.0.1" } }
+  use   /base/addr
+}
+"##;
+        let formatted = assert_stable(code);
+        assert_eq!(
+            formatted,
+            r##"// nginx access log
+#[tag(dev:"nginx", note:r#"a "b""#)]
+package /nginx/ {
+  // shared fields
+  fields ua {
+    (
+      chars:agent\s
+    )
+  }
+
+  use /base/addr
+
+  #[tag(kind:"access")]
+  rule access {
+    |decode/base64|
+    (
+      ip:sip,
+      _^2,
+      time/clf:time<[,]>,
+      http/request",
+      digit:status\,,
+      chars:msg\0
+    )
+  }
+
+  test access {
+    sample = "MTAuMC4wLjE="
+    expect {
+      ip:sip = "10.0.0.1"
+    }
+  }
+}
+"##
+        );
+        assert!(!is_formatted(code).assert());
+    }
+
+    #[test]
+    fn test_fmt_comment_placement() {
+        let code = r#"package demo {
+  /* first
+   * rule
+   */
+  rule a {
+    (digit:id | digit_in([1,2]), json(chars@name:user, opt(digit)@age) | f_has(name))
+  }
+  // before b
+  rule b { alt(ip:sip,digit:id)[10]\, }
+  // end of package
+}
+// end of file
+"#;
+        let formatted = assert_stable(code);
+        assert_eq!(
+            formatted,
+            r#"package demo {
+  /* first
+   * rule
+   */
+  rule a {
+    (
+      digit:id | digit_in([1,2]),
+      json(@name:user, opt(digit)@age) | f_has(name)
+    )
+  }
+
+  // before b
+  rule b {
+    alt(
+      ip:sip,
+      digit:id
+    )[10]\,
+  }
+
+  // end of package
+}
+// end of file
+"#
+        );
+    }
+
+    #[test]
+    fn test_fmt_refuse_inner_comment() {
+        // 语句内部与注解之后的注释无法原位保留，拒绝改写
+        let codes = [
+            "package demo {\n  rule a {\n    // inside a\n    (digit:id)\n  }\n}\n",
+            "package demo {\n  rule a {\n    (digit:id)\n    // tail of a\n  }\n  rule b { (ip) }\n}\n",
+            "package demo {\n  #[tag(kind:\"a\")]\n  // after tag\n  rule a { (digit) }\n}\n",
+            "rule x {\n  /* inside\n  */\n  (digit)\n}\n",
+        ];
+        for code in codes {
+            let err = format_code(code).expect_err(code).to_string();
+            assert!(err.contains("comment inside a statement"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_fmt_bare_rule() {
+        let formatted =
+            assert_stable("// cli rule\nrule x { (symbol(<190>)[5], peek_symbol(a), _\\!) }");
+        assert_eq!(
+            formatted,
+            "// cli rule\nrule x {\n  (\n    symbol(<190>)[5],\n    peek_symbol(a),\n    _\\!\n  )\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_fmt_syntax_error() {
+        assert!(format_code("package x { rule a { (digit } }").is_err());
+    }
+}
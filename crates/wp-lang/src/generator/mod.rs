@@ -1,8 +1,10 @@
 mod fmt;
+mod proto_bin;
 mod rule;
 
 pub use fmt::{CSVGenFmt, JsonGenFmt, KVGenFmt, ProtoGenFmt, RAWGenFmt};
 pub use fmt::{FmtField, FmtFieldVec, GenChannel, ParserValue, record_from_fmt_fields};
+pub use proto_bin::{ProtoBinFmt, WpField, WpRecord, WpValue};
 pub use rule::FieldGenBuilder;
 pub use rule::FieldGenConf;
 pub use rule::FieldsGenRule;
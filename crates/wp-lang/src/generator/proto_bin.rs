@@ -0,0 +1,122 @@
+//! 二进制 protobuf 输出（`TextFmt::Proto`）。记录按以下固定结构编码，每条消息带 varint 长度前缀
+//! （与 `writeDelimitedTo` 一致），便于在文件/流中连续写出：
+//!
+//! ```proto
+//! message WpRecord { repeated WpField fields = 1; }
+//! message WpField {
+//!   string name = 1;
+//!   string meta = 2;
+//!   oneof value { string chars = 3; int64 digit = 4; double float = 5; bool bool = 6; }
+//!   repeated WpField items = 7; // obj/array 子字段
+//! }
+//! ```
+use prost::Message;
+use wp_model_core::model::{DataField, DataRecord, Value};
+
+use super::fmt::{FmtFieldVec, record_from_fmt_fields};
+
+pub struct ProtoBinFmt<T>(pub T);
+
+#[derive(Clone, PartialEq, Message)]
+pub struct WpRecord {
+    #[prost(message, repeated, tag = "1")]
+    pub fields: Vec<WpField>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct WpField {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub meta: String,
+    #[prost(oneof = "WpValue", tags = "3, 4, 5, 6")]
+    pub value: Option<WpValue>,
+    #[prost(message, repeated, tag = "7")]
+    pub items: Vec<WpField>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum WpValue {
+    #[prost(string, tag = "3")]
+    Chars(String),
+    #[prost(int64, tag = "4")]
+    Digit(i64),
+    #[prost(double, tag = "5")]
+    Float(f64),
+    #[prost(bool, tag = "6")]
+    Bool(bool),
+}
+
+impl From<&DataField> for WpField {
+    fn from(field: &DataField) -> Self {
+        let mut out = WpField {
+            name: field.get_name().to_string(),
+            meta: field.get_meta().to_string(),
+            value: None,
+            items: Vec::new(),
+        };
+        match field.get_value() {
+            Value::Null | Value::Ignore(_) => {}
+            Value::Bool(x) => out.value = Some(WpValue::Bool(*x)),
+            Value::Digit(x) => out.value = Some(WpValue::Digit(*x)),
+            Value::Float(x) => out.value = Some(WpValue::Float(*x)),
+            Value::Chars(x) => out.value = Some(WpValue::Chars(x.to_string())),
+            Value::Obj(obj) => out.items = obj.0.values().map(WpField::from).collect(),
+            Value::Array(arr) => out.items = arr.iter().map(WpField::from).collect(),
+            other => out.value = Some(WpValue::Chars(other.to_string())),
+        }
+        out
+    }
+}
+
+impl From<&DataRecord> for WpRecord {
+    fn from(record: &DataRecord) -> Self {
+        WpRecord {
+            fields: record.items.iter().map(WpField::from).collect(),
+        }
+    }
+}
+
+impl ProtoBinFmt<&DataRecord> {
+    /// 编码为带长度前缀的 `WpRecord`
+    pub fn encode(&self) -> Vec<u8> {
+        WpRecord::from(self.0).encode_length_delimited_to_vec()
+    }
+}
+
+impl ProtoBinFmt<&FmtFieldVec> {
+    pub fn encode(&self) -> Vec<u8> {
+        ProtoBinFmt(&record_from_fmt_fields(self.0.clone())).encode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn test_proto_bin_roundtrip() {
+        let record = DataRecord::from(vec![
+            DataField::from_chars("host", "web-01"),
+            DataField::from_digit("code", 500),
+            DataField::from_float("cost", 0.25),
+            DataField::from_ip("sip", IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        ]);
+        let bytes = ProtoBinFmt(&record).encode();
+        let decoded = WpRecord::decode_length_delimited(bytes.as_slice()).unwrap();
+        assert_eq!(decoded.fields.len(), 4);
+        assert_eq!(
+            decoded.fields[0].value,
+            Some(WpValue::Chars("web-01".into()))
+        );
+        assert_eq!(decoded.fields[1].name, "code");
+        assert_eq!(decoded.fields[1].value, Some(WpValue::Digit(500)));
+        assert_eq!(decoded.fields[2].value, Some(WpValue::Float(0.25)));
+        assert_eq!(decoded.fields[3].meta, "ip");
+        assert_eq!(
+            decoded.fields[3].value,
+            Some(WpValue::Chars("10.0.0.1".into()))
+        );
+    }
+}
@@ -10,7 +10,8 @@ pub mod eval;
 pub mod parser;
 #[macro_use]
 pub mod macro_def;
-//mod checker;
+pub mod checker;
+pub mod formatter;
 pub mod generator;
 mod pkg;
 pub mod precompile;
@@ -24,10 +25,11 @@ pub use ast::WplCode;
 pub use ast::WplRule;
 pub use ast::WplSep;
 pub use ast::WplStatementType;
-pub use ast::ann_func::{AnnotationFunc, AnnotationType};
+pub use ast::ann_func::{AnnotationFunc, AnnotationType, SourceGuard};
 pub use ast::{WplExpress, WplPackage, WplPkgMeta};
 pub use eval::DataTypeParser;
 pub use eval::OPTIMIZE_TIMES;
+pub use eval::ParseExplain;
 pub use eval::PipeLineResult;
 pub use eval::WplEvaluator;
 pub use eval::builtins::registry::{
@@ -39,7 +41,7 @@ pub use eval::builtins::registry::{
 pub use eval::{WparseError, WparseReason, WparseResult};
 pub use parser::error::error_detail;
 pub use parser::parse_code::wpl_express;
-pub use parser::wpl_pkg::wpl_package;
+pub use parser::wpl_pkg::{resolve_pkg_refs, wpl_package};
 pub use pkg::DEFAULT_KEY;
 pub use pkg::PkgID;
 pub use pkg::gen_pkg_id;
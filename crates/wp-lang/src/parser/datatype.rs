@@ -31,6 +31,56 @@ pub fn take_datatype_impl(data: &mut &str) -> WResult<DataType> {
     }
 }
 
+/// CEF (ArcSight Common Event Format) protocol type.
+pub const CEF: &str = "cef";
+/// LEEF 1.0/2.0 (IBM QRadar Log Event Extended Format) protocol type.
+pub const LEEF: &str = "leef";
+/// XML document flattened into `a/b/@attr` paths.
+pub const XML: &str = "xml";
+/// RFC 4180 delimited record (CSV/TSV).
+pub const CSV: &str = "csv";
+/// Syslog header (RFC3164/RFC5424); the message body is left to the following fields.
+pub const SYSLOG: &str = "syslog";
+/// Time with an explicit strftime format: `time/fmt("%Y%m%d %H%M%S"[, "+08:00"])`.
+pub const TIME_FMT: &str = "time/fmt";
+/// Regex with named capture groups: `regex(r#"(?P<ip>\S+) (?P<code>\d+)"#)`.
+pub const REGEX: &str = "regex";
+/// Grok expression expanded from the pattern library: `grok("%{IP:client} %{INT:code:int}")`.
+pub const GROK: &str = "grok";
+/// User-agent decomposed into browser/os/device via the uap-core regex database.
+pub const HTTP_UA: &str = "http/ua";
+
+/// WPL protocol types without a `DataType` variant of their own.
+/// Fields of these types are carried as `DataType::Obj`; the parser is resolved from `meta_name`.
+pub const WPL_PROTO_TYPES: &[&str] = &[CEF, LEEF, XML, CSV, SYSLOG, TIME_FMT, REGEX, GROK, HTTP_UA];
+
+/// Resolve a WPL field type name into `(DataType, meta_name)`.
+pub fn wpl_meta_from(name: &str) -> Option<(DataType, FNameStr)> {
+    if let Ok(meta) = DataType::from(name) {
+        let meta_name = FNameStr::from(meta.static_name());
+        return Some((meta, meta_name));
+    }
+    WPL_PROTO_TYPES
+        .iter()
+        .find(|x| **x == name)
+        .map(|x| (DataType::Obj, FNameStr::from(*x)))
+}
+
+pub fn take_field_meta(data: &mut &str) -> WResult<(DataType, FNameStr)> {
+    let _ = multispace0.parse_next(data)?;
+    let cp = data.checkpoint();
+    let meta_str = take_meta_name
+        .context(StrContext::Label("<datatype>"))
+        .parse_next(data)?;
+    if let Some(meta) = wpl_meta_from(meta_str) {
+        Ok(meta)
+    } else {
+        data.reset(&cp);
+        fail.context(ctx_desc("DataType from str fail"))
+            .parse_next(&mut "")
+    }
+}
+
 pub fn field_ins<N: Into<FNameStr>, V: Into<SmolStr> + Display>(
     meta: DataType,
     name: N,
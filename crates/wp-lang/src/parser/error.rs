@@ -97,6 +97,9 @@ pub enum WplCodeReason {
     #[from(skip)]
     #[error("unsupport > {0}")]
     UnSupport(String),
+    #[from(skip)]
+    #[error("reference error > {0}")]
+    Reference(String),
     #[error("{0}")]
     Uvs(UvsReason),
 }
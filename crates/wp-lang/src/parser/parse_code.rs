@@ -1,11 +1,12 @@
 use crate::ast::WplExpress;
+use crate::ast::group::WplGroup;
 use crate::parser::utils::is_sep_next;
-use crate::parser::wpl_group::wpl_group;
+use crate::parser::wpl_group::{wpl_group, wpl_group_refer};
 use crate::parser::wpl_rule;
 use crate::types::AnyResult;
 use anyhow::anyhow;
 use winnow::ascii::multispace0;
-use winnow::combinator::{cut_err, delimited, opt};
+use winnow::combinator::{alt, cut_err, delimited, opt};
 use winnow::token::literal;
 use wp_parser::Parser;
 use wp_parser::WResult;
@@ -19,16 +20,23 @@ pub fn wpl_express(input: &mut &str) -> WResult<WplExpress> {
     if let Some(mut pipe) = opt(wpl_rule::pip_proc).parse_next(input)? {
         rule.pipe_process.append(&mut pipe);
     }
+    rule.group = wpl_groups.parse_next(input)?;
+    Ok(rule)
+}
+
+/// 逗号分隔的组序列，组位置可写字段组引用 `@<name>`
+pub(crate) fn wpl_groups(input: &mut &str) -> WResult<Vec<WplGroup>> {
+    let mut groups = Vec::new();
     loop {
-        wpl_group
+        alt((wpl_group_refer, wpl_group))
             .context(ctx_desc("group"))
-            .map(|x| rule.group.push(x))
+            .map(|x| groups.push(x))
             .parse_next(input)?;
         if !is_sep_next(input) {
             break;
         }
     }
-    Ok(rule)
+    Ok(groups)
 }
 
 pub(crate) fn segment(input: &mut &str) -> WResult<WplExpress> {
@@ -226,7 +234,7 @@ mod tests {
         assert_eq!(
             wpl_express.parse(data).assert().to_string(),
             "  (
-    json(_@_origin,_@payload/packet_data,)
+    json(_@_origin, _@payload/packet_data)
   )"
         );
     }
@@ -21,6 +21,8 @@ pub fn take_ref_path<'a>(input: &mut &'a str) -> WResult<&'a str> {
             || c == '['
             || c == ']'
             || c == '*'
+            // xml 属性路径: a/b/@attr
+            || c == '@'
     })
     .parse_next(input)
 }
@@ -237,15 +239,17 @@ fn decode_escapes(s: &str) -> String {
     String::from_utf8_lossy(&out).to_string()
 }
 
+/// 字符串值：普通引号字符串会做一次反转义，原始字符串保持原样
+pub fn take_str_value(input: &mut &str) -> WResult<String> {
+    alt((quot_r_str.map(String::from), quot_str.map(decode_escapes))).parse_next(input)
+}
+
 pub fn take_tag_kv(input: &mut &str) -> WResult<(SmolStr, SmolStr)> {
     // 值支持普通引号字符串与原始字符串；普通字符串会做一次反转义，原始字符串保持原样
     separated_pair(
         preceded(multispace0, take_key),
         (multispace0, ':', multispace0),
-        alt((
-            quot_r_str.map(|s: &str| SmolStr::from(s)),
-            quot_str.map(|s: &str| SmolStr::from(decode_escapes(s))),
-        )),
+        take_str_value.map(SmolStr::from),
     )
     .map(|(k, v)| (SmolStr::from(k), v))
     .parse_next(input)
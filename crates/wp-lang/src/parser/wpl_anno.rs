@@ -35,13 +35,26 @@ fn copy_raw(input: &mut &str) -> WResult<AnnEnum> {
     Ok(AnnEnum::Copy(obj))
 }
 
+/// 来源选择：`source(src_key:"fw_*", dev:"proxy")`
+fn wpl_source(input: &mut &str) -> WResult<AnnEnum> {
+    let items: Vec<(SmolStr, SmolStr)> = delimited(
+        (multispace0, literal("source"), multispace0, literal('(')),
+        cut_err(separated(1.., utils::take_tag_kv, literal(","))).context(ctx_desc(
+            "source(src_key: \"pattern\", tag: \"pattern\", ... )",
+        )),
+        (multispace0, literal(')')),
+    )
+    .parse_next(input)?;
+    Ok(AnnEnum::Source(items.into_iter().collect()))
+}
+
 pub fn ann_fun(input: &mut &str) -> WResult<AnnFun> {
     multispace0.parse_next(input)?;
     literal("#[")
         .context(ctx_desc("annotation start"))
         .parse_next(input)?;
     let x: Vec<AnnEnum> =
-        separated(0.., alt((wpl_tags, copy_raw)), literal(",")).parse_next(input)?;
+        separated(0.., alt((wpl_tags, copy_raw, wpl_source)), literal(",")).parse_next(input)?;
     multispace0.parse_next(input)?;
     literal("]")
         .context(ctx_desc("annotation end"))
@@ -56,6 +69,9 @@ pub fn ann_fun(input: &mut &str) -> WResult<AnnFun> {
             AnnEnum::Tags(v) => {
                 af.tags = v;
             }
+            AnnEnum::Source(v) => {
+                af.source = v;
+            }
         }
     }
     Ok(af)
@@ -67,7 +83,7 @@ mod tests {
 
     use crate::ast::{AnnEnum, AnnFun};
     use crate::parser::utils::take_tag_kv;
-    use crate::parser::wpl_anno::{ann_fun, wpl_tags};
+    use crate::parser::wpl_anno::{ann_fun, wpl_source, wpl_tags};
     use orion_error::TestAssert;
     use wp_parser::Parser;
 
@@ -144,6 +160,7 @@ mod tests {
                     ("cc_y".into(), "qw_/e".into())
                 ]),
                 copy_raw: Some(("name".into(), "tq".into())),
+                source: Default::default(),
             }
         );
 
@@ -161,6 +178,7 @@ mod tests {
                     ("cc_y".into(), "qw_/e".into())
                 ]),
                 copy_raw: None,
+                source: Default::default(),
             }
         );
 
@@ -175,7 +193,29 @@ mod tests {
             AnnFun {
                 tags: Default::default(),
                 copy_raw: Some(("name".into(), "tq".into())),
+                source: Default::default(),
             }
         );
     }
+
+    #[test]
+    fn test_source() {
+        assert_eq!(
+            wpl_source
+                .parse(r#"source(src_key:"fw_*", dev : "proxy")"#)
+                .assert(),
+            AnnEnum::Source(BTreeMap::from([
+                ("src_key".into(), "fw_*".into()),
+                ("dev".into(), "proxy".into()),
+            ]))
+        );
+        assert!(wpl_source.parse("source()").is_err());
+        assert_eq!(
+            ann_fun
+                .parse(r#"#[tag(t:"x"), source(src_key:"syslog_*")]"#)
+                .assert()
+                .source,
+            BTreeMap::from([("src_key".into(), "syslog_*".into())])
+        );
+    }
 }
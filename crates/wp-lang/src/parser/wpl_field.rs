@@ -1,10 +1,12 @@
 use super::wpl_fun;
 use crate::ast::WplSep;
 use crate::ast::fld_fmt::WplFieldFmt;
-use crate::ast::{DEFAULT_FIELD_KEY, WplField, WplFieldSet, WplPipe};
-use crate::parser::datatype::take_datatype;
+use crate::ast::{DEFAULT_FIELD_KEY, EnrichConf, WplField, WplFieldSet, WplPipe};
+use crate::eval::{TimeFmtConf, compile_wpl_regex, take_regex_str};
+use crate::parser::datatype::{GROK, REGEX, TIME_FMT, take_field_meta, wpl_meta_from};
 use crate::parser::utils::{
-    peek_next, peek_str, take_key, take_parentheses, take_ref_path, take_to_end, take_var_name,
+    peek_next, peek_str, take_fun_name, take_key, take_parentheses, take_ref_path, take_to_end,
+    take_var_name,
 };
 use crate::parser::wpl_group::wpl_group;
 use crate::types::WildMap;
@@ -14,7 +16,7 @@ use winnow::error::{StrContext, StrContextValue};
 use winnow::stream::Stream;
 use winnow::token::{literal, take, take_till};
 // Use workspace-wide parser result alias to decouple from winnow's concrete type
-use wp_model_core::model::DataType;
+use wp_model_core::model::{DataType, FNameStr};
 use wp_parser::Parser;
 use wp_parser::WResult as ModalResult;
 use wp_parser::symbol::{ctx_desc, ctx_literal};
@@ -109,7 +111,7 @@ fn wpl_field_fmt(input: &mut &str) -> ModalResult<WplFieldFmt> {
     }
     Ok(WplFieldFmt::default())
 }
-fn wpl_opt_meta(input: &mut &str) -> ModalResult<(DataType, bool)> {
+fn wpl_opt_meta(input: &mut &str) -> ModalResult<(DataType, FNameStr, bool)> {
     multispace0.parse_next(input)?;
     let mut is_opt = true;
     let meta_key = opt(delimited(
@@ -126,21 +128,25 @@ fn wpl_opt_meta(input: &mut &str) -> ModalResult<(DataType, bool)> {
         Some((_, v, _)) => Some(v),
     };
     if let Some(mk) = meta_key {
-        match DataType::from(mk) {
-            Ok(meta) => return Ok((meta, is_opt)),
-            Err(_) => {
+        match wpl_meta_from(mk) {
+            Some((meta, meta_name)) => return Ok((meta, meta_name, is_opt)),
+            None => {
                 fail.context(ctx_desc("bad meta")).parse_next(input)?;
             }
         }
     }
-    Ok((DataType::Chars, is_opt))
+    Ok((
+        DataType::Chars,
+        DataType::Chars.static_name().into(),
+        is_opt,
+    ))
 }
 
 #[allow(clippy::bind_instead_of_map)]
 fn wpl_id_field<'a>(input: &mut &'a str) -> ModalResult<(&'a str, WplField)> {
     let before_len = input.len();
     let mut content = None;
-    let (meta_type, is_opt) = wpl_opt_meta.parse_next(input)?;
+    let (meta_type, meta_name, is_opt) = wpl_opt_meta.parse_next(input)?;
     /*
     let (meta_type, f_meta) = preceded(multispace0, opt(take_key).map(|x| x.unwrap_or("chars")))
         .try_map::<_, _, MetaErr>(|x| {
@@ -155,6 +161,15 @@ fn wpl_id_field<'a>(input: &mut &'a str) -> ModalResult<(&'a str, WplField)> {
             .parse_next(input)?
             .and_then(|x| Some(x.to_string()));
     }
+    if meta_name == TIME_FMT {
+        content = Some(take_time_fmt.parse_next(input)?);
+    }
+    if meta_name == REGEX {
+        content = Some(take_regex.parse_next(input)?);
+    }
+    if meta_name == GROK {
+        content = Some(take_grok.parse_next(input)?);
+    }
 
     let k = opt((multispace0, literal('@'), take_ref_path).map(|x| x.2))
         .map(|x| x.unwrap_or(DEFAULT_FIELD_KEY))
@@ -176,7 +191,7 @@ fn wpl_id_field<'a>(input: &mut &'a str) -> ModalResult<(&'a str, WplField)> {
 
     let mut conf = WplField {
         name: f_key.map(|s| s.into()),
-        meta_name: meta_type.static_name().into(),
+        meta_name,
         meta_type,
         fmt_conf,
         separator: sep,
@@ -185,6 +200,7 @@ fn wpl_id_field<'a>(input: &mut &'a str) -> ModalResult<(&'a str, WplField)> {
         ..Default::default()
     };
     conf.pipe = repeat(0.., wpl_pipe).parse_next(input)?;
+    conf.enriches = repeat(0.., wpl_enrich).parse_next(input)?;
     conf.setup();
 
     Ok((k, conf))
@@ -240,11 +256,14 @@ fn wpl_field_impl(input: &mut &str) -> ModalResult<WplField> {
             conf.continuous_cnt = Some(rep_cnt.parse::<usize>().unwrap_or(255));
         }
     }
-    let main_meta = take_datatype.parse_next(input)?;
-    conf.meta_name = main_meta.static_name().into();
+    let (main_meta, meta_name) = take_field_meta.parse_next(input)?;
+    conf.meta_name = meta_name;
     conf.meta_type = main_meta;
     parse_symbol(input, &mut conf)?;
     parse_peek_symbol(input, &mut conf)?;
+    parse_time_fmt(input, &mut conf)?;
+    parse_regex(input, &mut conf)?;
+    parse_grok(input, &mut conf)?;
 
     multispace0.parse_next(input)?;
     if peek_str("(", input).is_ok() {
@@ -272,6 +291,7 @@ fn wpl_field_impl(input: &mut &str) -> ModalResult<WplField> {
     let opt_sep = wpl_sep.parse_next(input)?;
     conf.separator = opt_sep;
     conf.pipe = repeat(0.., wpl_pipe).parse_next(input)?;
+    conf.enriches = repeat(0.., wpl_enrich).parse_next(input)?;
 
     conf.fmt_conf = fmt_conf;
     /*
@@ -295,6 +315,51 @@ pub fn wpl_pipe(data: &mut &str) -> ModalResult<WplPipe> {
     let pipe = alt((wpl_pipe_fun, wpl_pipe_group)).parse_next(data)?;
     Ok(pipe)
 }
+
+// | enrich(<dict>, <key>, <col>[:<alias>], ...)
+pub fn wpl_enrich(data: &mut &str) -> ModalResult<EnrichConf> {
+    (
+        multispace0,
+        literal('|'),
+        multispace0,
+        literal("enrich"),
+        multispace0,
+        literal('('),
+    )
+        .parse_next(data)?;
+    let dict = preceded(multispace0, take_fun_name)
+        .context(ctx_desc("enrich(<dict>, ...)"))
+        .parse_next(data)?;
+    (multispace0, literal(',')).parse_next(data)?;
+    let key = preceded(multispace0, take_fun_name)
+        .context(ctx_desc("enrich(<dict>, <key>, ...)"))
+        .parse_next(data)?;
+    let adds: Vec<(String, String)> = repeat(
+        1..,
+        preceded(
+            (multispace0, literal(','), multispace0),
+            (
+                take_fun_name,
+                opt(preceded(
+                    (multispace0, literal(':'), multispace0),
+                    take_fun_name,
+                )),
+            ),
+        )
+        .map(|(col, alias): (&str, Option<&str>)| {
+            (col.to_string(), alias.unwrap_or(col).to_string())
+        }),
+    )
+    .context(ctx_desc("enrich(<dict>, <key>, <col>[:<alias>], ...)"))
+    .parse_next(data)?;
+    (multispace0, literal(')')).parse_next(data)?;
+    Ok(EnrichConf {
+        key: key.to_string(),
+        dict: dict.to_string(),
+        adds,
+    })
+}
+
 fn wpl_pipe_group(data: &mut &str) -> ModalResult<WplPipe> {
     let group = wpl_group.parse_next(data)?;
     Ok(WplPipe::Group(group))
@@ -312,6 +377,69 @@ fn parse_symbol(input: &mut &str, conf: &mut WplField) -> ModalResult<()> {
     }
     Ok(())
 }
+fn parse_time_fmt(input: &mut &str, conf: &mut WplField) -> ModalResult<()> {
+    if conf.meta_name == TIME_FMT {
+        conf.content = Some(take_time_fmt.parse_next(input)?);
+    }
+    Ok(())
+}
+// time/fmt("<fmt>"[, "<tz>"])：加载时校验格式
+fn take_time_fmt(input: &mut &str) -> ModalResult<String> {
+    let cp = input.checkpoint();
+    let content = take_parentheses
+        .context(ctx_desc("time/fmt(\"<fmt>\")"))
+        .parse_next(input)?;
+    if TimeFmtConf::parse(content).is_err() {
+        input.reset(&cp);
+        fail.context(ctx_desc("bad time/fmt format"))
+            .parse_next(input)?;
+    }
+    Ok(content.to_string())
+}
+fn parse_regex(input: &mut &str, conf: &mut WplField) -> ModalResult<()> {
+    if conf.meta_name == REGEX {
+        conf.content = Some(take_regex.parse_next(input)?);
+    }
+    Ok(())
+}
+// regex(r#"..."#)：加载时编译校验，错误位置指向正则字面量
+fn take_regex(input: &mut &str) -> ModalResult<String> {
+    (literal('('), multispace0)
+        .context(ctx_desc("regex(\"<pattern>\")"))
+        .parse_next(input)?;
+    let cp = input.checkpoint();
+    let beg = *input;
+    take_regex_str.parse_next(input)?;
+    let content = &beg[..beg.len() - input.len()];
+    if compile_wpl_regex(content).is_err() {
+        input.reset(&cp);
+        fail.context(ctx_desc("bad regex pattern"))
+            .parse_next(input)?;
+    }
+    (multispace0, literal(')'))
+        .context(ctx_desc("regex(...) <)>"))
+        .parse_next(input)?;
+    Ok(content.to_string())
+}
+fn parse_grok(input: &mut &str, conf: &mut WplField) -> ModalResult<()> {
+    if conf.meta_name == GROK {
+        conf.content = Some(take_grok.parse_next(input)?);
+    }
+    Ok(())
+}
+// grok("%{IP:client} ...")：此处仅校验字面量，模式引用在加载时按模式库展开
+fn take_grok(input: &mut &str) -> ModalResult<String> {
+    (literal('('), multispace0)
+        .context(ctx_desc("grok(\"<expr>\")"))
+        .parse_next(input)?;
+    let beg = *input;
+    take_regex_str.parse_next(input)?;
+    let content = &beg[..beg.len() - input.len()];
+    (multispace0, literal(')'))
+        .context(ctx_desc("grok(...) <)>"))
+        .parse_next(input)?;
+    Ok(content.to_string())
+}
 fn parse_peek_symbol(input: &mut &str, conf: &mut WplField) -> ModalResult<()> {
     if conf.meta_type == DataType::PeekSymbol {
         //if conf.meta_name == "peek_symbol" {
@@ -746,4 +874,36 @@ mod tests {
         let conf = wpl_field.parse(code).assert();
         assert_eq!(code, conf.to_string());
     }
+
+    #[test]
+    fn test_parse_enrich() {
+        let conf = wpl_field
+            .parse("ip:src_ip | enrich(asset, ip, owner:asset_owner, zone)")
+            .assert();
+        assert_eq!(
+            conf.enriches,
+            vec![EnrichConf {
+                key: "ip".to_string(),
+                dict: "asset".to_string(),
+                adds: vec![
+                    ("owner".to_string(), "asset_owner".to_string()),
+                    ("zone".to_string(), "zone".to_string()),
+                ],
+            }]
+        );
+        assert_eq!(
+            conf.to_string(),
+            "ip:src_ip | enrich(asset, ip, owner:asset_owner, zone)"
+        );
+
+        // 富化位于管道之后
+        let conf = wpl_field
+            .parse("chars:host | to_lower() | enrich(host_geo, host, city)")
+            .assert();
+        assert_eq!(conf.pipe.len(), 1);
+        assert_eq!(conf.enriches.len(), 1);
+
+        assert!(wpl_field.parse("ip | enrich(asset, ip)").is_err());
+        assert!(wpl_field.parse("ip | enrich(asset)").is_err());
+    }
 }
@@ -1,37 +1,50 @@
 use std::net::IpAddr;
 
+use ipnet::IpNet;
+use regex::Regex;
 use smol_str::SmolStr;
 use winnow::{
     Parser,
     ascii::{digit1, multispace0},
-    combinator::alt,
+    combinator::{alt, cut_err, fail, opt},
+    error::{ErrMode, ParserError},
+    token::take_while,
 };
 use wp_parser::{
     WResult,
     fun::{fun_trait::Fun0Builder, parser::call_fun_args0},
+    symbol::ctx_desc,
 };
 use wp_parser::{
     atom::take_path,
     fun::{
-        fun_trait::{Fun1Builder, Fun2Builder, ParseNext},
-        parser::{call_fun_args1, call_fun_args2, take_arr},
+        fun_trait::{Fun1Builder, Fun2Builder, Fun3Builder, ParseNext},
+        parser::{call_fun_args1, call_fun_args2, call_fun_args3, take_arr},
     },
 };
 
 use crate::ast::{
     WplFun,
     processor::{
-        CharsHas, CharsIn, CharsInArg, CharsNotHas, CharsNotHasArg, CharsValue, DigitHas,
-        DigitHasArg, DigitIn, DigitInArg, Has, HasArg, IpIn, IpInArg, SelectLast, TakeField,
-        TargetCharsHas, TargetCharsIn, TargetCharsNotHas, TargetDigitHas, TargetDigitIn, TargetHas,
-        TargetIpIn, normalize_target,
+        CharsHas, CharsIn, CharsInArg, CharsNotHas, CharsNotHasArg, CharsValue, CidrIn, CidrValue,
+        DigitHas, DigitHasArg, DigitIn, DigitInArg, DigitRange, EndsWith, Has, HasArg, IpIn,
+        IpInArg, LenBetween, PipeRegex, RegexMatch, SelectLast, StartsWith, TakeField,
+        TargetCharsHas, TargetCharsIn, TargetCharsNotHas, TargetCidrIn, TargetDigitHas,
+        TargetDigitIn, TargetDigitRange, TargetEndsWith, TargetHas, TargetIpIn, TargetLenBetween,
+        TargetRegexMatch, TargetStartsWith, TargetToLower, TargetToUpper, TargetTrim, ToLower,
+        ToUpper, Trim, normalize_target,
     },
 };
 
-use super::utils::take_key;
+use super::utils::{take_key, take_str_value};
 
 pub fn wpl_fun(input: &mut &str) -> WResult<WplFun> {
     multispace0.parse_next(input)?;
+    let fun = alt((base_fun, match_fun, trans_fun)).parse_next(input)?;
+    Ok(fun)
+}
+
+fn base_fun(input: &mut &str) -> WResult<WplFun> {
     let fun = alt((
         call_fun_args1::<TakeField>.map(WplFun::SelectTake),
         call_fun_args0::<SelectLast>.map(WplFun::SelectLast),
@@ -50,13 +63,76 @@ pub fn wpl_fun(input: &mut &str) -> WResult<WplFun> {
         call_fun_args1::<IpInArg>.map(|arg| WplFun::IpIn(IpIn { value: arg.value })),
         call_fun_args1::<TargetHas>.map(WplFun::TargetHas),
         call_fun_args0::<HasArg>.map(|_| WplFun::Has(Has)),
-        call_fun_args0::<JsonUnescape>.map(WplFun::TransJsonUnescape),
-        call_fun_args0::<Base64Decode>.map(WplFun::TransBase64Decode),
     ))
     .parse_next(input)?;
     Ok(fun)
 }
 
+// 匹配类：正则、前后缀、长度与数值区间、网段
+fn match_fun(input: &mut &str) -> WResult<WplFun> {
+    alt((
+        call_fun_args2::<TargetRegexMatch>.map(WplFun::TargetRegexMatch),
+        call_fun_args1::<RegexMatch>.map(WplFun::RegexMatch),
+        call_fun_args2::<TargetStartsWith>.map(WplFun::TargetStartsWith),
+        call_fun_args1::<StartsWith>.map(WplFun::StartsWith),
+        call_fun_args2::<TargetEndsWith>.map(WplFun::TargetEndsWith),
+        call_fun_args1::<EndsWith>.map(WplFun::EndsWith),
+        target_len_between.map(WplFun::TargetLenBetween),
+        len_between.map(WplFun::LenBetween),
+        target_digit_range.map(WplFun::TargetDigitRange),
+        digit_range.map(WplFun::DigitRange),
+        call_fun_args2::<TargetCidrIn>.map(WplFun::TargetCidrIn),
+        call_fun_args1::<CidrIn>.map(WplFun::CidrIn),
+    ))
+    .parse_next(input)
+}
+
+// 区间下界大于上界时任何值都不会命中，解析时直接拒绝
+fn check_bounds<T: PartialOrd>(data: &mut &str, min: T, max: T, desc: &'static str) -> WResult<()> {
+    if min > max {
+        return cut_err(fail.context(ctx_desc(desc))).parse_next(data);
+    }
+    Ok(())
+}
+
+fn len_between(data: &mut &str) -> WResult<LenBetween> {
+    let fun = call_fun_args2::<LenBetween>.parse_next(data)?;
+    check_bounds(data, fun.min, fun.max, "len_between | min > max")?;
+    Ok(fun)
+}
+
+fn target_len_between(data: &mut &str) -> WResult<TargetLenBetween> {
+    let fun = call_fun_args3::<TargetLenBetween>.parse_next(data)?;
+    check_bounds(data, fun.min, fun.max, "f_len_between | min > max")?;
+    Ok(fun)
+}
+
+fn digit_range(data: &mut &str) -> WResult<DigitRange> {
+    let fun = call_fun_args2::<DigitRange>.parse_next(data)?;
+    check_bounds(data, fun.min, fun.max, "digit_range | min > max")?;
+    Ok(fun)
+}
+
+fn target_digit_range(data: &mut &str) -> WResult<TargetDigitRange> {
+    let fun = call_fun_args3::<TargetDigitRange>.parse_next(data)?;
+    check_bounds(data, fun.min, fun.max, "f_digit_range | min > max")?;
+    Ok(fun)
+}
+
+fn trans_fun(input: &mut &str) -> WResult<WplFun> {
+    alt((
+        call_fun_args0::<JsonUnescape>.map(WplFun::TransJsonUnescape),
+        call_fun_args0::<Base64Decode>.map(WplFun::TransBase64Decode),
+        call_fun_args1::<TargetToLower>.map(WplFun::TargetToLower),
+        call_fun_args0::<ToLower>.map(WplFun::TransToLower),
+        call_fun_args1::<TargetToUpper>.map(WplFun::TargetToUpper),
+        call_fun_args0::<ToUpper>.map(WplFun::TransToUpper),
+        call_fun_args1::<TargetTrim>.map(WplFun::TargetTrim),
+        call_fun_args0::<Trim>.map(WplFun::TransTrim),
+    ))
+    .parse_next(input)
+}
+
 impl Fun2Builder for TargetDigitHas {
     type ARG1 = SmolStr;
     type ARG2 = i64;
@@ -347,6 +423,322 @@ impl Fun2Builder for TargetIpIn {
     }
 }
 
+// ---------------- Match Mode ----------------
+
+fn take_target(data: &mut &str) -> WResult<SmolStr> {
+    multispace0.parse_next(data)?;
+    let val = take_key.parse_next(data)?;
+    Ok(val.into())
+}
+
+// 引号/原始字符串，或不含特殊字符的裸值
+fn take_str_arg(data: &mut &str) -> WResult<SmolStr> {
+    multispace0.parse_next(data)?;
+    alt((
+        take_str_value.map(SmolStr::from),
+        take_path.map(SmolStr::from),
+    ))
+    .parse_next(data)
+}
+
+// 正则在加载时编译，非法模式直接报错
+fn take_regex(data: &mut &str) -> WResult<PipeRegex> {
+    multispace0.parse_next(data)?;
+    let pattern = take_str_value.parse_next(data)?;
+    match Regex::new(&pattern) {
+        Ok(re) => Ok(PipeRegex(re)),
+        Err(_) => cut_err(fail.context(ctx_desc("regex_match | invalid regex"))).parse_next(data),
+    }
+}
+
+fn take_i64(data: &mut &str) -> WResult<i64> {
+    multispace0.parse_next(data)?;
+    (opt('-'), digit1)
+        .take()
+        .try_map(str::parse::<i64>)
+        .parse_next(data)
+}
+
+fn take_usize(data: &mut &str) -> WResult<usize> {
+    multispace0.parse_next(data)?;
+    digit1.try_map(str::parse::<usize>).parse_next(data)
+}
+
+impl ParseNext<CidrValue> for CidrValue {
+    fn parse_next(input: &mut &str) -> WResult<CidrValue> {
+        multispace0.parse_next(input)?;
+        let raw = take_while(1.., ('0'..='9', 'a'..='f', 'A'..='F', [':', '.', '/']))
+            .parse_next(input)?;
+        // 不带前缀长度的地址视为单个主机
+        let net = if raw.contains('/') {
+            raw.parse::<IpNet>().ok()
+        } else {
+            raw.parse::<IpAddr>().ok().map(IpNet::from)
+        };
+        net.map(CidrValue).ok_or_else(|| ErrMode::from_input(input))
+    }
+}
+
+impl Fun1Builder for RegexMatch {
+    type ARG1 = PipeRegex;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_regex(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "regex_match"
+    }
+
+    fn build(args: Self::ARG1) -> Self {
+        Self { value: args }
+    }
+}
+
+impl Fun2Builder for TargetRegexMatch {
+    type ARG1 = SmolStr;
+    type ARG2 = PipeRegex;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_regex(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_regex_match"
+    }
+
+    fn build(args: (Self::ARG1, Self::ARG2)) -> Self {
+        Self {
+            target: normalize_target(args.0),
+            value: args.1,
+        }
+    }
+}
+
+impl Fun1Builder for StartsWith {
+    type ARG1 = SmolStr;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_str_arg(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "starts_with"
+    }
+
+    fn build(args: Self::ARG1) -> Self {
+        Self { value: args }
+    }
+}
+
+impl Fun2Builder for TargetStartsWith {
+    type ARG1 = SmolStr;
+    type ARG2 = SmolStr;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_str_arg(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_starts_with"
+    }
+
+    fn build(args: (Self::ARG1, Self::ARG2)) -> Self {
+        Self {
+            target: normalize_target(args.0),
+            value: args.1,
+        }
+    }
+}
+
+impl Fun1Builder for EndsWith {
+    type ARG1 = SmolStr;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_str_arg(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "ends_with"
+    }
+
+    fn build(args: Self::ARG1) -> Self {
+        Self { value: args }
+    }
+}
+
+impl Fun2Builder for TargetEndsWith {
+    type ARG1 = SmolStr;
+    type ARG2 = SmolStr;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_str_arg(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_ends_with"
+    }
+
+    fn build(args: (Self::ARG1, Self::ARG2)) -> Self {
+        Self {
+            target: normalize_target(args.0),
+            value: args.1,
+        }
+    }
+}
+
+impl Fun2Builder for LenBetween {
+    type ARG1 = usize;
+    type ARG2 = usize;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_usize(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_usize(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "len_between"
+    }
+
+    fn build(args: (Self::ARG1, Self::ARG2)) -> Self {
+        Self {
+            min: args.0,
+            max: args.1,
+        }
+    }
+}
+
+impl Fun3Builder for TargetLenBetween {
+    type ARG1 = SmolStr;
+    type ARG2 = usize;
+    type ARG3 = usize;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_usize(data)
+    }
+    fn args3(data: &mut &str) -> WResult<Self::ARG3> {
+        take_usize(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_len_between"
+    }
+
+    fn build(args: (Self::ARG1, Self::ARG2, Self::ARG3)) -> Self {
+        Self {
+            target: normalize_target(args.0),
+            min: args.1,
+            max: args.2,
+        }
+    }
+}
+
+impl Fun2Builder for DigitRange {
+    type ARG1 = i64;
+    type ARG2 = i64;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_i64(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_i64(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "digit_range"
+    }
+
+    fn build(args: (Self::ARG1, Self::ARG2)) -> Self {
+        Self {
+            min: args.0,
+            max: args.1,
+        }
+    }
+}
+
+impl Fun3Builder for TargetDigitRange {
+    type ARG1 = SmolStr;
+    type ARG2 = i64;
+    type ARG3 = i64;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_i64(data)
+    }
+    fn args3(data: &mut &str) -> WResult<Self::ARG3> {
+        take_i64(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_digit_range"
+    }
+
+    fn build(args: (Self::ARG1, Self::ARG2, Self::ARG3)) -> Self {
+        Self {
+            target: normalize_target(args.0),
+            min: args.1,
+            max: args.2,
+        }
+    }
+}
+
+impl Fun1Builder for CidrIn {
+    type ARG1 = Vec<CidrValue>;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_arr::<CidrValue>(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "cidr_in"
+    }
+
+    fn build(args: Self::ARG1) -> Self {
+        Self {
+            value: args.into_iter().map(|x| x.0).collect(),
+        }
+    }
+}
+
+impl Fun2Builder for TargetCidrIn {
+    type ARG1 = SmolStr;
+    type ARG2 = Vec<CidrValue>;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_arr::<CidrValue>(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_cidr_in"
+    }
+
+    fn build(args: (Self::ARG1, Self::ARG2)) -> Self {
+        Self {
+            target: normalize_target(args.0),
+            value: args.1.into_iter().map(|x| x.0).collect(),
+        }
+    }
+}
+
 // ---------------- String Mode ----------------
 use crate::ast::processor::JsonUnescape;
 
@@ -371,6 +763,90 @@ impl Fun0Builder for Base64Decode {
     }
 }
 
+impl Fun0Builder for ToLower {
+    fn fun_name() -> &'static str {
+        "to_lower"
+    }
+
+    fn build() -> Self {
+        ToLower {}
+    }
+}
+
+impl Fun1Builder for TargetToLower {
+    type ARG1 = SmolStr;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_to_lower"
+    }
+
+    fn build(args: Self::ARG1) -> Self {
+        Self {
+            target: normalize_target(args),
+        }
+    }
+}
+
+impl Fun0Builder for ToUpper {
+    fn fun_name() -> &'static str {
+        "to_upper"
+    }
+
+    fn build() -> Self {
+        ToUpper {}
+    }
+}
+
+impl Fun1Builder for TargetToUpper {
+    type ARG1 = SmolStr;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_to_upper"
+    }
+
+    fn build(args: Self::ARG1) -> Self {
+        Self {
+            target: normalize_target(args),
+        }
+    }
+}
+
+impl Fun0Builder for Trim {
+    fn fun_name() -> &'static str {
+        "trim"
+    }
+
+    fn build() -> Self {
+        Trim {}
+    }
+}
+
+impl Fun1Builder for TargetTrim {
+    type ARG1 = SmolStr;
+
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_target(data)
+    }
+
+    fn fun_name() -> &'static str {
+        "f_trim"
+    }
+
+    fn build(args: Self::ARG1) -> Self {
+        Self {
+            target: normalize_target(args),
+        }
+    }
+}
+
 impl Fun1Builder for TakeField {
     type ARG1 = SmolStr;
 
@@ -530,4 +1006,101 @@ mod tests {
         assert_eq!(fun, WplFun::TransBase64Decode(Base64Decode {}));
         assert!(wpl_fun.parse("base64_decode(decoded)").is_err());
     }
+    #[test]
+    fn test_parse_match_fun() {
+        let fun = wpl_fun
+            .parse(r##"f_regex_match(msg, r#"^\d+ (GET|POST)"#)"##)
+            .assert();
+        let WplFun::TargetRegexMatch(x) = &fun else {
+            panic!("expect f_regex_match");
+        };
+        assert_eq!(x.target, Some("msg".into()));
+        assert!(x.value.0.is_match("200 GET"));
+        assert!(wpl_fun.parse(r#"regex_match("(")"#).is_err());
+
+        let fun = wpl_fun.parse(r#"starts_with("GET ")"#).assert();
+        assert_eq!(
+            fun,
+            WplFun::StartsWith(StartsWith {
+                value: "GET ".into()
+            })
+        );
+        let fun = wpl_fun.parse("f_ends_with(_, .log)").assert();
+        assert_eq!(
+            fun,
+            WplFun::TargetEndsWith(TargetEndsWith {
+                target: None,
+                value: ".log".into()
+            })
+        );
+
+        let fun = wpl_fun.parse("digit_range(-5, 10)").assert();
+        assert_eq!(fun, WplFun::DigitRange(DigitRange { min: -5, max: 10 }));
+        let fun = wpl_fun.parse("f_len_between(code, 3, 3)").assert();
+        assert_eq!(
+            fun,
+            WplFun::TargetLenBetween(TargetLenBetween {
+                target: Some("code".into()),
+                min: 3,
+                max: 3
+            })
+        );
+
+        let fun = wpl_fun
+            .parse("f_cidr_in(sip, [10.0.0.0/8,192.168.1.1,fe80::/10])")
+            .assert();
+        assert_eq!(
+            fun,
+            WplFun::TargetCidrIn(TargetCidrIn {
+                target: Some("sip".into()),
+                value: vec![
+                    "10.0.0.0/8".parse().unwrap(),
+                    "192.168.1.1/32".parse().unwrap(),
+                    "fe80::/10".parse().unwrap(),
+                ]
+            })
+        );
+        assert!(wpl_fun.parse("cidr_in([10.0.0.0/40])").is_err());
+        // 下界大于上界的区间解析失败
+        assert!(wpl_fun.parse("len_between(10, 2)").is_err());
+        assert!(wpl_fun.parse("f_len_between(code, 10, 2)").is_err());
+        assert!(wpl_fun.parse("digit_range(9, 1)").is_err());
+        assert!(wpl_fun.parse("f_digit_range(status, 9, 1)").is_err());
+        assert!(wpl_fun.parse("digit_range(1, 1)").is_ok());
+
+        assert_eq!(
+            wpl_fun.parse("to_lower()").assert(),
+            WplFun::TransToLower(ToLower {})
+        );
+        assert_eq!(
+            wpl_fun.parse("f_trim(name)").assert(),
+            WplFun::TargetTrim(TargetTrim {
+                target: Some("name".into())
+            })
+        );
+    }
+
+    #[test]
+    fn test_match_fun_display_round_trip() {
+        for code in [
+            r##"f_regex_match(msg, r#"^\d+ "x""#)"##,
+            r#"regex_match("^[a-z]+$")"#,
+            r#"starts_with("GET ")"#,
+            r#"f_ends_with(_, ".log")"#,
+            "len_between(1, 64)",
+            "f_len_between(code, 3, 3)",
+            "digit_range(-5, 10)",
+            "f_digit_range(status, 200, 299)",
+            "cidr_in([10.0.0.0/8,::1/128])",
+            "f_cidr_in(sip, [192.168.0.0/16])",
+            "to_lower()",
+            "f_to_upper(method)",
+            "trim()",
+            "f_trim(_)",
+        ] {
+            let fun = wpl_fun.parse(code).assert();
+            assert_eq!(fun.to_string(), code);
+            assert_eq!(wpl_fun.parse(fun.to_string().as_str()).assert(), fun);
+        }
+    }
 }
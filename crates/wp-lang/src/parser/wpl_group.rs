@@ -1,7 +1,7 @@
 use crate::ast::WplSep;
 use crate::ast::group::WplGroup;
 use crate::parser::constants::{CTX_EXPECT_GROUP_META, CTX_GROUP_CONTENT, CTX_GROUP_META_HINT};
-use crate::parser::utils::peek_str;
+use crate::parser::utils::{self, peek_str};
 use crate::parser::wpl_field::wpl_sep_str;
 use crate::parser::wpl_rule;
 use winnow::ascii::{digit1, multispace0};
@@ -50,6 +50,13 @@ pub fn wpl_group(input: &mut &str) -> WResult<WplGroup> {
     group.fields.append(&mut fields);
     Ok(group)
 }
+/// 字段组引用：`@<name>`，由包解析阶段展开
+pub fn wpl_group_refer(input: &mut &str) -> WResult<WplGroup> {
+    (multispace0, '@', utils::take_var_name)
+        .context(ctx_desc("@<fields name>"))
+        .map(|x| WplGroup::refer(x.2))
+        .parse_next(input)
+}
 // old alternative implementation removed (kept in VCS history)
 
 #[cfg(test)]
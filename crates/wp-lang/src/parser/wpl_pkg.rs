@@ -1,9 +1,16 @@
 use super::wpl_anno::ann_fun;
-use crate::ast::{WplPackage, WplRule};
+use crate::ast::group::WplGroup;
+use crate::ast::{
+    WplExpectField, WplExpress, WplPackage, WplRule, WplStatementType, WplTestCase, WplUse,
+};
+use crate::parser::error::{WplCodeError, WplCodeReason, WplCodeResult};
+use crate::parser::parse_code::wpl_groups;
 use crate::parser::{MergeTags, utils, wpl_rule};
+use orion_overload::new::New1;
 use smol_str::SmolStr;
+use std::collections::HashMap;
 use winnow::ascii::{multispace0, multispace1};
-use winnow::combinator::{alt, cut_err, delimited, opt, repeat};
+use winnow::combinator::{alt, cut_err, delimited, opt, preceded, repeat, separated};
 use winnow::error::{ContextError, StrContext};
 use winnow::token::literal;
 use wp_parser::Parser;
@@ -13,17 +20,120 @@ use wp_parser::symbol::{ctx_desc, ctx_label, ctx_literal};
 pub fn wpl_pkg_body2(input: &mut &str) -> WResult<Vec<WplRule>> {
     let mut rules = Vec::new();
     loop {
-        wpl_rule::wpl_rule
+        wpl_pkg_item
             .context(StrContext::Expected("rule <name> {...}".into()))
             .map(|x| rules.push(x))
             .parse_next(input)?;
-        if !utils::is_next(alt(("rule", "#[")), input) {
+        if !utils::is_next(alt(("rule", "#[", "fields", "use", "test")), input) {
             break;
         }
     }
     Ok(rules)
 }
 
+fn wpl_pkg_item(input: &mut &str) -> WResult<WplRule> {
+    alt((wpl_fields_def, wpl_use, wpl_test, wpl_rule::wpl_rule)).parse_next(input)
+}
+
+/// 规则样例测试：
+/// `test <rule> { sample = "<line>" expect { <type>:<name> = "<value>", <name>, .. } }`
+pub fn wpl_test(input: &mut &str) -> WResult<WplRule> {
+    (multispace0, literal("test"), multispace1)
+        .context(ctx_label("wpl keyword"))
+        .parse_next(input)?;
+    let name = cut_err(utils::take_var_name)
+        .context(ctx_desc("<<< test <rule>"))
+        .parse_next(input)?;
+    (multispace0, cut_err(literal("{")), multispace0).parse_next(input)?;
+    let sample = preceded(
+        (literal("sample"), multispace0, literal("="), multispace0),
+        cut_err(utils::take_str_value),
+    )
+    .context(ctx_desc("sample = \"<line>\""))
+    .parse_next(input)?;
+    let expect = opt(delimited(
+        (multispace0, literal("expect"), multispace0, literal("{")),
+        cut_err(separated(0.., wpl_expect_field, literal(",")))
+            .context(ctx_desc("expect { <type>:<name> = \"<value>\", ... }")),
+        (
+            multispace0,
+            opt(literal(",")),
+            multispace0,
+            cut_err(literal("}")),
+        ),
+    ))
+    .parse_next(input)?
+    .unwrap_or_default();
+    (multispace0, cut_err(literal("}")), multispace0).parse_next(input)?;
+    Ok(WplRule::new(
+        name.to_string(),
+        WplStatementType::Test(WplTestCase { sample, expect }),
+    ))
+}
+
+fn wpl_expect_field(input: &mut &str) -> WResult<WplExpectField> {
+    multispace0.parse_next(input)?;
+    let first = utils::take_key.parse_next(input)?;
+    let second = opt(preceded(
+        (multispace0, literal(":"), multispace0),
+        utils::take_key,
+    ))
+    .parse_next(input)?;
+    let (meta, name) = match second {
+        Some(name) => (Some(SmolStr::from(first)), SmolStr::from(name)),
+        None => (None, SmolStr::from(first)),
+    };
+    let value = opt(preceded(
+        (multispace0, literal("="), multispace0),
+        cut_err(utils::take_str_value),
+    ))
+    .parse_next(input)?;
+    Ok(WplExpectField { meta, name, value })
+}
+
+/// 命名字段组：`fields <name> { (..), (..) }`
+pub fn wpl_fields_def(input: &mut &str) -> WResult<WplRule> {
+    (multispace0, literal("fields"), multispace1)
+        .context(ctx_label("wpl keyword"))
+        .parse_next(input)?;
+    let name = cut_err(utils::take_var_name)
+        .context(ctx_desc("<<< fields <name>"))
+        .parse_next(input)?;
+    let groups = delimited(
+        (multispace0, literal("{"), multispace0),
+        cut_err(wpl_groups).context(ctx_desc("{ (...) }")),
+        (multispace0, cut_err(literal("}")), multispace0),
+    )
+    .parse_next(input)?;
+    Ok(WplRule::new(
+        name.to_string(),
+        WplStatementType::Fields(WplExpress::new(groups)),
+    ))
+}
+
+/// 引入字段组：`use <pkg>/<name> [as <alias>]`
+pub fn wpl_use(input: &mut &str) -> WResult<WplRule> {
+    (multispace0, literal("use"), multispace1)
+        .context(ctx_label("wpl keyword"))
+        .parse_next(input)?;
+    let (pkg, name) = cut_err(utils::take_exact_path.verify_map(|x: &str| x.rsplit_once('/')))
+        .context(ctx_desc("<<< use <pkg>/<name>"))
+        .parse_next(input)?;
+    let alias = opt((
+        multispace1,
+        literal("as"),
+        multispace1,
+        utils::take_var_name,
+    ))
+    .map(|x| x.map(|x| x.3))
+    .parse_next(input)?;
+    multispace0.parse_next(input)?;
+    Ok(WplRule::new(
+        alias.unwrap_or(name).to_string(),
+        WplStatementType::Use(WplUse::new(pkg, name)),
+    ))
+}
+
 pub fn wpl_pkg_body<'a, 'b>(
     package: &'b mut WplPackage,
 ) -> impl Parser<&'a str, (), ContextError> + 'b {
@@ -33,9 +143,9 @@ pub fn wpl_pkg_body<'a, 'b>(
             multispace0,
             repeat(
                 1..,
-                wpl_rule::wpl_rule
+                wpl_pkg_item
                     .context(StrContext::Expected("rule <name> {...}".into()))
-                    .map(|x| package.rules.push_back(x)),
+                    .map(|x| package.append(vec![x])),
             ),
             multispace0,
         )
@@ -47,8 +157,15 @@ pub fn wpl_pkg_body<'a, 'b>(
     }
 }
 
-#[allow(clippy::field_reassign_with_default)]
 pub fn wpl_package(input: &mut &str) -> WResult<WplPackage> {
+    let mut package = wpl_package_src.parse_next(input)?;
+    package.merge_tags(&None);
+    Ok(package)
+}
+
+/// 按源码形态解析包：包级注解保持原样，不合并到各规则（供格式化输出）
+#[allow(clippy::field_reassign_with_default)]
+pub fn wpl_package_src(input: &mut &str) -> WResult<WplPackage> {
     let mut package = WplPackage::default();
     opt(ann_fun).map(|t| package.tags = t).parse_next(input)?;
     package.name = (
@@ -71,6 +188,266 @@ pub fn wpl_package(input: &mut &str) -> WResult<WplPackage> {
     .parse_next(input)?;
 
     package.append(rules);
-    package.merge_tags(&None);
     Ok(package)
 }
+
+struct FieldsDef {
+    owner: usize,
+    groups: Vec<WplGroup>,
+}
+
+// 规则与字段组可以同名，展开栈按 (类型, 路径) 区分
+#[derive(PartialEq)]
+enum RefKind {
+    Rule,
+    Fields,
+}
+
+type RefNode = (RefKind, String);
+
+fn node_path(stack: &[RefNode]) -> String {
+    stack
+        .iter()
+        .map(|x| x.1.as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// 字段组引用解析：包内 `fields` 与 `use` 引入的名称 -> `<pkg>/<name>`
+struct RefResolver {
+    defs: HashMap<String, FieldsDef>,
+    scopes: Vec<HashMap<SmolStr, String>>,
+}
+
+fn ref_error(msg: String) -> WplCodeError {
+    WplCodeError::from(WplCodeReason::Reference(msg))
+}
+
+impl RefResolver {
+    fn new(pkgs: &[WplPackage]) -> WplCodeResult<Self> {
+        let mut defs = HashMap::new();
+        for (idx, pkg) in pkgs.iter().enumerate() {
+            for decl in &pkg.decls {
+                if let WplStatementType::Fields(expr) = &decl.statement {
+                    let path = decl.path(pkg.name.as_str());
+                    let def = FieldsDef {
+                        owner: idx,
+                        groups: expr.group.clone(),
+                    };
+                    if defs.insert(path.clone(), def).is_some() {
+                        return Err(ref_error(format!("duplicate fields '{}'", path)));
+                    }
+                }
+            }
+        }
+        let mut scopes = Vec::with_capacity(pkgs.len());
+        for pkg in pkgs {
+            let mut scope = HashMap::new();
+            for decl in &pkg.decls {
+                let target = match &decl.statement {
+                    WplStatementType::Fields(_) => decl.path(pkg.name.as_str()),
+                    WplStatementType::Use(item) => {
+                        if !pkgs.iter().any(|x| x.name == item.pkg) {
+                            return Err(ref_error(format!(
+                                "package '{}': use {}: package '{}' not found",
+                                pkg.name,
+                                item.path(),
+                                item.pkg
+                            )));
+                        }
+                        if !defs.contains_key(&item.path()) {
+                            return Err(ref_error(format!(
+                                "package '{}': use {}: fields '{}' not declared in package '{}'",
+                                pkg.name,
+                                item.path(),
+                                item.name,
+                                item.pkg
+                            )));
+                        }
+                        item.path()
+                    }
+                    WplStatementType::Express(_) | WplStatementType::Test(_) => continue,
+                };
+                if scope.insert(decl.name.clone(), target).is_some() {
+                    return Err(ref_error(format!(
+                        "package '{}': name '{}' declared more than once",
+                        pkg.name, decl.name
+                    )));
+                }
+            }
+            scopes.push(scope);
+        }
+        Ok(Self { defs, scopes })
+    }
+
+    fn expand(
+        &self,
+        owner: usize,
+        groups: &[WplGroup],
+        stack: &mut Vec<RefNode>,
+    ) -> WplCodeResult<Vec<WplGroup>> {
+        let mut out = Vec::with_capacity(groups.len());
+        for group in groups {
+            let Some(name) = &group.refer else {
+                out.push(group.clone());
+                continue;
+            };
+            let Some(path) = self.scopes[owner].get(name) else {
+                return Err(ref_error(format!(
+                    "{}: unknown fields '@{}' (declare it with `fields` or import it with `use`)",
+                    stack.first().map(|x| x.1.as_str()).unwrap_or_default(),
+                    name
+                )));
+            };
+            let node = (RefKind::Fields, path.clone());
+            if stack.contains(&node) {
+                return Err(ref_error(format!(
+                    "fields cycle: {} -> {}",
+                    node_path(stack),
+                    path
+                )));
+            }
+            let def = &self.defs[path];
+            stack.push(node);
+            out.append(&mut self.expand(def.owner, &def.groups, stack)?);
+            stack.pop();
+        }
+        Ok(out)
+    }
+}
+
+/// 展开规则与字段组中的 `@<name>` 引用；`use` 可引入同批次其他包声明的字段组。
+/// 引用未声明、重复声明或循环引用时返回 `WplCodeReason::Reference`
+pub fn resolve_pkg_refs(pkgs: &mut [WplPackage]) -> WplCodeResult<()> {
+    let resolver = RefResolver::new(pkgs)?;
+    for (idx, pkg) in pkgs.iter_mut().enumerate() {
+        let pkg_name = pkg.name.clone();
+        let items = pkg.decls.iter_mut().chain(pkg.rules.iter_mut());
+        for rule in items {
+            let path = rule.path(pkg_name.as_str());
+            let (kind, expr) = match &mut rule.statement {
+                WplStatementType::Express(expr) => (RefKind::Rule, expr),
+                WplStatementType::Fields(expr) => (RefKind::Fields, expr),
+                _ => continue,
+            };
+            if expr.group.iter().any(|x| x.refer.is_some()) {
+                expr.group = resolver.expand(idx, &expr.group, &mut vec![(kind, path)])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WplEvaluator;
+    use crate::parser::error::WplCodeReason;
+    use orion_error::TestAssert;
+    use wp_model_core::model::DataField;
+
+    fn parse_pkgs(codes: &[&str]) -> Vec<WplPackage> {
+        codes
+            .iter()
+            .map(|x| wpl_package.parse(x).assert())
+            .collect()
+    }
+
+    fn reason(pkgs: &mut [WplPackage]) -> String {
+        match resolve_pkg_refs(pkgs) {
+            Err(e) => {
+                assert!(matches!(e.reason(), WplCodeReason::Reference(_)));
+                e.reason().to_string()
+            }
+            Ok(_) => panic!("expect reference error"),
+        }
+    }
+
+    #[test]
+    fn test_fields_use() {
+        let mut pkgs = parse_pkgs(&[
+            r#"package common {
+                fields dev_head { (time:ts, chars:dev_name, sn:serial) }
+                fields sys_head { @dev_head, (digit:pid) }
+            }"#,
+            r#"package vendor {
+                use common/sys_head as head
+                fields tail { (kv) }
+                rule fw { @head, @tail }
+            }"#,
+        ]);
+        assert_eq!(pkgs[1].rules.len(), 1);
+        assert_eq!(pkgs[1].decls.len(), 2);
+        resolve_pkg_refs(&mut pkgs).assert();
+
+        let rule = pkgs[1].rules.front().unwrap();
+        let expr = rule.statement.express().unwrap();
+        assert_eq!(expr.group.len(), 3);
+        assert!(expr.group.iter().all(|x| x.refer.is_none()));
+        assert_eq!(expr.group[0].fields.len(), 3);
+        assert_eq!(expr.group[1].fields.len(), 1);
+
+        let pipe = WplEvaluator::from(expr, None).assert();
+        let (tdc, left) = pipe
+            .proc("2023-05-15 07:09:12 fw-01 SN001 42 act=deny", 0)
+            .assert();
+        assert_eq!(left, "");
+        assert_eq!(
+            tdc.field("dev_name"),
+            Some(&DataField::from_chars("dev_name", "fw-01"))
+        );
+        assert_eq!(tdc.field("pid"), Some(&DataField::from_digit("pid", 42)));
+        assert_eq!(
+            tdc.field("act"),
+            Some(&DataField::from_chars("act", "deny"))
+        );
+    }
+
+    #[test]
+    fn test_fields_display() {
+        let pkg = wpl_package
+            .parse(r#"package vendor { use common/head fields tail { (kv) } rule fw { @head, @tail } }"#)
+            .assert();
+        let code = pkg.to_string();
+        assert!(code.contains("use common/head"));
+        assert!(code.contains("fields tail {"));
+        assert!(code.contains("@head,"));
+        let again = wpl_package.parse(code.as_str()).assert();
+        assert_eq!(again.to_string(), code);
+    }
+
+    #[test]
+    fn test_fields_errors() {
+        let mut pkgs = parse_pkgs(&[r#"package a { rule r { @nope } }"#]);
+        assert!(reason(&mut pkgs).contains("unknown fields '@nope'"));
+
+        let mut pkgs = parse_pkgs(&[r#"package a { use b/x rule r { @x } }"#]);
+        assert!(reason(&mut pkgs).contains("package 'b' not found"));
+
+        let mut pkgs = parse_pkgs(&[
+            r#"package a { use b/y rule r { @y } }"#,
+            r#"package b { fields x { (digit) } }"#,
+        ]);
+        assert!(reason(&mut pkgs).contains("fields 'y' not declared"));
+
+        let mut pkgs = parse_pkgs(&[
+            r#"package a { use b/y fields x { @y } }"#,
+            r#"package b { use a/x fields y { (digit), @x } }"#,
+        ]);
+        assert!(reason(&mut pkgs).contains("fields cycle: a/x -> b/y -> a/x"));
+
+        // 规则与字段组同名不构成循环
+        let mut pkgs = parse_pkgs(&[r#"package a { fields x { (digit) } rule x { @x } }"#]);
+        resolve_pkg_refs(&mut pkgs).assert();
+        let expr = pkgs[0].rules.front().unwrap().statement.express().unwrap();
+        assert!(expr.group.iter().all(|x| x.refer.is_none()));
+
+        let mut pkgs = parse_pkgs(&[r#"package a { fields x { (digit) } fields x { (ip) } }"#]);
+        assert!(reason(&mut pkgs).contains("duplicate fields 'a/x'"));
+
+        // 未展开的引用不能直接编译
+        let pkgs = parse_pkgs(&[r#"package a { rule r { @x } }"#]);
+        let expr = pkgs[0].rules.front().unwrap().statement.express().unwrap();
+        assert!(WplEvaluator::from(expr, None).is_err());
+    }
+}
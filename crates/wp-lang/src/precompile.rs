@@ -1,5 +1,3 @@
-use wp_model_core::model::DataType;
-
 use crate::ParserFactory;
 use crate::ast::{WplRule, WplSep, WplStatementType};
 use crate::generator::{FmtField, FmtFieldVec, GenChannel, NamedFieldGF};
@@ -55,8 +53,7 @@ pub fn compile_rule(
                     let f_conf_cloned = f_conf.clone();
                     let sep_cloned = sep.clone();
                     let field_fn: FieldGenFn = Box::new(move |ch: &mut GenChannel| {
-                        let meta = DataType::from(f_conf_cloned.meta_name.as_str())?;
-                        let parser = ParserFactory::create(&meta)?;
+                        let parser = ParserFactory::create_by_conf(&f_conf_cloned)?;
                         let f = parser.generate(ch, &sep_cloned, &f_conf_cloned, gconf.as_ref())?;
                         Ok(f)
                     });
@@ -65,5 +62,11 @@ pub fn compile_rule(
             }
             Ok(CompiledRule::new(items))
         }
+        _ => Err(crate::parser::error::WplCodeError::from(
+            crate::parser::error::WplCodeReason::UnSupport(format!(
+                "'{}' is not a rule",
+                rule.name
+            )),
+        )),
     }
 }
@@ -1,10 +1,16 @@
 use anyhow::Context;
 use glob::glob;
-use std::{fs::File, io::Read, path::PathBuf};
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
 use orion_error::{ContextRecord, ErrorOwe, ErrorWith, OperationContext};
 use wp_log::info_ctrl;
 
+use crate::eval::{register_grok_patterns, register_proto_descriptors, register_ua_db};
+use crate::parser::error::{WplCodeError, WplCodeReason};
 use crate::{WplCode, parser::error::WplCodeResult, types::AnyResult};
 
 pub fn fetch_wpl_data(path: &str, target: &str) -> WplCodeResult<Vec<WplCode>> {
@@ -23,6 +29,78 @@ pub fn fetch_wpl_data(path: &str, target: &str) -> WplCodeResult<Vec<WplCode>> {
     }
     Ok(wpl_vec)
 }
+/// 加载 grok 模式目录（目录下每个文件每行 `NAME regex`），目录不存在时忽略
+pub fn fetch_grok_patterns(path: &str) -> WplCodeResult<usize> {
+    let mut ctx = OperationContext::want("load grok patterns");
+    ctx.record("path", path);
+    if !Path::new(path).is_dir() {
+        return Ok(0);
+    }
+    let mut files = std::fs::read_dir(path)
+        .owe_conf()
+        .with(&ctx)?
+        .filter_map(|x| x.ok().map(|e| e.path()))
+        .filter(|x| x.is_file())
+        .filter(|x| {
+            x.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| !n.starts_with('.'))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    let mut count = 0;
+    for f_name in &files {
+        info_ctrl!("load grok patterns: {:?}", f_name);
+        let file_data = std::fs::read_to_string(f_name).owe_conf().with(&ctx)?;
+        count += register_grok_patterns(f_name.display().to_string().as_str(), &file_data)
+            .map_err(|e| WplCodeError::from(WplCodeReason::Syntax(e)))
+            .with(&ctx)?;
+    }
+    Ok(count)
+}
+/// 加载 uap-core 格式的 user-agent 正则库，文件不存在时沿用内置库
+pub fn fetch_ua_db(path: &str) -> WplCodeResult<bool> {
+    let mut ctx = OperationContext::want("load uap regexes");
+    ctx.record("path", path);
+    if !Path::new(path).is_file() {
+        return Ok(false);
+    }
+    info_ctrl!("load uap regexes: {}", path);
+    let file_data = std::fs::read_to_string(path).owe_conf().with(&ctx)?;
+    register_ua_db(&file_data)
+        .map_err(|e| WplCodeError::from(WplCodeReason::Syntax(e)))
+        .with(&ctx)?;
+    Ok(true)
+}
+/// 加载 protobuf 描述符集目录（`*.desc`/`*.pb`/`*.binpb`），目录不存在时忽略
+pub fn fetch_proto_descriptors(path: &str) -> WplCodeResult<usize> {
+    let mut ctx = OperationContext::want("load proto descriptors");
+    ctx.record("path", path);
+    if !Path::new(path).is_dir() {
+        return Ok(0);
+    }
+    let mut files = std::fs::read_dir(path)
+        .owe_conf()
+        .with(&ctx)?
+        .filter_map(|x| x.ok().map(|e| e.path()))
+        .filter(|x| x.is_file())
+        .filter(|x| {
+            x.extension()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| matches!(n, "desc" | "pb" | "binpb"))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    let mut count = 0;
+    for f_name in &files {
+        info_ctrl!("load proto descriptors: {:?}", f_name);
+        let file_data = std::fs::read(f_name).owe_conf().with(&ctx)?;
+        count += register_proto_descriptors(&file_data)
+            .map_err(|e| WplCodeError::from(WplCodeReason::Syntax(e)))
+            .with(&ctx)?;
+    }
+    Ok(count)
+}
 fn find_conf_files(path: &str, target: &str) -> AnyResult<Vec<PathBuf>> {
     let mut found = Vec::new();
     info_ctrl!("find conf files in: {}", path);
@@ -9,6 +9,9 @@ pub enum OmlIssueKind {
     UnsupportedConvert, // 不支持的类型转换
     ParseFail,          // 文本解析为目标类型失败
     BatchNoMatch,       // 批量匹配 0 命中
+    DivByZero,          // 表达式除零/模零
+    TypeMismatch,       // 表达式操作数类型不匹配
+    Overflow,           // 表达式整数运算溢出
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +34,9 @@ impl OmlIssue {
             OmlIssueKind::UnsupportedConvert => format!("unsupported_convert: {}", self.detail),
             OmlIssueKind::ParseFail => format!("parse_fail: {}", self.detail),
             OmlIssueKind::BatchNoMatch => format!("batch_no_match: {}", self.detail),
+            OmlIssueKind::DivByZero => format!("div_by_zero: {}", self.detail),
+            OmlIssueKind::TypeMismatch => format!("type_mismatch: {}", self.detail),
+            OmlIssueKind::Overflow => format!("overflow: {}", self.detail),
         }
     }
 }
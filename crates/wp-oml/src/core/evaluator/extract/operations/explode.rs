@@ -0,0 +1,155 @@
+use crate::core::prelude::*;
+use crate::language::ExplodeOperation;
+
+impl ExplodeOperation {
+    /// 按数组元素生成输入记录：`keep` 父级字段在前，元素字段在后（对象元素按成员平铺）。
+    /// 数组字段缺失或不是数组时返回 None，由调用方按单条记录处理
+    pub fn explode(&self, data: &DataRecord) -> Option<Vec<DataRecord>> {
+        let Value::Array(elements) = data.field(self.array())?.get_value() else {
+            return None;
+        };
+        let parents: Vec<&DataField> = self
+            .keep()
+            .iter()
+            .filter_map(|name| data.field(name))
+            .collect();
+        let mut out = Vec::with_capacity(elements.len());
+        for elem in elements {
+            let mut rec = DataRecord::default();
+            for parent in parents.iter() {
+                rec.append((*parent).clone());
+            }
+            match elem.get_value() {
+                Value::Obj(obj) => {
+                    for (name, field) in obj.iter() {
+                        let mut field = field.clone();
+                        field.set_name(name.as_str());
+                        rec.append(field);
+                    }
+                }
+                _ => {
+                    let mut field = elem.clone();
+                    field.set_name(self.array().as_str());
+                    rec.append(field);
+                }
+            }
+            out.push(rec);
+        }
+        Some(out)
+    }
+
+    /// 将 `keep` 父级字段补齐到输出（模型已显式产出的同名字段优先）
+    pub fn fill_keep(&self, parent: &DataRecord, out: &mut DataRecord) {
+        for name in self.keep() {
+            if out.field(name).is_some() {
+                continue;
+            }
+            if let Some(field) = parent.field(name) {
+                out.append(field.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::DataTransformer;
+    use crate::parser::oml_parse;
+    use orion_error::TestAssertWithMsg;
+    use wp_data_model::cache::FieldQueryCache;
+    use wp_model_core::model::types::value::ObjectValue;
+    use wp_model_core::model::{DataField, DataRecord, Value};
+
+    fn event(user: &str, action: &str) -> DataField {
+        let mut obj = ObjectValue::default();
+        obj.insert("user", DataField::from_chars("user", user));
+        obj.insert("action", DataField::from_chars("action", action));
+        DataField::from_obj("", obj)
+    }
+
+    fn audit_log() -> DataRecord {
+        let mut src = DataRecord::default();
+        src.append(DataField::from_chars("host", "cloud-a"));
+        src.append(DataField::from_chars("account", "1001"));
+        src.append(DataField::from_arr(
+            "records",
+            vec![event("alice", "login"), event("bob", "delete")],
+        ));
+        src
+    }
+
+    #[test]
+    fn test_explode_records() {
+        let mut code = r#"
+name : test
+explode : records keep (host, account) ;
+---
+who    = read(user) ;
+action = read() ;
+"#;
+        let model = oml_parse(&mut code).assert("oml_parse");
+        let mut cache = FieldQueryCache::default();
+        let outs = model.transform_many(&audit_log(), &mut cache).unwrap();
+        assert_eq!(outs.len(), 2);
+        assert_eq!(outs[0].get_value("who"), Some(&Value::from("alice")));
+        assert_eq!(outs[1].get_value("action"), Some(&Value::from("delete")));
+        for out in outs.iter() {
+            assert_eq!(out.get_value("host"), Some(&Value::from("cloud-a")));
+            assert_eq!(out.get_value("account"), Some(&Value::from("1001")));
+        }
+
+        // 空数组不产出记录；缺少数组字段按单条处理
+        let mut empty = DataRecord::default();
+        empty.append(DataField::from_arr("records", Vec::new()));
+        assert_eq!(model.transform_many(&empty, &mut cache), Some(Vec::new()));
+        let mut plain = DataRecord::default();
+        plain.append(DataField::from_chars("user", "carol"));
+        let outs = model.transform_many(&plain, &mut cache).unwrap();
+        assert_eq!(outs.len(), 1);
+        assert_eq!(outs[0].get_value("who"), Some(&Value::from("carol")));
+    }
+
+    #[test]
+    fn test_explode_scalar_array() {
+        let mut code = r#"
+name : test
+explode : tags ;
+---
+tag = read(tags) ;
+"#;
+        let model = oml_parse(&mut code).assert("oml_parse");
+        let mut src = DataRecord::default();
+        src.append(DataField::from_arr(
+            "tags",
+            vec![
+                DataField::from_chars("", "a"),
+                DataField::from_chars("", "b"),
+            ],
+        ));
+        let outs = model
+            .transform_many(&src, &mut FieldQueryCache::default())
+            .unwrap();
+        let tags: Vec<_> = outs.iter().filter_map(|x| x.get_value("tag")).collect();
+        assert_eq!(tags, vec![&Value::from("a"), &Value::from("b")]);
+    }
+
+    #[test]
+    fn test_explode_filtered_vs_empty() {
+        let mut code = r#"
+name : test
+drop when : match read(host) { chars(noise) } ;
+explode : records ;
+---
+who = read(user) ;
+"#;
+        let model = oml_parse(&mut code).assert("oml_parse");
+        let mut cache = FieldQueryCache::default();
+        // 过滤返回 None，空数组返回空 Vec，调用方据此区分丢弃计数
+        let mut dropped = DataRecord::default();
+        dropped.append(DataField::from_chars("host", "noise"));
+        assert!(model.transform_many(&dropped, &mut cache).is_none());
+        let mut empty = DataRecord::default();
+        empty.append(DataField::from_arr("records", Vec::new()));
+        assert_eq!(model.transform_many(&empty, &mut cache), Some(Vec::new()));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::core::prelude::*;
+use crate::language::{DirectAccessor, FilterMode, MatchAble, MatchSource, RecordFilter};
+
+impl RecordFilter {
+    /// 判断记录是否应被丢弃；取不到数据源字段视为未命中
+    pub fn is_drop(&self, data: &DataRecord) -> bool {
+        let empty = DataRecord::default();
+        let mut src = DataRecordRef::from(data);
+        let matched = match self.source() {
+            MatchSource::Single(dat) => match filter_field(dat, &mut src, &empty) {
+                Some(x) => self.conds().iter().any(|c| c.is_match(&x)),
+                None => false,
+            },
+            MatchSource::Double(fst, sec) => {
+                let fst_val = filter_field(fst, &mut src, &empty);
+                let sec_val = filter_field(sec, &mut src, &empty);
+                match (fst_val, sec_val) {
+                    (Some(a), Some(b)) => self.conds().iter().any(|c| c.is_match((&a, &b))),
+                    _ => false,
+                }
+            }
+        };
+        match self.mode() {
+            FilterMode::Keep => !matched,
+            FilterMode::Drop => matched,
+        }
+    }
+}
+
+fn filter_field(
+    dat: &DirectAccessor,
+    src: &mut DataRecordRef<'_>,
+    dst: &DataRecord,
+) -> Option<DataField> {
+    let key = dat.field_name().clone().unwrap_or_default();
+    let cur = EvaluationTarget::new(key, DataType::Auto);
+    dat.extract_one(&cur, src, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::DataTransformer;
+    use crate::parser::oml_parse;
+    use orion_error::TestAssertWithMsg;
+    use wp_data_model::cache::FieldQueryCache;
+    use wp_model_core::model::{DataField, DataRecord, Value};
+
+    fn record(level: &str, status: i64) -> DataRecord {
+        let mut src = DataRecord::default();
+        src.append(DataField::from_chars("level", level));
+        src.append(DataField::from_digit("status", status));
+        src
+    }
+
+    #[test]
+    fn test_where_filter() {
+        let mut code = r#"
+name : test
+where : match read(level) { chars(error), chars(warn) } ;
+---
+level = read() ;
+"#;
+        let model = oml_parse(&mut code).assert("oml_parse");
+        let mut cache = FieldQueryCache::default();
+        let out = model.transform_or_drop(&record("error", 500), &mut cache);
+        assert_eq!(
+            out.and_then(|x| x.get_value("level").cloned()),
+            Some(Value::from("error"))
+        );
+        assert!(
+            model
+                .transform_or_drop(&record("debug", 200), &mut cache)
+                .is_none()
+        );
+        // transform 对被过滤记录输出空记录
+        assert!(
+            model
+                .transform(record("info", 200), &mut cache)
+                .items
+                .is_empty()
+        );
+        // 缺少字段视为未命中
+        assert!(
+            model
+                .transform_or_drop(&DataRecord::default(), &mut cache)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_drop_when_filter() {
+        let mut code = r#"
+name : test
+drop when : match read(status) { in (digit(200), digit(299)) } ;
+---
+status = read() ;
+"#;
+        let model = oml_parse(&mut code).assert("oml_parse");
+        let mut cache = FieldQueryCache::default();
+        assert!(
+            model
+                .transform_or_drop(&record("info", 204), &mut cache)
+                .is_none()
+        );
+        assert!(
+            model
+                .transform_or_drop(&record("error", 500), &mut cache)
+                .is_some()
+        );
+        assert!(
+            model
+                .transform_or_drop(&DataRecord::default(), &mut cache)
+                .is_some()
+        );
+        assert!(model.to_string().contains("drop when : match"));
+    }
+}
@@ -1,4 +1,6 @@
 mod array;
+mod explode;
+mod filter;
 mod map;
 mod matchs;
 mod other;
@@ -62,5 +62,22 @@ pub trait DataTransformer {
     fn transform_ref(&self, data: &DataRecord, cache: &mut FieldQueryCache) -> DataRecord {
         self.transform(data.clone(), cache)
     }
+    /// 带过滤语义的变换：返回 None 表示记录被模型的 `where`/`drop when` 丢弃
+    fn transform_or_drop(
+        &self,
+        data: &DataRecord,
+        cache: &mut FieldQueryCache,
+    ) -> Option<DataRecord> {
+        Some(self.transform_ref(data, cache))
+    }
+    /// 1→N 变换：模型声明 `explode` 时按数组元素展开；
+    /// 返回 None 表示记录被过滤，空数组展开得到空 Vec
+    fn transform_many(
+        &self,
+        data: &DataRecord,
+        cache: &mut FieldQueryCache,
+    ) -> Option<Vec<DataRecord>> {
+        self.transform_or_drop(data, cache).map(|out| vec![out])
+    }
     fn append(&self, data: &mut DataRecord);
 }
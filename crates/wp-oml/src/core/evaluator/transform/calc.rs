@@ -0,0 +1,278 @@
+use crate::core::diagnostics::{self, OmlIssue, OmlIssueKind};
+use crate::core::prelude::*;
+use crate::language::{CalcExpr, CalcOp, CalcOperation};
+use std::cmp::Ordering;
+use wp_data_fmt::{DataFormat, Raw};
+
+impl FieldExtractor for CalcOperation {
+    fn extract_one(
+        &self,
+        target: &EvaluationTarget,
+        src: &mut DataRecordRef<'_>,
+        dst: &DataRecord,
+    ) -> Option<DataField> {
+        let value = calc_eval(self.expr(), target, src, dst)?;
+        let name = target.safe_name();
+        let field = match value {
+            Value::Digit(v) => DataField::from_digit(name, v),
+            Value::Float(v) => DataField::from_float(name, v),
+            Value::Bool(v) => DataField::from_bool(name, v),
+            Value::Chars(v) => DataField::from_chars(name, v.to_string()),
+            other => DataField::new(DataType::Auto, name, other),
+        };
+        Some(field)
+    }
+}
+
+// 出错时记录诊断并返回 None，不产出目标字段
+fn calc_eval(
+    expr: &CalcExpr,
+    target: &EvaluationTarget,
+    src: &mut DataRecordRef<'_>,
+    dst: &DataRecord,
+) -> Option<Value> {
+    match expr {
+        CalcExpr::Val(v) => Some(v.clone()),
+        CalcExpr::Var(op) => match op.extract_one(target, src, dst) {
+            Some(field) => Some(field.get_value().clone()),
+            None => {
+                diagnostics::push(OmlIssue::new(
+                    OmlIssueKind::MissingField,
+                    op.dat_get().to_string(),
+                ));
+                None
+            }
+        },
+        CalcExpr::Concat(items) => {
+            let mut out = String::new();
+            for item in items {
+                let v = calc_eval(item, target, src, dst)?;
+                match v {
+                    Value::Chars(s) => out.push_str(s.as_str()),
+                    other => {
+                        let field = DataField::new(DataType::Auto, "_", other);
+                        out.push_str(&Raw.format_field(&field).to_string());
+                    }
+                }
+            }
+            Some(Value::from(out.as_str()))
+        }
+        CalcExpr::Binary(l, op, r) => {
+            let lv = calc_eval(l, target, src, dst)?;
+            let rv = calc_eval(r, target, src, dst)?;
+            let out = binary_eval(&lv, *op, &rv);
+            if out.is_none() {
+                debug_data!("oml calc fail: {} {} {}", lv, op, rv);
+            }
+            out
+        }
+    }
+}
+
+enum Num {
+    Int(i64),
+    Real(f64),
+}
+
+fn as_num(v: &Value) -> Option<Num> {
+    match v {
+        Value::Digit(x) => Some(Num::Int(*x)),
+        Value::Float(x) => Some(Num::Real(*x)),
+        _ => None,
+    }
+}
+
+fn mismatch(l: &Value, op: CalcOp, r: &Value) -> Option<Value> {
+    diagnostics::push(OmlIssue::new(
+        OmlIssueKind::TypeMismatch,
+        format!("{} {} {}", l, op, r),
+    ));
+    None
+}
+
+pub(crate) fn binary_eval(l: &Value, op: CalcOp, r: &Value) -> Option<Value> {
+    if op.is_cmp() {
+        return compare_eval(l, op, r);
+    }
+    let (Some(ln), Some(rn)) = (as_num(l), as_num(r)) else {
+        return mismatch(l, op, r);
+    };
+    if matches!(op, CalcOp::Div | CalcOp::Mod) && is_zero(&rn) {
+        diagnostics::push(OmlIssue::new(
+            OmlIssueKind::DivByZero,
+            format!("{} {} {}", l, op, r),
+        ));
+        return None;
+    }
+    match (ln, rn) {
+        // 整数运算溢出（含 i64::MIN % -1）单独上报
+        (Num::Int(a), Num::Int(b)) => {
+            let v = match op {
+                CalcOp::Add => a.checked_add(b),
+                CalcOp::Sub => a.checked_sub(b),
+                CalcOp::Mul => a.checked_mul(b),
+                CalcOp::Div => a.checked_div(b),
+                CalcOp::Mod => a.checked_rem(b),
+                _ => return None,
+            };
+            if v.is_none() {
+                diagnostics::push(OmlIssue::new(
+                    OmlIssueKind::Overflow,
+                    format!("{} {} {}", l, op, r),
+                ));
+            }
+            v.map(Value::Digit)
+        }
+        (a, b) => {
+            let (a, b) = (to_f64(a), to_f64(b));
+            let v = match op {
+                CalcOp::Add => a + b,
+                CalcOp::Sub => a - b,
+                CalcOp::Mul => a * b,
+                CalcOp::Div => a / b,
+                CalcOp::Mod => a % b,
+                _ => return None,
+            };
+            Some(Value::Float(v))
+        }
+    }
+}
+
+fn is_zero(n: &Num) -> bool {
+    match n {
+        Num::Int(x) => *x == 0,
+        Num::Real(x) => *x == 0.0,
+    }
+}
+
+fn to_f64(n: Num) -> f64 {
+    match n {
+        Num::Int(x) => x as f64,
+        Num::Real(x) => x,
+    }
+}
+
+fn compare_eval(l: &Value, op: CalcOp, r: &Value) -> Option<Value> {
+    let ord = match (as_num(l), as_num(r)) {
+        (Some(Num::Int(a)), Some(Num::Int(b))) => Some(a.cmp(&b)),
+        (Some(a), Some(b)) => to_f64(a).partial_cmp(&to_f64(b)),
+        _ => match (l, r) {
+            (Value::Chars(a), Value::Chars(b)) => Some(a.cmp(b)),
+            (Value::Bool(a), Value::Bool(b)) if matches!(op, CalcOp::Eq | CalcOp::Ne) => {
+                Some(a.cmp(b))
+            }
+            _ => return mismatch(l, op, r),
+        },
+    };
+    // NaN 参与比较时除 != 外均为 false
+    let res = match (op, ord) {
+        (CalcOp::Ne, None) => true,
+        (_, None) => false,
+        (CalcOp::Eq, Some(o)) => o == Ordering::Equal,
+        (CalcOp::Ne, Some(o)) => o != Ordering::Equal,
+        (CalcOp::Gt, Some(o)) => o == Ordering::Greater,
+        (CalcOp::Ge, Some(o)) => o != Ordering::Less,
+        (CalcOp::Lt, Some(o)) => o == Ordering::Less,
+        (CalcOp::Le, Some(o)) => o != Ordering::Greater,
+        _ => false,
+    };
+    Some(Value::Bool(res))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DataTransformer;
+    use crate::parser::oml_parse;
+    use orion_error::TestAssertWithMsg;
+
+    #[test]
+    fn test_binary_eval() {
+        assert_eq!(
+            binary_eval(&Value::Digit(7), CalcOp::Add, &Value::Digit(3)),
+            Some(Value::Digit(10))
+        );
+        assert_eq!(
+            binary_eval(&Value::Digit(7), CalcOp::Div, &Value::Digit(2)),
+            Some(Value::Digit(3))
+        );
+        assert_eq!(
+            binary_eval(&Value::Digit(7), CalcOp::Div, &Value::Float(2.0)),
+            Some(Value::Float(3.5))
+        );
+        assert_eq!(
+            binary_eval(&Value::Digit(7), CalcOp::Mod, &Value::Digit(0)),
+            None
+        );
+        assert_eq!(
+            binary_eval(&Value::Float(1.0), CalcOp::Div, &Value::Float(0.0)),
+            None
+        );
+        assert_eq!(
+            binary_eval(&Value::from("a"), CalcOp::Add, &Value::Digit(1)),
+            None
+        );
+        assert_eq!(
+            binary_eval(&Value::Digit(i64::MAX), CalcOp::Add, &Value::Digit(1)),
+            None
+        );
+        assert_eq!(
+            binary_eval(&Value::Digit(i64::MIN), CalcOp::Mod, &Value::Digit(-1)),
+            None
+        );
+        assert_eq!(
+            binary_eval(&Value::Digit(2), CalcOp::Ge, &Value::Float(1.5)),
+            Some(Value::Bool(true))
+        );
+        assert_eq!(
+            binary_eval(&Value::from("abc"), CalcOp::Eq, &Value::from("abc")),
+            Some(Value::Bool(true))
+        );
+        assert_eq!(
+            binary_eval(&Value::from("abc"), CalcOp::Lt, &Value::Digit(1)),
+            None
+        );
+    }
+
+    #[cfg(feature = "oml-diag")]
+    #[test]
+    fn test_overflow_diagnostic() {
+        diagnostics::reset();
+        binary_eval(&Value::Digit(i64::MIN), CalcOp::Mod, &Value::Digit(-1));
+        let issues = diagnostics::take();
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0].kind, OmlIssueKind::Overflow));
+    }
+
+    #[test]
+    fn test_calc_transform() {
+        let mut code = r#"
+name : test
+---
+total    = read(bytes_in) + read(bytes_out) ;
+secs     = read(duration) / 1000 ;
+ratio    = read(bytes_in) * 1.0 / read(bytes_out) ;
+mixed    = (read(bytes_in) - 10) * 2 % 7 ;
+big      = read(bytes_in) > 100 ;
+key      = concat(read(host), "-", read(bytes_in)) ;
+bad      = read(duration) / 0 ;
+oops     = read(host) + 1 ;
+"#;
+        let model = oml_parse(&mut code).assert("oml_parse");
+        let mut src = DataRecord::default();
+        src.append(DataField::from_digit("bytes_in", 150));
+        src.append(DataField::from_digit("bytes_out", 50));
+        src.append(DataField::from_digit("duration", 4500));
+        src.append(DataField::from_chars("host", "web01"));
+
+        let out = model.transform(src, &mut FieldQueryCache::default());
+        assert_eq!(out.get_value("total"), Some(&Value::Digit(200)));
+        assert_eq!(out.get_value("secs"), Some(&Value::Digit(4)));
+        assert_eq!(out.get_value("ratio"), Some(&Value::Float(3.0)));
+        assert_eq!(out.get_value("mixed"), Some(&Value::Digit(280 % 7)));
+        assert_eq!(out.get_value("big"), Some(&Value::Bool(true)));
+        assert_eq!(out.get_value("key"), Some(&Value::from("web01-150")));
+        assert!(out.get_value("bad").is_none());
+        assert!(out.get_value("oops").is_none());
+    }
+}
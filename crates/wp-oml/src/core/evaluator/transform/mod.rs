@@ -1,3 +1,4 @@
+mod calc;
 mod conv;
 mod fmt;
 mod pipe;
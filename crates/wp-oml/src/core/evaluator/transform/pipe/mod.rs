@@ -6,6 +6,7 @@ mod base64;
 mod escape;
 mod net;
 pub mod other;
+mod regex;
 mod time;
 
 impl ValueProcessor for PipeFun {
@@ -32,6 +33,9 @@ impl ValueProcessor for PipeFun {
             PipeFun::PathGet(o) => o.value_cacu(in_val),
             PipeFun::UrlGet(o) => o.value_cacu(in_val),
             PipeFun::Ip4ToInt(o) => o.value_cacu(in_val),
+            PipeFun::RegexExtract(o) => o.value_cacu(in_val),
+            PipeFun::RegexReplace(o) => o.value_cacu(in_val),
+            PipeFun::RegexMatch(o) => o.value_cacu(in_val),
         }
     }
 }
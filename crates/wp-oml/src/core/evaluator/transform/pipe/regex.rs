@@ -0,0 +1,137 @@
+use crate::core::prelude::*;
+use crate::language::{RegexExtract, RegexMatch, RegexReplace};
+use wp_model_core::model::{DataField, Value};
+
+impl ValueProcessor for RegexExtract {
+    fn value_cacu(&self, in_val: DataField) -> DataField {
+        match in_val.get_value() {
+            Value::Chars(x) => {
+                // 未匹配或捕获组为空时输出忽略字段
+                match self.re.captures(x).and_then(|c| c.get(self.group)) {
+                    Some(m) => DataField::from_chars(in_val.get_name().to_string(), m.as_str()),
+                    None => DataField::from_ignore(in_val.get_name()),
+                }
+            }
+            _ => in_val,
+        }
+    }
+}
+
+impl ValueProcessor for RegexReplace {
+    fn value_cacu(&self, in_val: DataField) -> DataField {
+        match in_val.get_value() {
+            Value::Chars(x) => {
+                let out = self.re.replace_all(x, self.repl.as_str()).to_string();
+                DataField::from_chars(in_val.get_name().to_string(), out)
+            }
+            _ => in_val,
+        }
+    }
+}
+
+impl ValueProcessor for RegexMatch {
+    fn value_cacu(&self, in_val: DataField) -> DataField {
+        let matched = match in_val.get_value() {
+            Value::Chars(x) => self.re.is_match(x),
+            _ => false,
+        };
+        DataField::from_bool(in_val.get_name().to_string(), matched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::DataTransformer;
+    use crate::parser::oml_parse;
+    use orion_error::TestAssert;
+    use wp_data_model::cache::FieldQueryCache;
+    use wp_model_core::model::{DataField, DataRecord, Value};
+
+    #[test]
+    fn test_pipe_regex() {
+        let cache = &mut FieldQueryCache::default();
+        let data = vec![DataField::from_chars(
+            "msg",
+            "user=alice id=1001 from 10.0.0.1",
+        )];
+        let src = DataRecord { items: data };
+
+        let mut conf = r##"
+        name : test
+        ---
+        user  = pipe read(msg) | regex_extract("user=(\\w+)", 1) ;
+        id    = pipe read(msg) | regex_extract(r#"id=(?P<id>\d+)"#, 1) ;
+        none  = pipe read(msg) | regex_extract("port=([0-9]+)", 1) ;
+        mask  = pipe read(msg) | regex_replace(r#"\d+\.\d+\.\d+\.(\d+)"#, "x.x.x.$1") ;
+        is_ip = pipe read(msg) | regex_match(r#"\d+\.\d+\.\d+\.\d+"#) ;
+        is_v6 = read(msg) | regex_match("::") ;
+         "##;
+        let model = oml_parse(&mut conf).assert();
+        let target = model.transform(src, cache);
+        assert_eq!(target.get_value("user"), Some(&Value::from("alice")));
+        assert_eq!(target.get_value("id"), Some(&Value::from("1001")));
+        assert_eq!(
+            target.field("none"),
+            Some(DataField::from_ignore("none")).as_ref()
+        );
+        assert_eq!(
+            target.get_value("mask"),
+            Some(&Value::from("user=alice id=1001 from x.x.x.1"))
+        );
+        assert_eq!(target.get_value("is_ip"), Some(&Value::Bool(true)));
+        assert_eq!(target.get_value("is_v6"), Some(&Value::Bool(false)));
+    }
+
+    #[test]
+    fn test_pipe_regex_display_roundtrip() {
+        // 模式与替换串同时含 `"` 与 `"#`，回显后仍可重新解析且行为一致
+        let mut conf = r##"
+        name : test
+        ---
+        tag  = pipe read(msg) | regex_extract("say \"#(\\w+)\"", 1) ;
+        repl = pipe read(msg) | regex_replace(r#"(\w+)"#, "\"#$1\\") ;
+        plain = pipe read(msg) | regex_match(r#"\d+"#) ;
+         "##;
+        let model = oml_parse(&mut conf).assert();
+        let shown = model.to_string();
+        let mut code = shown.as_str();
+        let reparsed = oml_parse(&mut code).assert();
+        assert_eq!(reparsed.to_string(), shown);
+
+        let src = DataRecord {
+            items: vec![DataField::from_chars("msg", r##"say "#hi""##)],
+        };
+        let cache = &mut FieldQueryCache::default();
+        let a = model.transform(src.clone(), cache);
+        let b = reparsed.transform(src, cache);
+        assert_eq!(a.get_value("tag"), Some(&Value::from("hi")));
+        assert_eq!(a.get_value("tag"), b.get_value("tag"));
+        assert_eq!(a.get_value("repl"), b.get_value("repl"));
+        assert_eq!(a.get_value("plain"), Some(&Value::Bool(false)));
+    }
+
+    #[test]
+    fn test_pipe_regex_invalid() {
+        let mut conf = r#"
+        name : test
+        ---
+        user = pipe read(msg) | regex_extract("user=(", 1) ;
+         "#;
+        assert!(oml_parse(&mut conf).is_err());
+
+        // 捕获组越界在加载时拒绝
+        let mut conf = r#"
+        name : test
+        ---
+        user = pipe read(msg) | regex_extract("a(b)", 5) ;
+         "#;
+        let err = oml_parse(&mut conf).err().expect("group out of range");
+        assert!(format!("{:?}", err).contains("capture group out of range"));
+        let mut conf = r#"
+        name : test
+        ---
+        user = pipe read(msg) | regex_extract("a(b)", 1) ;
+         "#;
+        assert!(oml_parse(&mut conf).is_ok());
+    }
+}
@@ -57,6 +57,7 @@ impl FieldExtractor for PreciseEvaluator {
             PreciseEvaluator::Pipe(o) => o.extract_one(target, src, dst),
             PreciseEvaluator::Fun(o) => o.extract_one(target, src, dst),
             PreciseEvaluator::Fmt(o) => o.extract_one(target, src, dst),
+            PreciseEvaluator::Calc(o) => o.extract_one(target, src, dst),
             PreciseEvaluator::Collect(o) => o.extract_one(target, src, dst),
             PreciseEvaluator::Val(o) => o.extract_one(target, src, dst),
         }
@@ -77,6 +78,7 @@ impl FieldExtractor for PreciseEvaluator {
             PreciseEvaluator::Pipe(o) => o.extract_more(src, dst, cache),
             PreciseEvaluator::Fun(o) => o.extract_more(src, dst, cache),
             PreciseEvaluator::Fmt(o) => o.extract_more(src, dst, cache),
+            PreciseEvaluator::Calc(o) => o.extract_more(src, dst, cache),
             PreciseEvaluator::Collect(o) => o.extract_more(src, dst, cache),
             PreciseEvaluator::Val(o) => o.extract_more(src, dst, cache),
         }
@@ -92,6 +94,7 @@ impl FieldExtractor for PreciseEvaluator {
             PreciseEvaluator::Pipe(o) => o.support_batch(),
             PreciseEvaluator::Fun(o) => o.support_batch(),
             PreciseEvaluator::Fmt(o) => o.support_batch(),
+            PreciseEvaluator::Calc(o) => o.support_batch(),
             PreciseEvaluator::Collect(o) => o.support_batch(),
             PreciseEvaluator::Val(o) => o.support_batch(),
         }
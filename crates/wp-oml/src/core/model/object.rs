@@ -5,7 +5,7 @@ use crate::core::prelude::*;
 use crate::language::ObjModel;
 use crate::parser::error::OMLCodeErrorTait;
 use crate::parser::oml_parse;
-//use crate::privacy::PrivacyProcessor;
+use crate::privacy::{PrivacyConf, PrivacyProcessor};
 use orion_error::{ContextRecord, ErrorOwe, ErrorWith, WithContext};
 use wp_data_model::cache::FieldQueryCache;
 use wp_error::parse_error::{OMLCodeError, OMLCodeReason, OMLCodeResult};
@@ -18,14 +18,48 @@ impl DataTransformer for ObjModel {
     }
 
     fn transform_ref(&self, data: &DataRecord, cache: &mut FieldQueryCache) -> DataRecord {
+        // 被过滤的记录输出为空
+        self.transform_or_drop(data, cache).unwrap_or_default()
+    }
+
+    fn transform_or_drop(
+        &self,
+        data: &DataRecord,
+        cache: &mut FieldQueryCache,
+    ) -> Option<DataRecord> {
         diagnostics::reset();
-        let mut out = DataRecord::default();
-        let mut tdo_ref = DataRecordRef::from(data);
-        for ado in &self.items {
-            ado.eval_proc(&mut tdo_ref, &mut out, cache);
+        if self.is_filtered(data) {
+            return None;
         }
-        debug_data!("{} convert crate item : {}", self.name(), self.items.len());
-        out
+        Some(self.eval_record(data, cache))
+    }
+
+    fn transform_many(
+        &self,
+        data: &DataRecord,
+        cache: &mut FieldQueryCache,
+    ) -> Option<Vec<DataRecord>> {
+        diagnostics::reset();
+        if self.is_filtered(data) {
+            return None;
+        }
+        let Some(explode) = self.explode() else {
+            return Some(vec![self.eval_record(data, cache)]);
+        };
+        let Some(elements) = explode.explode(data) else {
+            return Some(vec![self.eval_record(data, cache)]);
+        };
+        debug_data!("{} explode {} records", self.name(), elements.len());
+        let outs = elements
+            .iter()
+            .map(|elem| {
+                let mut out = self.eval_items(elem, cache);
+                explode.fill_keep(data, &mut out);
+                self.apply_privacy(&mut out);
+                out
+            })
+            .collect();
+        Some(outs)
     }
 
     fn append(&self, data: &mut DataRecord) {
@@ -38,6 +72,49 @@ impl DataTransformer for ObjModel {
     }
 }
 
+impl ObjModel {
+    fn is_filtered(&self, data: &DataRecord) -> bool {
+        if let Some(filter) = self.filter()
+            && filter.is_drop(data)
+        {
+            debug_data!("{} drop record by filter", self.name());
+            return true;
+        }
+        false
+    }
+
+    fn eval_items(&self, data: &DataRecord, cache: &mut FieldQueryCache) -> DataRecord {
+        let mut out = DataRecord::default();
+        let mut tdo_ref = DataRecordRef::from(data);
+        for ado in &self.items {
+            ado.eval_proc(&mut tdo_ref, &mut out, cache);
+        }
+        debug_data!("{} convert crate item : {}", self.name(), self.items.len());
+        out
+    }
+
+    fn eval_record(&self, data: &DataRecord, cache: &mut FieldQueryCache) -> DataRecord {
+        let mut out = self.eval_items(data, cache);
+        self.apply_privacy(&mut out);
+        out
+    }
+
+    fn apply_privacy(&self, out: &mut DataRecord) {
+        if self.privacy().is_empty() {
+            return;
+        }
+        let conf = self
+            .privacy_conf()
+            .as_ref()
+            .unwrap_or_else(|| PrivacyConf::global());
+        for (name, proc) in self.privacy() {
+            if let Some(field) = out.get_value_mut(name) {
+                field.value = proc.process(&field.value, conf);
+            }
+        }
+    }
+}
+
 impl ConfADMExt for ObjModel {
     fn load(path: &str) -> OMLCodeResult<Self>
     where
@@ -60,4 +137,62 @@ impl ConfADMExt for ObjModel {
     }
 }
 
-// privacy-related tests removed in this edition
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wp_model_core::model::{DataField, Value};
+
+    #[test]
+    fn test_transform_with_privacy() {
+        let mut code = r#"
+name : test
+---
+phone  = take() ;
+src_ip = take() ;
+email  = take() ;
+---
+phone  : mask(3,4) ;
+src_ip : ip_prefix(24) ;
+"#;
+        let model = oml_parse(&mut code).expect("parse oml");
+        assert_eq!(model.privacy().len(), 2);
+
+        let mut src = DataRecord::default();
+        src.append(DataField::from_chars("phone", "13812345678"));
+        src.append(DataField::from_ip("src_ip", "10.1.2.3".parse().unwrap()));
+        src.append(DataField::from_chars("email", "a@b.com"));
+        let out = model.transform(src, &mut FieldQueryCache::default());
+        assert_eq!(out.get_value("phone"), Some(&Value::from("138****5678")));
+        assert_eq!(
+            out.get_value("src_ip"),
+            Some(&Value::IpAddr("10.1.2.0".parse().unwrap()))
+        );
+        assert_eq!(out.get_value("email"), Some(&Value::from("a@b.com")));
+
+        // 隐私段可回显
+        assert!(model.to_string().contains("src_ip : ip_prefix(24) ;"));
+    }
+
+    #[test]
+    fn test_privacy_conf_bound_per_model() {
+        let mut code = r#"
+name : test
+---
+phone = take() ;
+---
+phone : hash ;
+"#;
+        let mut model_a = oml_parse(&mut code).expect("parse oml");
+        assert!(model_a.needs_privacy_salt());
+        let mut model_b = model_a.clone();
+        model_a.bind_privacy_conf(PrivacyConf::new("salt-a"));
+        model_b.bind_privacy_conf(PrivacyConf::new("salt-b"));
+
+        let mut src = DataRecord::default();
+        src.append(DataField::from_chars("phone", "13812345678"));
+        let cache = &mut FieldQueryCache::default();
+        let out_a = model_a.transform_ref(&src, cache);
+        let out_b = model_b.transform_ref(&src, cache);
+        assert_ne!(out_a.get_value("phone"), out_b.get_value("phone"));
+    }
+}
@@ -28,6 +28,28 @@ impl DataTransformer for DataModel {
         }
     }
 
+    fn transform_or_drop(
+        &self,
+        data: &DataRecord,
+        cache: &mut FieldQueryCache,
+    ) -> Option<DataRecord> {
+        match self {
+            DataModel::Stub(null_model) => null_model.transform_or_drop(data, cache),
+            DataModel::Object(obj_model) => obj_model.transform_or_drop(data, cache),
+        }
+    }
+
+    fn transform_many(
+        &self,
+        data: &DataRecord,
+        cache: &mut FieldQueryCache,
+    ) -> Option<Vec<DataRecord>> {
+        match self {
+            DataModel::Stub(null_model) => null_model.transform_many(data, cache),
+            DataModel::Object(obj_model) => obj_model.transform_many(data, cache),
+        }
+    }
+
     fn append(&self, data: &mut DataRecord) {
         match self {
             DataModel::Stub(null_model) => null_model.append(data),
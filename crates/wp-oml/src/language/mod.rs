@@ -17,17 +17,18 @@ pub use syntax::{
         FUN_NOW_TIME, FunOperation, Get, HtmlEscape, HtmlUnescape, Ip4ToInt, JsonEscape,
         JsonUnescape, NowDate, NowHour, NowTime, Nth, PIPE_BASE64_DECODE, PIPE_BASE64_ENCODE,
         PIPE_GET, PIPE_HTML_ESCAPE, PIPE_HTML_UNESCAPE, PIPE_IP4_TO_INT, PIPE_JSON_ESCAPE,
-        PIPE_JSON_UNESCAPE, PIPE_NTH, PIPE_PATH, PIPE_SKIP_EMPTY, PIPE_STR_ESCAPE, PIPE_SXF_GET,
-        PIPE_TIME_TO_TS, PIPE_TIME_TO_TS_MS, PIPE_TIME_TO_TS_US, PIPE_TIME_TO_TS_ZONE,
-        PIPE_TO_JSON, PIPE_TO_STR, PIPE_URL, PathGet, PathType, PipeFun, SkipEmpty, StrEscape,
-        SxfGet, TimeStampUnit, TimeToTs, TimeToTsMs, TimeToTsUs, TimeToTsZone, ToJson, ToStr,
-        UrlGet, UrlType,
+        PIPE_JSON_UNESCAPE, PIPE_NTH, PIPE_PATH, PIPE_REGEX_EXTRACT, PIPE_REGEX_MATCH,
+        PIPE_REGEX_REPLACE, PIPE_SKIP_EMPTY, PIPE_STR_ESCAPE, PIPE_SXF_GET, PIPE_TIME_TO_TS,
+        PIPE_TIME_TO_TS_MS, PIPE_TIME_TO_TS_US, PIPE_TIME_TO_TS_ZONE, PIPE_TO_JSON, PIPE_TO_STR,
+        PIPE_URL, PathGet, PathType, PipeFun, RegexExtract, RegexMatch, RegexReplace, SkipEmpty,
+        StrEscape, SxfGet, TimeStampUnit, TimeToTs, TimeToTsMs, TimeToTsUs, TimeToTsZone, ToJson,
+        ToStr, UrlGet, UrlType,
     },
     //lib_prm::LookupQuery,
     operations::{
-        FmtOperation, MapOperation, MatchAble, MatchCase, MatchCond, MatchCondition,
-        MatchOperation, MatchSource, PiPeOperation, RecordOperation, RecordOperationBuilder,
-        SqlQuery,
+        CalcExpr, CalcOp, CalcOperation, ExplodeOperation, FilterMode, FmtOperation, MapOperation,
+        MatchAble, MatchCase, MatchCond, MatchCondition, MatchOperation, MatchSource,
+        PiPeOperation, RecordFilter, RecordOperation, RecordOperationBuilder, SqlQuery,
     },
 };
 pub use types::model::DataModel;
@@ -1,6 +1,7 @@
 use crate::language::prelude::*;
 use crate::language::syntax::accessors::nested::arr::ArrOperation;
 use crate::language::syntax::functions::FunOperation;
+use crate::language::syntax::operations::calc::CalcOperation;
 use crate::language::syntax::operations::fmt::FmtOperation;
 use crate::language::syntax::operations::map::MapOperation;
 use crate::language::syntax::operations::matchs::MatchOperation;
@@ -42,6 +43,7 @@ pub enum PreciseEvaluator {
     Pipe(PiPeOperation),
     Fun(FunOperation),
     Fmt(FmtOperation),
+    Calc(CalcOperation),
     Collect(ArrOperation),
     Val(Value),
 }
@@ -63,6 +65,7 @@ impl Display for PreciseEvaluator {
             PreciseEvaluator::Pipe(x) => Display::fmt(x, f),
             PreciseEvaluator::Fun(x) => Display::fmt(x, f),
             PreciseEvaluator::Fmt(x) => Display::fmt(x, f),
+            PreciseEvaluator::Calc(x) => Display::fmt(x, f),
             PreciseEvaluator::Collect(x) => Display::fmt(x, f),
             PreciseEvaluator::Val(x) => Display::fmt(x, f),
         }
@@ -5,12 +5,14 @@ pub mod escape;
 pub mod fmt;
 pub mod net;
 pub mod other;
+pub mod regex;
 pub mod time;
 pub use base64::*;
 pub use escape::*;
 pub use fmt::*;
 pub use net::*;
 pub use other::*;
+pub use regex::*;
 pub use time::*;
 
 #[allow(clippy::large_enum_variant)]
@@ -37,6 +39,9 @@ pub enum PipeFun {
     PathGet(PathGet),
     UrlGet(UrlGet),
     Ip4ToInt(Ip4ToInt),
+    RegexExtract(RegexExtract),
+    RegexReplace(RegexReplace),
+    RegexMatch(RegexMatch),
 }
 
 impl Display for PipeFun {
@@ -63,6 +68,9 @@ impl Display for PipeFun {
             PipeFun::PathGet(v) => write!(f, "{}", v),
             PipeFun::UrlGet(v) => write!(f, "{}", v),
             PipeFun::Ip4ToInt(v) => write!(f, "{}", v),
+            PipeFun::RegexExtract(v) => write!(f, "{}", v),
+            PipeFun::RegexReplace(v) => write!(f, "{}", v),
+            PipeFun::RegexMatch(v) => write!(f, "{}", v),
         }
     }
 }
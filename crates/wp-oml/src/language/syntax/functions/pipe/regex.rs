@@ -0,0 +1,69 @@
+use crate::language::prelude::*;
+use regex::Regex;
+
+pub const PIPE_REGEX_EXTRACT: &str = "regex_extract";
+pub const PIPE_REGEX_REPLACE: &str = "regex_replace";
+pub const PIPE_REGEX_MATCH: &str = "regex_match";
+
+/// 取正则第 `group` 个捕获组；模式在模型加载时编译
+#[derive(Clone, Debug)]
+pub struct RegexExtract {
+    pub(crate) re: Regex,
+    pub(crate) group: usize,
+}
+
+/// 以 `repl` 替换全部匹配，`repl` 支持 `$1` / `${name}` 引用捕获组
+#[derive(Clone, Debug)]
+pub struct RegexReplace {
+    pub(crate) re: Regex,
+    pub(crate) repl: String,
+}
+
+/// 判断是否匹配，输出 bool
+#[derive(Clone, Debug)]
+pub struct RegexMatch {
+    pub(crate) re: Regex,
+}
+
+// 含引号或反斜杠时输出原始字符串；原始字符串无法容纳 `"#` 时改为转义的普通字符串，
+// 保证可被重新解析
+fn quote_arg(value: &str) -> String {
+    if value.contains("\"#") {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{}\"", escaped)
+    } else if value.contains('"') || value.contains('\\') {
+        format!("r#\"{}\"#", value)
+    } else {
+        format!("\"{}\"", value)
+    }
+}
+
+impl Display for RegexExtract {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({},{})",
+            PIPE_REGEX_EXTRACT,
+            quote_arg(self.re.as_str()),
+            self.group
+        )
+    }
+}
+
+impl Display for RegexReplace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({},{})",
+            PIPE_REGEX_REPLACE,
+            quote_arg(self.re.as_str()),
+            quote_arg(&self.repl)
+        )
+    }
+}
+
+impl Display for RegexMatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", PIPE_REGEX_MATCH, quote_arg(self.re.as_str()))
+    }
+}
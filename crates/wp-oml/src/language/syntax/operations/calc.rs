@@ -0,0 +1,121 @@
+use crate::language::prelude::*;
+
+use super::record::RecordOperation;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalcOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl CalcOp {
+    /// 优先级：比较 < 加减 < 乘除模
+    pub fn priority(&self) -> u8 {
+        match self {
+            CalcOp::Mul | CalcOp::Div | CalcOp::Mod => 3,
+            CalcOp::Add | CalcOp::Sub => 2,
+            _ => 1,
+        }
+    }
+    pub fn is_cmp(&self) -> bool {
+        self.priority() == 1
+    }
+}
+
+impl Display for CalcOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sym = match self {
+            CalcOp::Add => "+",
+            CalcOp::Sub => "-",
+            CalcOp::Mul => "*",
+            CalcOp::Div => "/",
+            CalcOp::Mod => "%",
+            CalcOp::Eq => "==",
+            CalcOp::Ne => "!=",
+            CalcOp::Gt => ">",
+            CalcOp::Ge => ">=",
+            CalcOp::Lt => "<",
+            CalcOp::Le => "<=",
+        };
+        write!(f, "{}", sym)
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcExpr {
+    Var(RecordOperation),
+    Val(Value),
+    Binary(Box<CalcExpr>, CalcOp, Box<CalcExpr>),
+    Concat(Vec<CalcExpr>),
+}
+
+impl CalcExpr {
+    fn fmt_operand(&self, f: &mut Formatter<'_>, parent: CalcOp, right: bool) -> std::fmt::Result {
+        // 左结合：右侧同优先级也需要括号
+        let wrap = match self {
+            CalcExpr::Binary(_, op, _) => {
+                op.priority() < parent.priority() || (right && op.priority() == parent.priority())
+            }
+            _ => false,
+        };
+        if wrap {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+impl Display for CalcExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalcExpr::Var(x) => write!(f, "{}", x.dat_get()),
+            CalcExpr::Val(Value::Chars(s)) => write!(f, "\"{}\"", s),
+            // 保留小数点，避免回读后变为整数
+            CalcExpr::Val(Value::Float(v)) if v.fract() == 0.0 => write!(f, "{:.1}", v),
+            CalcExpr::Val(x) => write!(f, "{}", x),
+            CalcExpr::Binary(l, op, r) => {
+                l.fmt_operand(f, *op, false)?;
+                write!(f, " {} ", op)?;
+                r.fmt_operand(f, *op, true)
+            }
+            CalcExpr::Concat(items) => {
+                write!(f, "concat(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Getters)]
+pub struct CalcOperation {
+    expr: CalcExpr,
+}
+
+impl CalcOperation {
+    pub fn new(expr: CalcExpr) -> Self {
+        Self { expr }
+    }
+}
+
+impl Display for CalcOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::language::prelude::*;
+
+/// 模型级 1→N 展开：按数组字段的每个元素产出一条记录，并复制 `keep` 指定的父级字段
+#[derive(Debug, Clone, Getters, PartialEq)]
+pub struct ExplodeOperation {
+    array: String,
+    keep: Vec<String>,
+}
+
+impl ExplodeOperation {
+    pub fn new(array: String, keep: Vec<String>) -> Self {
+        Self { array, keep }
+    }
+}
+
+impl Display for ExplodeOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "explode : {}", self.array)?;
+        if !self.keep.is_empty() {
+            write!(f, " keep ({})", self.keep.join(", "))?;
+        }
+        write!(f, " ;")
+    }
+}
@@ -0,0 +1,55 @@
+use crate::language::prelude::*;
+
+use super::matchs::{MatchCondition, MatchSource};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterMode {
+    /// `where`：命中条件的记录保留，其余丢弃
+    Keep,
+    /// `drop when`：命中条件的记录丢弃
+    Drop,
+}
+
+impl Display for FilterMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterMode::Keep => write!(f, "where"),
+            FilterMode::Drop => write!(f, "drop when"),
+        }
+    }
+}
+
+/// 模型级记录过滤：与 `match` 使用相同的数据源与条件语法，多个条件之间为“或”
+#[derive(Debug, Clone, Getters)]
+pub struct RecordFilter {
+    mode: FilterMode,
+    source: MatchSource,
+    conds: Vec<MatchCondition>,
+}
+
+impl RecordFilter {
+    pub fn new(mode: FilterMode, source: MatchSource, conds: Vec<MatchCondition>) -> Self {
+        Self {
+            mode,
+            source,
+            conds,
+        }
+    }
+}
+
+impl Display for RecordFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} : match ", self.mode)?;
+        match &self.source {
+            MatchSource::Single(c) => write!(f, "{} {{ ", c)?,
+            MatchSource::Double(fst, sec) => write!(f, "({}, {}) {{ ", fst, sec)?,
+        }
+        for (i, cond) in self.conds.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", cond)?;
+        }
+        write!(f, " }} ;")
+    }
+}
@@ -1,9 +1,15 @@
+pub mod calc;
+pub mod explode;
+pub mod filter;
 pub mod fmt;
 pub mod map;
 pub mod matchs;
 pub mod pipe;
 pub mod record;
 pub mod sql;
+pub use calc::*;
+pub use explode::*;
+pub use filter::*;
 pub use fmt::*;
 pub use map::*;
 pub use matchs::*;
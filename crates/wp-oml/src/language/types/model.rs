@@ -1,6 +1,7 @@
 use std::fmt::{Display, Formatter};
 
-use crate::language::EvalExp; //privacy::PrivacyProcessorType};
+use crate::language::{EvalExp, ExplodeOperation, RecordFilter};
+use crate::privacy::{PrivacyConf, PrivacyProcessorType};
 use derive_getters::Getters;
 use enum_dispatch::enum_dispatch;
 use wp_specs::WildArray;
@@ -9,7 +10,11 @@ use wp_specs::WildArray;
 pub struct ObjModel {
     name: String,
     rules: WildArray,
+    filter: Option<Box<RecordFilter>>,
+    explode: Option<ExplodeOperation>,
     pub items: Vec<EvalExp>,
+    privacy: Vec<(String, PrivacyProcessorType)>,
+    privacy_conf: Option<PrivacyConf>,
 }
 
 impl ObjModel {
@@ -18,6 +23,25 @@ impl ObjModel {
             self.rules = WildArray::new1(rules);
         }
     }
+    pub(crate) fn bind_filter(&mut self, filter: Option<RecordFilter>) {
+        self.filter = filter.map(Box::new);
+    }
+    pub(crate) fn bind_explode(&mut self, explode: Option<ExplodeOperation>) {
+        self.explode = explode;
+    }
+    pub(crate) fn insert_privacy(&mut self, name: String, proc: PrivacyProcessorType) {
+        // 同名字段以最后一次配置为准
+        self.privacy.retain(|(k, _)| k != &name);
+        self.privacy.push((name, proc));
+    }
+    /// 绑定项目级隐私配置；未绑定时回退到 [`PrivacyConf::global`]
+    pub fn bind_privacy_conf(&mut self, conf: PrivacyConf) {
+        self.privacy_conf = Some(conf);
+    }
+    /// 是否含依赖 salt 的规则（hash / token）
+    pub fn needs_privacy_salt(&self) -> bool {
+        self.privacy.iter().any(|(_, proc)| proc.needs_salt())
+    }
 }
 
 impl ObjModel {
@@ -25,7 +49,11 @@ impl ObjModel {
         Self {
             name,
             rules: WildArray::default(),
+            filter: None,
+            explode: None,
             items: Vec::new(),
+            privacy: Vec::new(),
+            privacy_conf: None,
         }
     }
 }
@@ -38,10 +66,22 @@ impl Display for ObjModel {
                 writeln!(f, "\t{}", rule)?;
             }
         }
+        if let Some(filter) = &self.filter {
+            writeln!(f, "{}", filter)?;
+        }
+        if let Some(explode) = &self.explode {
+            writeln!(f, "{}", explode)?;
+        }
         writeln!(f, "---")?;
         for i in &self.items {
             writeln!(f, "{}", i)?;
         }
+        if !self.privacy.is_empty() {
+            writeln!(f, "---")?;
+            for (name, proc) in &self.privacy {
+                writeln!(f, "{} : {} ;", name, proc)?;
+            }
+        }
         Ok(())
     }
 }
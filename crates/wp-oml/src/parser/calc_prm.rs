@@ -0,0 +1,180 @@
+use crate::language::PreciseEvaluator;
+use crate::language::{CalcExpr, CalcOp, CalcOperation, RecordOperation};
+use crate::parser::oml_aggregate::oml_var_get_std;
+use winnow::ascii::{digit1, multispace0};
+use winnow::combinator::{alt, cut_err, delimited, fail, opt, separated};
+use winnow::token::literal;
+use wp_model_core::model::Value;
+use wp_parser::Parser;
+use wp_parser::WResult;
+use wp_parser::symbol::{ctx_desc, symbol_bracket_beg, symbol_bracket_end, symbol_comma};
+use wp_parser::utils::get_scope;
+
+// <expr> := <arith> [ (== | != | > | >= | < | <=) <arith> ]
+// <arith> := <term> [ (+ | -) <term> ]*
+// <term> := <operand> [ (* | / | %) <operand> ]*
+// <operand> := read(..) | take(..) | <number> | "<str>" | concat(<expr>, ..) | ( <expr> )
+pub fn oml_aga_calc(data: &mut &str) -> WResult<PreciseEvaluator> {
+    let expr = calc_expr.parse_next(data)?;
+    // 单个操作数交由 read/take/value 等原有语法处理
+    if !matches!(expr, CalcExpr::Binary(..) | CalcExpr::Concat(_)) {
+        return fail.parse_next(data);
+    }
+    Ok(PreciseEvaluator::Calc(CalcOperation::new(expr)))
+}
+
+// 比较结果为 bool，不能再参与比较，因此至多一个比较运算符
+fn calc_expr(data: &mut &str) -> WResult<CalcExpr> {
+    let left = calc_arith.parse_next(data)?;
+    let Some(op) = opt(calc_cmp_op).parse_next(data)? else {
+        return Ok(left);
+    };
+    let right = cut_err(calc_arith)
+        .context(ctx_desc("calc: need operand after operator"))
+        .parse_next(data)?;
+    if opt(calc_cmp_op).parse_next(data)?.is_some() {
+        return cut_err(fail.context(ctx_desc("calc: chained comparison not allowed")))
+            .parse_next(data);
+    }
+    Ok(CalcExpr::Binary(Box::new(left), op, Box::new(right)))
+}
+
+fn calc_arith(data: &mut &str) -> WResult<CalcExpr> {
+    binary_chain(data, calc_term, calc_add_op)
+}
+
+fn calc_term(data: &mut &str) -> WResult<CalcExpr> {
+    binary_chain(data, calc_operand, calc_mul_op)
+}
+
+fn binary_chain(
+    data: &mut &str,
+    mut operand: impl FnMut(&mut &str) -> WResult<CalcExpr>,
+    mut operator: impl FnMut(&mut &str) -> WResult<CalcOp>,
+) -> WResult<CalcExpr> {
+    let mut left = operand(data)?;
+    while let Some(op) = opt(|i: &mut &str| operator(i)).parse_next(data)? {
+        let right = cut_err(|i: &mut &str| operand(i))
+            .context(ctx_desc("calc: need operand after operator"))
+            .parse_next(data)?;
+        left = CalcExpr::Binary(Box::new(left), op, Box::new(right));
+    }
+    Ok(left)
+}
+
+fn calc_cmp_op(data: &mut &str) -> WResult<CalcOp> {
+    multispace0.parse_next(data)?;
+    alt((
+        literal("==").value(CalcOp::Eq),
+        literal("!=").value(CalcOp::Ne),
+        literal(">=").value(CalcOp::Ge),
+        literal("<=").value(CalcOp::Le),
+        literal(">").value(CalcOp::Gt),
+        literal("<").value(CalcOp::Lt),
+    ))
+    .parse_next(data)
+}
+
+fn calc_add_op(data: &mut &str) -> WResult<CalcOp> {
+    multispace0.parse_next(data)?;
+    alt((
+        literal("+").value(CalcOp::Add),
+        literal("-").value(CalcOp::Sub),
+    ))
+    .parse_next(data)
+}
+
+fn calc_mul_op(data: &mut &str) -> WResult<CalcOp> {
+    multispace0.parse_next(data)?;
+    alt((
+        literal("*").value(CalcOp::Mul),
+        literal("/").value(CalcOp::Div),
+        literal("%").value(CalcOp::Mod),
+    ))
+    .parse_next(data)
+}
+
+fn calc_operand(data: &mut &str) -> WResult<CalcExpr> {
+    multispace0.parse_next(data)?;
+    alt((
+        calc_concat,
+        oml_var_get_std.map(|x| CalcExpr::Var(RecordOperation::new(x))),
+        calc_number,
+        calc_str,
+        delimited(symbol_bracket_beg, calc_expr, symbol_bracket_end),
+    ))
+    .parse_next(data)
+}
+
+fn calc_concat(data: &mut &str) -> WResult<CalcExpr> {
+    literal("concat").parse_next(data)?;
+    symbol_bracket_beg.parse_next(data)?;
+    let items: Vec<CalcExpr> = separated(1.., calc_expr, symbol_comma).parse_next(data)?;
+    symbol_bracket_end.parse_next(data)?;
+    Ok(CalcExpr::Concat(items))
+}
+
+fn calc_number(data: &mut &str) -> WResult<CalcExpr> {
+    let num = (opt(literal("-")), digit1, opt((literal("."), digit1)))
+        .take()
+        .parse_next(data)?;
+    if num.contains('.') {
+        num.parse::<f64>()
+            .map(|v| CalcExpr::Val(Value::Float(v)))
+            .or_else(|_| fail.parse_next(data))
+    } else {
+        num.parse::<i64>()
+            .map(|v| CalcExpr::Val(Value::Digit(v)))
+            .or_else(|_| {
+                fail.context(ctx_desc("calc: digit overflow"))
+                    .parse_next(data)
+            })
+    }
+}
+
+fn calc_str(data: &mut &str) -> WResult<CalcExpr> {
+    let s = get_scope(data, '"', '"')?;
+    Ok(CalcExpr::Val(Value::from(s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::utils::for_test::{assert_oml_parse, assert_oml_parse_ext};
+    use wp_parser::WResult as ModalResult;
+
+    #[test]
+    fn test_oml_calc() -> ModalResult<()> {
+        let mut code = r#"read(bytes_in) + read(bytes_out)"#;
+        assert_oml_parse(&mut code, oml_aga_calc);
+        let mut code = r#"read(duration) / 1000"#;
+        assert_oml_parse(&mut code, oml_aga_calc);
+        let mut code = r#"(read(a) + take(b)) * 2.5 % 3"#;
+        assert_oml_parse(&mut code, oml_aga_calc);
+        let mut code = r#"read(a) - (read(b) - 1)"#;
+        assert_oml_parse(&mut code, oml_aga_calc);
+        let mut code = r#"read(a) + read(b) >= 100"#;
+        assert_oml_parse(&mut code, oml_aga_calc);
+        let mut code = r#"concat(read(a), "-", read(b))"#;
+        assert_oml_parse(&mut code, oml_aga_calc);
+        let mut code = r#"((read(a) * 2))"#;
+        assert_oml_parse_ext(&mut code, oml_aga_calc, "read(a) * 2");
+
+        // 单个操作数不属于表达式
+        assert!(oml_aga_calc.parse_next(&mut "read(a)").is_err());
+        assert!(oml_aga_calc.parse_next(&mut "read(a) + ").is_err());
+        // 比较不可链式书写，括号内的比较可作为操作数
+        assert!(
+            oml_aga_calc
+                .parse_next(&mut "read(a) < read(b) < read(c)")
+                .is_err()
+        );
+        assert!(oml_aga_calc.parse_next(&mut "read(a) == 1 != 2").is_err());
+        assert!(
+            oml_aga_calc
+                .parse_next(&mut "(read(a) < read(b)) == (read(b) < read(c))")
+                .is_ok()
+        );
+        Ok(())
+    }
+}
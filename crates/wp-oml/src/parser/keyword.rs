@@ -1,6 +1,6 @@
 use crate::language::{OML_CRATE_IN, OmlKwGet};
 use winnow::ascii::Caseless;
-use winnow::ascii::multispace0;
+use winnow::ascii::{multispace0, multispace1};
 use winnow::error::{StrContext, StrContextValue};
 use winnow::token::literal;
 use wp_parser::Parser;
@@ -221,3 +221,36 @@ pub fn kw_where(data: &mut &str) -> WResult<()> {
         .parse_next(data)?;
     Ok(())
 }
+
+pub fn kw_explode(data: &mut &str) -> WResult<()> {
+    let _ = multispace0.parse_next(data)?;
+    literal("explode")
+        .context(StrContext::Label("oml keyword"))
+        .context(StrContext::Expected(StrContextValue::Description(
+            "need 'explode' keyword",
+        )))
+        .parse_next(data)?;
+    Ok(())
+}
+
+pub fn kw_keep(data: &mut &str) -> WResult<()> {
+    let _ = multispace0.parse_next(data)?;
+    literal("keep")
+        .context(StrContext::Label("oml keyword"))
+        .context(StrContext::Expected(StrContextValue::Description(
+            "need 'keep' keyword",
+        )))
+        .parse_next(data)?;
+    Ok(())
+}
+
+pub fn kw_drop_when(data: &mut &str) -> WResult<()> {
+    let _ = multispace0.parse_next(data)?;
+    (literal("drop"), multispace1, literal("when"))
+        .context(StrContext::Label("oml keyword"))
+        .context(StrContext::Expected(StrContextValue::Description(
+            "need 'drop when' keyword",
+        )))
+        .parse_next(data)?;
+    Ok(())
+}
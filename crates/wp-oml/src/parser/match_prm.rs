@@ -22,7 +22,7 @@ use wp_parser::utils::get_scope;
 use super::syntax;
 use super::tdc_prm::{oml_aga_tdc, oml_aga_value};
 
-fn match_cond1(data: &mut &str) -> WResult<MatchCond> {
+pub(crate) fn match_cond1(data: &mut &str) -> WResult<MatchCond> {
     multispace0.parse_next(data)?;
     let mut cond_exp = match peek(take(1usize)).parse_next(data)? {
         "!" => cond_neq,
@@ -80,7 +80,7 @@ fn match_calc_target(data: &mut &str) -> WResult<NestedAccessor> {
     Ok(sub_gw)
 }
 
-fn match_cond2(data: &mut &str) -> WResult<MatchCondition> {
+pub(crate) fn match_cond2(data: &mut &str) -> WResult<MatchCondition> {
     multispace0.parse_next(data)?;
     let code = get_scope(data, '(', ')')?;
     let mut code_data: &str = code;
@@ -1,3 +1,4 @@
+mod calc_prm;
 pub mod code;
 mod collect_prm;
 mod cond;
@@ -11,7 +12,9 @@ mod oml_acq;
 mod oml_aggregate;
 mod oml_conf;
 mod oml_err;
-//mod oml_privacy;
+mod oml_explode;
+mod oml_filter;
+mod oml_privacy;
 mod pipe_prm;
 //mod shm_prm;
 mod sql_prm;
@@ -9,6 +9,7 @@ use crate::language::ReadOptionBuilder;
 use crate::language::RecordOperationBuilder;
 use crate::language::SingleEvalExpBuilder;
 use crate::language::{MatchSource, RecordOperation};
+use crate::parser::calc_prm::oml_aga_calc;
 
 use crate::language::DirectAccessor;
 use crate::language::{BatchEvalTarget, EvaluationTarget};
@@ -25,7 +26,7 @@ use crate::parser::syntax::oml_default;
 use crate::parser::tdc_prm::{oml_aga_tdc, oml_aga_value, oml_batch_gw_get};
 use crate::parser::{oml_acq, syntax};
 use winnow::ascii::multispace0;
-use winnow::combinator::{alt, fail, peek, repeat, separated, trace};
+use winnow::combinator::{alt, fail, opt, peek, repeat, separated, trace};
 use winnow::error::StrContext;
 use winnow::error::StrContextValue;
 use winnow::stream::Stream;
@@ -103,7 +104,8 @@ pub fn oml_aggregate(data: &mut &str) -> WResult<EvalExp> {
         .parse_next(data)?;
     symbol_assign.parse_next(data)?;
     multispace0.parse_next(data)?;
-    let key = peek(take_key).parse_next(data)?;
+    // 以 `(` 开头的表达式没有关键字
+    let key = peek(opt(take_key)).parse_next(data)?.unwrap_or_default();
 
     let first_target = target_vec.first().expect("no target define");
     let unit = if first_target.safe_name().contains('*') {
@@ -133,9 +135,13 @@ pub fn oml_aggregate(data: &mut &str) -> WResult<EvalExp> {
             //"query" => oml_aga_shmlib.parse_next(data)?,
             "select" => oml_aga_sql.parse_next(data)?,
             "fmt" => oml_aga_fmt.parse_next(data)?,
-            "take" => alt((pipe_prm::oml_aga_pipe_noprefix, oml_aga_tdc)).parse_next(data)?,
-            "read" => alt((pipe_prm::oml_aga_pipe_noprefix, oml_aga_tdc)).parse_next(data)?,
+            "concat" => oml_aga_calc.parse_next(data)?,
+            "take" => alt((oml_aga_calc, pipe_prm::oml_aga_pipe_noprefix, oml_aga_tdc))
+                .parse_next(data)?,
+            "read" => alt((oml_aga_calc, pipe_prm::oml_aga_pipe_noprefix, oml_aga_tdc))
+                .parse_next(data)?,
             _ => alt((
+                trace("calc expr:", oml_aga_calc),
                 trace("get value:", oml_aga_value),
                 trace("fun  struct:", oml_gw_fun),
                 fail.context(StrContext::Label("method"))
@@ -1,14 +1,16 @@
 use crate::language::{EvalExp, ObjModel};
-use crate::parser::keyword::{kw_head_sep_line, kw_oml_name};
+use crate::parser::keyword::{kw_drop_when, kw_head_sep_line, kw_oml_name};
 use crate::parser::oml_aggregate::oml_aggregate;
-//use crate::parser::oml_privacy::oml_privacy;
-//use crate::privacy::PrivacyProcessorType;
+use crate::parser::oml_explode::oml_explode;
+use crate::parser::oml_filter::oml_filter;
+use crate::parser::oml_privacy::oml_privacy;
+use crate::privacy::PrivacyProcessorType;
 use winnow::ascii::multispace0;
-use winnow::combinator::{opt, repeat, trace};
+use winnow::combinator::{alt, not, opt, repeat, trace};
 use winnow::error::StrContext;
 use wp_parser::Parser;
 use wp_parser::WResult;
-use wp_parser::atom::{take_obj_path, take_obj_wild_path};
+use wp_parser::atom::{take_obj_path, take_obj_wild_path, take_var_name};
 use wp_parser::symbol::symbol_colon;
 use wpl::parser::utils::peek_str;
 
@@ -24,6 +26,10 @@ pub fn oml_conf_code(data: &mut &str) -> WResult<ObjModel> {
     let rules = opt(oml_conf_rules).parse_next(data)?;
     debug_data!("obj model: rules loaded!");
     a_items.bind_rules(rules);
+    let filter = opt(oml_filter).parse_next(data)?;
+    a_items.bind_filter(filter);
+    let explode = opt(oml_explode).parse_next(data)?;
+    a_items.bind_explode(explode);
     kw_head_sep_line.parse_next(data)?;
     let mut items: Vec<EvalExp> = repeat(1.., oml_aggregate).parse_next(data)?;
     debug_data!("obj model: aggregate item  loaded!");
@@ -33,14 +39,17 @@ pub fn oml_conf_code(data: &mut &str) -> WResult<ObjModel> {
     if !data.is_empty() {
         if peek_str("---", data).is_ok() {
             kw_head_sep_line.parse_next(data)?;
-            /*
             let privacys: Vec<(String, PrivacyProcessorType)> =
                 repeat(0.., oml_privacy).parse_next(data)?;
             debug_data!("obj model: oml privacy loaded!");
             for (k, v) in privacys {
                 a_items.insert_privacy(k, v);
             }
-            */
+            multispace0.parse_next(data)?;
+            if !data.is_empty() {
+                //探测错误;
+                oml_privacy.parse_next(data)?;
+            }
         } else {
             //探测错误;
             oml_aggregate.parse_next(data)?;
@@ -62,10 +71,16 @@ pub fn oml_conf_head(data: &mut &str) -> WResult<String> {
 pub fn oml_conf_rules(data: &mut &str) -> WResult<Vec<String>> {
     multispace0.parse_next(data)?;
     let (_, _) = (kw_oml_rule, symbol_colon).parse_next(data)?;
-    let rules: Vec<&str> = repeat(0.., take_obj_wild_path).parse_next(data)?;
+    let rules: Vec<&str> = repeat(0.., oml_rule_path).parse_next(data)?;
     Ok(rules.into_iter().map(|s| s.to_string()).collect())
 }
 
+fn oml_rule_path<'a>(data: &mut &'a str) -> WResult<&'a str> {
+    // 后续头部子句（`where :`、`drop when :`、`explode :`）不作为规则路径
+    not(alt((kw_drop_when, (take_var_name, symbol_colon).void()))).parse_next(data)?;
+    take_obj_wild_path.parse_next(data)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parser::oml_conf::oml_parse;
@@ -104,6 +119,31 @@ update_time  :time    = take() { _ :  time(2020-10-01 12:30:30) };
         Ok(())
     }
 
+    #[test]
+    fn test_conf_head_clause() -> ModalResult<()> {
+        let mut code = r#"
+name : test
+rule :
+    wpx/abc   wpx/efg
+where : match read(level) { chars(error) } ;
+explode : records keep (host) ;
+---
+user  : auto = read() ;
+        "#;
+        assert_oml_parse(&mut code, oml_parse);
+        let mut code = r#"
+name : test
+rule : wpx/abc
+drop when : match read(level) { chars(debug) } ;
+---
+user  = read() ;
+        "#;
+        let model = oml_parse.parse_next(&mut code)?;
+        assert_eq!(model.rules().as_ref().len(), 1);
+        assert!(model.filter().is_some());
+        Ok(())
+    }
+
     #[test]
     fn test_conf_fun() -> ModalResult<()> {
         let mut code = r#"
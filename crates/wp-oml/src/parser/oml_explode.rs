@@ -0,0 +1,53 @@
+use crate::language::ExplodeOperation;
+use crate::parser::keyword::{kw_explode, kw_keep};
+use winnow::ascii::multispace0;
+use winnow::combinator::{opt, separated};
+use wp_parser::Parser;
+use wp_parser::WResult;
+use wp_parser::atom::take_path;
+use wp_parser::symbol::{
+    ctx_desc, symbol_bracket_beg, symbol_bracket_end, symbol_colon, symbol_comma, symbol_semicolon,
+};
+
+// explode : <array_field> [ keep ( <field>, ... ) ] ;
+pub fn oml_explode(data: &mut &str) -> WResult<ExplodeOperation> {
+    multispace0.parse_next(data)?;
+    kw_explode.parse_next(data)?;
+    symbol_colon.parse_next(data)?;
+    let array = take_path
+        .context(ctx_desc("explode: need array field"))
+        .parse_next(data)?;
+    let keep = opt(explode_keep).parse_next(data)?.unwrap_or_default();
+    opt(symbol_semicolon).parse_next(data)?;
+    Ok(ExplodeOperation::new(array.to_string(), keep))
+}
+
+fn explode_keep(data: &mut &str) -> WResult<Vec<String>> {
+    kw_keep.parse_next(data)?;
+    symbol_bracket_beg.parse_next(data)?;
+    let keep: Vec<&str> = separated(1.., take_path, symbol_comma)
+        .context(ctx_desc(">> keep ( <field>, ... )"))
+        .parse_next(data)?;
+    symbol_bracket_end.parse_next(data)?;
+    Ok(keep.into_iter().map(|s| s.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::utils::for_test::assert_oml_parse;
+    use orion_error::TestAssert;
+
+    #[test]
+    fn test_oml_explode() {
+        let mut code = r#"explode : records keep (host, account/id) ;"#;
+        assert_oml_parse(&mut code, oml_explode);
+        let mut code = r#"explode : detail/events ;"#;
+        assert_oml_parse(&mut code, oml_explode);
+
+        let x = oml_explode.parse("explode: records keep(host)").assert();
+        assert_eq!(x.array(), "records");
+        assert_eq!(x.keep(), &vec!["host".to_string()]);
+        assert!(oml_explode.parse("explode : records keep ()").is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use crate::language::{FilterMode, MatchCondition, MatchSource, RecordFilter};
+use crate::parser::keyword::{kw_drop_when, kw_gw_match, kw_where};
+use crate::parser::match_prm::{match_cond1, match_cond2};
+use crate::parser::oml_aggregate::oml_crate_calc_ref;
+use winnow::ascii::multispace0;
+use winnow::combinator::{alt, opt, separated};
+use wp_parser::Parser;
+use wp_parser::WResult;
+use wp_parser::symbol::{
+    ctx_desc, symbol_brace_beg, symbol_brace_end, symbol_colon, symbol_comma, symbol_semicolon,
+};
+
+// where     : match <crate> { <cond>, ... } ;
+// drop when : match (<crate>, <crate>) { (<cond>, <cond>), ... } ;
+pub fn oml_filter(data: &mut &str) -> WResult<RecordFilter> {
+    multispace0.parse_next(data)?;
+    let mode = alt((
+        kw_where.value(FilterMode::Keep),
+        kw_drop_when.value(FilterMode::Drop),
+    ))
+    .parse_next(data)?;
+    symbol_colon.parse_next(data)?;
+    kw_gw_match.parse_next(data)?;
+    let source = oml_crate_calc_ref.parse_next(data)?;
+    symbol_brace_beg.parse_next(data)?;
+    let conds: Vec<MatchCondition> = match &source {
+        MatchSource::Single(_) => {
+            separated(1.., match_cond1.map(MatchCondition::Single), symbol_comma)
+                .context(ctx_desc(">> { <match_value>, ... }"))
+                .parse_next(data)?
+        }
+        MatchSource::Double(_, _) => separated(1.., match_cond2, symbol_comma)
+            .context(ctx_desc(">> { (<match_value>,<match_value>), ... }"))
+            .parse_next(data)?,
+    };
+    symbol_brace_end.parse_next(data)?;
+    opt(symbol_semicolon).parse_next(data)?;
+    Ok(RecordFilter::new(mode, source, conds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::utils::for_test::assert_oml_parse;
+
+    #[test]
+    fn test_oml_filter() {
+        let mut code = r#"where : match read(level) { chars(error), chars(warn) } ;"#;
+        assert_oml_parse(&mut code, oml_filter);
+        let mut code =
+            r#"drop when : match read(status) { in (digit(200), digit(299)), !digit(404) } ;"#;
+        assert_oml_parse(&mut code, oml_filter);
+        let mut code =
+            r#"drop when : match (read(city), read(level)) { (chars(bj), chars(debug)) } ;"#;
+        assert_oml_parse(&mut code, oml_filter);
+
+        assert!(
+            oml_filter
+                .parse_next(&mut "where : match read(a) { }")
+                .is_err()
+        );
+        assert!(
+            oml_filter
+                .parse_next(&mut "drop : match read(a) { chars(x) }")
+                .is_err()
+        );
+    }
+}
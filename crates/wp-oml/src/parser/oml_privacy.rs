@@ -0,0 +1,99 @@
+use crate::privacy::{DEFAULT_V6_PREFIX, PrivacyProcessorType};
+use winnow::ascii::{digit1, multispace0};
+use winnow::combinator::{alt, fail, opt, preceded};
+use winnow::token::literal;
+use wp_parser::Parser;
+use wp_parser::WResult;
+use wp_parser::atom::take_path;
+use wp_parser::symbol::{
+    ctx_desc, symbol_bracket_beg, symbol_bracket_end, symbol_colon, symbol_comma, symbol_semicolon,
+};
+
+// <field> : mask(<first>,<last>) | hash | ip_prefix(<v4>[,<v6>]) | token ;
+pub fn oml_privacy(data: &mut &str) -> WResult<(String, PrivacyProcessorType)> {
+    multispace0.parse_next(data)?;
+    let name = take_path.parse_next(data)?;
+    symbol_colon.parse_next(data)?;
+    multispace0.parse_next(data)?;
+    let proc = alt((privacy_mask, privacy_ip_prefix, privacy_hash, privacy_token))
+        .context(ctx_desc("privacy: mask(..) | hash | ip_prefix(..) | token"))
+        .parse_next(data)?;
+    symbol_semicolon.parse_next(data)?;
+    Ok((name.to_string(), proc))
+}
+
+fn take_usize(data: &mut &str) -> WResult<usize> {
+    preceded(multispace0, digit1.try_map(str::parse::<usize>)).parse_next(data)
+}
+
+fn take_u8(data: &mut &str) -> WResult<u8> {
+    preceded(multispace0, digit1.try_map(str::parse::<u8>)).parse_next(data)
+}
+
+fn privacy_mask(data: &mut &str) -> WResult<PrivacyProcessorType> {
+    literal("mask").parse_next(data)?;
+    symbol_bracket_beg.parse_next(data)?;
+    let first = take_usize.parse_next(data)?;
+    symbol_comma.parse_next(data)?;
+    let last = take_usize.parse_next(data)?;
+    symbol_bracket_end.parse_next(data)?;
+    Ok(PrivacyProcessorType::Mask { first, last })
+}
+
+fn privacy_ip_prefix(data: &mut &str) -> WResult<PrivacyProcessorType> {
+    literal("ip_prefix").parse_next(data)?;
+    symbol_bracket_beg.parse_next(data)?;
+    let v4 = take_u8.parse_next(data)?;
+    let v6 = opt(preceded(symbol_comma, take_u8)).parse_next(data)?;
+    symbol_bracket_end.parse_next(data)?;
+    let v6 = v6.unwrap_or(DEFAULT_V6_PREFIX);
+    if v4 > 32 || v6 > 128 {
+        fail.context(ctx_desc("ip_prefix: v4 <= 32, v6 <= 128"))
+            .parse_next(data)?;
+    }
+    Ok(PrivacyProcessorType::IpPrefix { v4, v6 })
+}
+
+fn privacy_hash(data: &mut &str) -> WResult<PrivacyProcessorType> {
+    literal("hash").parse_next(data)?;
+    Ok(PrivacyProcessorType::Hash)
+}
+
+fn privacy_token(data: &mut &str) -> WResult<PrivacyProcessorType> {
+    literal("token").parse_next(data)?;
+    Ok(PrivacyProcessorType::Token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orion_error::TestAssert;
+
+    #[test]
+    fn test_oml_privacy() {
+        assert_eq!(
+            oml_privacy.parse("phone : mask(3, 4) ;").assert(),
+            (
+                "phone".to_string(),
+                PrivacyProcessorType::Mask { first: 3, last: 4 }
+            )
+        );
+        assert_eq!(
+            oml_privacy.parse("src_ip: ip_prefix(24);").assert(),
+            (
+                "src_ip".to_string(),
+                PrivacyProcessorType::IpPrefix { v4: 24, v6: 64 }
+            )
+        );
+        assert_eq!(
+            oml_privacy.parse("email : hash;").assert().1,
+            PrivacyProcessorType::Hash
+        );
+        assert_eq!(
+            oml_privacy.parse("id_card : token ;").assert().1,
+            PrivacyProcessorType::Token
+        );
+        assert!(oml_privacy.parse("src_ip : ip_prefix(33);").is_err());
+        assert!(oml_privacy.parse("phone : blur ;").is_err());
+    }
+}
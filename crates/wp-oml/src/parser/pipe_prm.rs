@@ -10,19 +10,24 @@ use crate::language::{
 };
 use crate::language::{Base64Encode, PIPE_BASE64_ENCODE, PIPE_TO_STR, ToStr};
 use crate::language::{Ip4ToInt, PIPE_IP4_TO_INT, PiPeOperation, PipeFun};
+use crate::language::{
+    PIPE_REGEX_EXTRACT, PIPE_REGEX_MATCH, PIPE_REGEX_REPLACE, RegexExtract, RegexMatch,
+    RegexReplace,
+};
 use crate::parser::keyword::kw_gw_pipe;
 use crate::parser::oml_aggregate::oml_var_get;
 use crate::winnow::error::ParserError;
+use regex::Regex;
 use winnow::ascii::{alphanumeric0, digit1, multispace0};
-use winnow::combinator::{alt, fail, opt, repeat};
+use winnow::combinator::{alt, cut_err, fail, opt, repeat};
 use winnow::error::{ContextError, ErrMode};
 use winnow::stream::Stream; // for checkpoint/reset on &str
 use wp_parser::Parser;
 use wp_parser::WResult;
 use wp_parser::fun::fun_trait::{Fun1Builder, Fun2Builder};
 use wp_parser::fun::parser;
-use wp_parser::symbol::symbol_pipe;
-use wpl::parser::utils::take_key;
+use wp_parser::symbol::{ctx_desc, symbol_pipe};
+use wpl::parser::utils::{take_key, take_str_value};
 
 impl Fun1Builder for Nth {
     type ARG1 = usize;
@@ -183,6 +188,74 @@ impl Fun1Builder for UrlGet {
         UrlGet { key: args }
     }
 }
+// 正则在模型加载时编译，非法模式直接报错
+fn take_regex(data: &mut &str) -> WResult<Regex> {
+    multispace0.parse_next(data)?;
+    let pattern = take_str_value.parse_next(data)?;
+    match Regex::new(&pattern) {
+        Ok(re) => Ok(re),
+        Err(_) => cut_err(fail.context(ctx_desc("regex pipe | invalid regex"))).parse_next(data),
+    }
+}
+impl Fun2Builder for RegexExtract {
+    type ARG1 = Regex;
+    type ARG2 = usize;
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_regex(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        digit1.try_map(str::parse::<usize>).parse_next(data)
+    }
+    fn fun_name() -> &'static str {
+        PIPE_REGEX_EXTRACT
+    }
+    fn build(args: (Regex, usize)) -> Self {
+        RegexExtract {
+            re: args.0,
+            group: args.1,
+        }
+    }
+}
+// 捕获组序号同样在加载时校验
+fn regex_extract(data: &mut &str) -> WResult<RegexExtract> {
+    let fun = parser::call_fun_args2::<RegexExtract>.parse_next(data)?;
+    if fun.group >= fun.re.captures_len() {
+        return cut_err(fail.context(ctx_desc("regex_extract: capture group out of range")))
+            .parse_next(data);
+    }
+    Ok(fun)
+}
+impl Fun2Builder for RegexReplace {
+    type ARG1 = Regex;
+    type ARG2 = String;
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_regex(data)
+    }
+    fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+        take_str_value.parse_next(data)
+    }
+    fn fun_name() -> &'static str {
+        PIPE_REGEX_REPLACE
+    }
+    fn build(args: (Regex, String)) -> Self {
+        RegexReplace {
+            re: args.0,
+            repl: args.1,
+        }
+    }
+}
+impl Fun1Builder for RegexMatch {
+    type ARG1 = Regex;
+    fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+        take_regex(data)
+    }
+    fn fun_name() -> &'static str {
+        PIPE_REGEX_MATCH
+    }
+    fn build(args: Self::ARG1) -> Self {
+        RegexMatch { re: args }
+    }
+}
 pub fn oml_aga_pipe(data: &mut &str) -> WResult<PreciseEvaluator> {
     kw_gw_pipe.parse_next(data)?;
     let from = oml_var_get.parse_next(data)?;
@@ -214,6 +287,11 @@ pub fn oml_pipe(data: &mut &str) -> WResult<PipeFun> {
         parser::call_fun_args1::<SxfGet>.map(PipeFun::SxfGet),
         parser::call_fun_args1::<PathGet>.map(PipeFun::PathGet),
         parser::call_fun_args1::<UrlGet>.map(PipeFun::UrlGet),
+        alt((
+            regex_extract.map(PipeFun::RegexExtract),
+            parser::call_fun_args2::<RegexReplace>.map(PipeFun::RegexReplace),
+            parser::call_fun_args1::<RegexMatch>.map(PipeFun::RegexMatch),
+        )),
         PIPE_HTML_ESCAPE.map(|_| PipeFun::HtmlEscape(HtmlEscape::default())),
         PIPE_HTML_UNESCAPE.map(|_| PipeFun::HtmlUnescape(HtmlUnescape::default())),
         PIPE_STR_ESCAPE.map(|_| PipeFun::StrEscape(StrEscape::default())),
@@ -266,6 +344,13 @@ mod tests {
 
         let mut code = r#" pipe take(ip) | url(host)"#;
         assert_oml_parse(&mut code, oml_aga_pipe);
+
+        let mut code =
+            r##" pipe take(msg) | regex_extract(r#"id=(\d+)"#, 1) | regex_match("^[0-9]+$")"##;
+        assert_oml_parse(&mut code, oml_aga_pipe);
+
+        let mut code = r#" pipe take(msg) | regex_replace("([a-z]+)@corp", "$1")"#;
+        assert_oml_parse(&mut code, oml_aga_pipe);
         Ok(())
     }
 }
@@ -0,0 +1,347 @@
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::sync::OnceLock;
+
+use ipnet::IpNet;
+use sha2::{Digest, Sha256};
+use wp_model_core::model::Value;
+
+/// 隐私处理的项目级配置（当前仅 salt）。
+/// 引擎加载模型时按项目配置绑定到每个模型；未绑定的模型使用 [`PrivacyConf::global`]。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrivacyConf {
+    pub salt: String,
+}
+
+static GLOBAL_CONF: OnceLock<PrivacyConf> = OnceLock::new();
+
+impl PrivacyConf {
+    pub fn new<S: Into<String>>(salt: S) -> Self {
+        Self { salt: salt.into() }
+    }
+
+    /// 设置全局配置；只能设置一次，重复设置返回 false
+    pub fn set_global(conf: PrivacyConf) -> bool {
+        GLOBAL_CONF.set(conf).is_ok()
+    }
+
+    /// 未设置时取环境变量 `WP_PRIVACY_SALT`，缺省为空
+    pub fn global() -> &'static PrivacyConf {
+        GLOBAL_CONF.get_or_init(|| PrivacyConf {
+            salt: std::env::var("WP_PRIVACY_SALT").unwrap_or_default(),
+        })
+    }
+}
+
+pub trait PrivacyProcessor {
+    /// 返回脱敏后的值；不适用的值原样返回
+    fn process(&self, value: &Value, conf: &PrivacyConf) -> Value;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrivacyProcessorType {
+    /// 保留前 `first` 与后 `last` 个字符，其余替换为 `*`
+    Mask { first: usize, last: usize },
+    /// 以 salt 为密钥的 HMAC-SHA256，输出十六进制
+    Hash,
+    /// IP 截断为网段地址
+    IpPrefix { v4: u8, v6: u8 },
+    /// 保留格式的一致化令牌：字母/数字（含中日韩文字）按 salt 确定性替换，
+    /// 空白与标点保留
+    Token,
+}
+
+pub const DEFAULT_V6_PREFIX: u8 = 64;
+
+impl PrivacyProcessorType {
+    /// 空 salt 下的 hash/token 可被暴力还原，需要项目配置 salt
+    pub fn needs_salt(&self) -> bool {
+        matches!(
+            self,
+            PrivacyProcessorType::Hash | PrivacyProcessorType::Token
+        )
+    }
+}
+
+impl Display for PrivacyProcessorType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrivacyProcessorType::Mask { first, last } => write!(f, "mask({},{})", first, last),
+            PrivacyProcessorType::Hash => write!(f, "hash"),
+            PrivacyProcessorType::IpPrefix { v4, v6 } => {
+                if *v6 == DEFAULT_V6_PREFIX {
+                    write!(f, "ip_prefix({})", v4)
+                } else {
+                    write!(f, "ip_prefix({},{})", v4, v6)
+                }
+            }
+            PrivacyProcessorType::Token => write!(f, "token"),
+        }
+    }
+}
+
+impl PrivacyProcessor for PrivacyProcessorType {
+    fn process(&self, value: &Value, conf: &PrivacyConf) -> Value {
+        if matches!(value, Value::Null | Value::Ignore(_)) {
+            return value.clone();
+        }
+        match self {
+            PrivacyProcessorType::Mask { first, last } => {
+                chars_value(mask_str(&value_text(value), *first, *last))
+            }
+            PrivacyProcessorType::Hash => chars_value(hex_string(&hmac_sha256(
+                conf.salt.as_bytes(),
+                value_text(value).as_bytes(),
+            ))),
+            PrivacyProcessorType::IpPrefix { v4, v6 } => match value {
+                Value::IpAddr(ip) => Value::IpAddr(truncate_ip(*ip, *v4, *v6)),
+                Value::Chars(s) => match s.parse::<IpAddr>() {
+                    Ok(ip) => chars_value(truncate_ip(ip, *v4, *v6).to_string()),
+                    Err(_) => value.clone(),
+                },
+                _ => value.clone(),
+            },
+            PrivacyProcessorType::Token => chars_value(tokenize(&conf.salt, &value_text(value))),
+        }
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::Chars(s) => s.to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn chars_value(s: String) -> Value {
+    Value::from(s.as_str())
+}
+
+fn mask_str(s: &str, first: usize, last: usize) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    // 长度不足以同时保留首尾时整体遮盖，避免泄露全部原文
+    if chars.len() <= first + last {
+        return "*".repeat(chars.len());
+    }
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            if i < first || i >= chars.len() - last {
+                *c
+            } else {
+                '*'
+            }
+        })
+        .collect()
+}
+
+const HMAC_BLOCK: usize = 64;
+
+// HMAC-SHA256（RFC 2104）：salt 作为密钥，避免拼接边界歧义与长度扩展
+fn hmac_sha256(key: &[u8], msg: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; HMAC_BLOCK];
+    if key.len() > HMAC_BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(msg);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn truncate_ip(ip: IpAddr, v4: u8, v6: u8) -> IpAddr {
+    let prefix = match ip {
+        IpAddr::V4(_) => v4.min(32),
+        IpAddr::V6(_) => v6.min(128),
+    };
+    IpNet::new(ip, prefix)
+        .map(|net| net.network())
+        .unwrap_or(ip)
+}
+
+// CJK 统一表意文字基本区
+const CJK_BEG: u32 = 0x4E00;
+const CJK_LEN: u32 = 0x9FFF - CJK_BEG + 1;
+
+// 以 salt 为密钥派生的字节流，保证同值同令牌
+struct KeyedStream<'a> {
+    key: &'a [u8],
+    block: [u8; 32],
+    pos: usize,
+}
+
+impl<'a> KeyedStream<'a> {
+    fn new(key: &'a [u8], seed: &[u8]) -> Self {
+        Self {
+            key,
+            block: hmac_sha256(key, seed),
+            pos: 0,
+        }
+    }
+
+    fn next_u16(&mut self) -> u16 {
+        if self.pos + 2 > self.block.len() {
+            self.block = hmac_sha256(self.key, &self.block);
+            self.pos = 0;
+        }
+        let v = u16::from_be_bytes([self.block[self.pos], self.block[self.pos + 1]]);
+        self.pos += 2;
+        v
+    }
+}
+
+fn tokenize(salt: &str, s: &str) -> String {
+    let mut stream = KeyedStream::new(salt.as_bytes(), s.as_bytes());
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        // 空白与标点作为格式保留，文字一律替换，避免非 ASCII 原文泄露
+        if !c.is_alphanumeric() {
+            out.push(c);
+            continue;
+        }
+        let r = stream.next_u16() as u32;
+        let mapped = if c.is_ascii_digit() {
+            char::from(b'0' + (r % 10) as u8)
+        } else if c.is_ascii_uppercase() {
+            char::from(b'A' + (r % 26) as u8)
+        } else if c.is_ascii_lowercase() {
+            char::from(b'a' + (r % 26) as u8)
+        } else {
+            // 非 ASCII 字母/数字（中文、日文、韩文、拉丁扩展等）统一映射为 CJK 文字
+            char::from_u32(CJK_BEG + r % CJK_LEN).unwrap_or('*')
+        };
+        out.push(mapped);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let mask = PrivacyProcessorType::Mask { first: 3, last: 4 };
+        let conf = PrivacyConf::default();
+        assert_eq!(
+            mask.process(&Value::from("13812345678"), &conf),
+            Value::from("138****5678")
+        );
+        assert_eq!(
+            mask.process(&Value::from("1234"), &conf),
+            Value::from("****")
+        );
+        assert_eq!(mask.process(&Value::Null, &conf), Value::Null);
+    }
+
+    #[test]
+    fn test_hash_with_salt() {
+        let hash = PrivacyProcessorType::Hash;
+        let a = hash.process(&Value::from("alice@example.com"), &PrivacyConf::new("p1"));
+        let b = hash.process(&Value::from("alice@example.com"), &PrivacyConf::new("p1"));
+        let c = hash.process(&Value::from("alice@example.com"), &PrivacyConf::new("p2"));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        // RFC 4231 测试向量 2
+        assert_eq!(
+            hash.process(
+                &Value::from("what do ya want for nothing?"),
+                &PrivacyConf::new("Jefe")
+            ),
+            Value::from("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+    }
+
+    #[test]
+    fn test_ip_prefix() {
+        let trunc = PrivacyProcessorType::IpPrefix {
+            v4: 24,
+            v6: DEFAULT_V6_PREFIX,
+        };
+        let conf = PrivacyConf::default();
+        let ip: IpAddr = "192.168.10.23".parse().unwrap();
+        assert_eq!(
+            trunc.process(&Value::IpAddr(ip), &conf),
+            Value::IpAddr("192.168.10.0".parse().unwrap())
+        );
+        assert_eq!(
+            trunc.process(&Value::from("2001:db8:1:2:3:4:5:6"), &conf),
+            Value::from("2001:db8:1:2::")
+        );
+        assert_eq!(
+            trunc.process(&Value::from("n/a"), &conf),
+            Value::from("n/a")
+        );
+    }
+
+    #[test]
+    fn test_token_consistent() {
+        let token = PrivacyProcessorType::Token;
+        let conf = PrivacyConf::new("project-a");
+        let a = token.process(&Value::from("alice@example.com"), &conf);
+        assert_eq!(a, token.process(&Value::from("alice@example.com"), &conf));
+        assert_ne!(a, token.process(&Value::from("bob@example.com"), &conf));
+
+        // 保留格式：长度与分隔符不变
+        let Value::Chars(phone) = token.process(&Value::from("138-1234-5678"), &conf) else {
+            panic!("token should be chars");
+        };
+        assert_eq!(phone.len(), 13);
+        assert_eq!(phone.chars().nth(3), Some('-'));
+        assert!(
+            phone
+                .chars()
+                .filter(|c| *c != '-')
+                .all(|c| c.is_ascii_digit())
+        );
+        assert_ne!(phone.as_str(), "138-1234-5678");
+    }
+
+    #[test]
+    fn test_token_non_ascii() {
+        let token = PrivacyProcessorType::Token;
+        let conf = PrivacyConf::new("project-a");
+        let src = "张三，北京市朝阳区 Café-1";
+        let Value::Chars(out) = token.process(&Value::from(src), &conf) else {
+            panic!("token should be chars");
+        };
+        assert_eq!(out, token.process(&Value::from(src), &conf).to_string());
+        assert_eq!(out.chars().count(), src.chars().count());
+        // 非 ASCII 文字全部替换，标点与空白保留
+        for (a, b) in src.chars().zip(out.chars()) {
+            if !a.is_alphanumeric() {
+                assert_eq!(a, b);
+            } else if !a.is_ascii() {
+                assert_ne!(a, b);
+            }
+        }
+        assert_ne!(
+            out.as_str(),
+            token
+                .process(&Value::from(src), &PrivacyConf::new("project-b"))
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            PrivacyProcessorType::Mask { first: 3, last: 4 }.to_string(),
+            "mask(3,4)"
+        );
+        assert_eq!(
+            PrivacyProcessorType::IpPrefix { v4: 24, v6: 48 }.to_string(),
+            "ip_prefix(24,48)"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use std::net::IpAddr;
 
-use crate::fun::fun_trait::{Fun0Builder, Fun1Builder, Fun2Builder};
+use crate::fun::fun_trait::{Fun0Builder, Fun1Builder, Fun2Builder, Fun3Builder};
 use crate::net::ip;
 use crate::symbol::{symbol_bracket_beg, symbol_bracket_end, symbol_comma};
 use winnow::ascii::{digit1, multispace0};
@@ -21,6 +21,20 @@ pub fn take_call_args2<T: Fun2Builder>(data: &mut &str) -> WResult<(T::ARG1, T::
     Ok((a1, a2))
 }
 
+pub fn take_call_args3<T: Fun3Builder>(data: &mut &str) -> WResult<(T::ARG1, T::ARG2, T::ARG3)> {
+    multispace0.parse_next(data)?;
+    symbol_bracket_beg.parse_next(data)?;
+    multispace0.parse_next(data)?;
+    let a1 = T::args1.parse_next(data)?;
+    (multispace0, symbol_comma, multispace0).parse_next(data)?;
+    let a2 = T::args2.parse_next(data)?;
+    (multispace0, symbol_comma, multispace0).parse_next(data)?;
+    let a3 = T::args3.parse_next(data)?;
+    multispace0.parse_next(data)?;
+    symbol_bracket_end.parse_next(data)?;
+    Ok((a1, a2, a3))
+}
+
 pub fn take_call_args0<T: Fun0Builder>(data: &mut &str) -> WResult<()> {
     multispace0.parse_next(data)?;
     symbol_bracket_beg.parse_next(data)?;
@@ -39,6 +53,13 @@ pub fn take_call_args1<T: Fun1Builder>(data: &mut &str) -> WResult<T::ARG1> {
     Ok(a1)
 }
 
+pub fn call_fun_args3<T: Fun3Builder>(data: &mut &str) -> WResult<T> {
+    T::fun_name().parse_next(data)?;
+    let args = take_call_args3::<T>.parse_next(data)?;
+    let obj = T::build(args);
+    Ok(obj)
+}
+
 pub fn call_fun_args2<T: Fun2Builder>(data: &mut &str) -> WResult<T> {
     T::fun_name().parse_next(data)?;
     let args = take_call_args2::<T>.parse_next(data)?;
@@ -127,6 +148,60 @@ mod test {
         Ok(())
     }
 
+    #[derive(Debug, PartialEq)]
+    struct B {
+        name: String,
+        lo: u32,
+        hi: u32,
+    }
+    impl crate::fun::fun_trait::Fun3Builder for B {
+        type ARG1 = String;
+        type ARG2 = u32;
+        type ARG3 = u32;
+
+        fn args1(data: &mut &str) -> WResult<Self::ARG1> {
+            crate::atom::take_path.map(String::from).parse_next(data)
+        }
+        fn args2(data: &mut &str) -> WResult<Self::ARG2> {
+            <u32 as super::ParseNext<u32>>::parse_next(data)
+        }
+        fn args3(data: &mut &str) -> WResult<Self::ARG3> {
+            <u32 as super::ParseNext<u32>>::parse_next(data)
+        }
+
+        fn fun_name() -> &'static str {
+            "fun_b"
+        }
+
+        fn build(args: (Self::ARG1, Self::ARG2, Self::ARG3)) -> Self {
+            B {
+                name: args.0,
+                lo: args.1,
+                hi: args.2,
+            }
+        }
+    }
+
+    #[test]
+    fn test_args3_fun() -> WResult<()> {
+        let mut data = "fun_b( src , 1,10 )";
+        let x = super::call_fun_args3::<B>.parse_next(&mut data)?;
+        assert_eq!(
+            x,
+            B {
+                name: "src".into(),
+                lo: 1,
+                hi: 10
+            }
+        );
+        assert!(
+            super::call_fun_args3::<B>
+                .parse_next(&mut "fun_b(src, 1)")
+                .is_err()
+        );
+        Ok(())
+    }
+
     // ========================================================================
     // Tests for error handling and boundary conditions
     // ========================================================================
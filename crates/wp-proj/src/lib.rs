@@ -10,6 +10,7 @@ pub mod test_utils;
 mod types;
 pub mod utils;
 pub mod wparse;
+pub mod wpchk;
 pub mod wpgen;
 //pub mod cli_ops;
 //pub mod cli_project;
@@ -4,7 +4,10 @@ use std::sync::Arc;
 use wp_conf::engine::EngineConfig;
 use wp_engine::facade::config::WPARSE_RULE_FILE;
 use wp_error::run_error::{RunReason, RunResult};
-use wpl::WplCode;
+use wpl::checker::{Checker, explain_sample};
+use wpl::formatter::format_code;
+use wpl::util::{fetch_grok_patterns, fetch_proto_descriptors, fetch_ua_db};
+use wpl::{WplCode, WplPackage, resolve_pkg_refs};
 
 use crate::utils::{config_path::ConfigPathResolver, error_handler::ErrorHandler};
 
@@ -36,6 +39,43 @@ impl Wpl {
         }
     }
 
+    fn grok_root(&self) -> PathBuf {
+        let raw = self.eng_conf.grok_root();
+        let candidate = Path::new(raw);
+        if candidate.is_absolute() {
+            candidate.to_path_buf()
+        } else {
+            self.work_root.join(candidate)
+        }
+    }
+
+    fn uap_file(&self) -> PathBuf {
+        let raw = self.eng_conf.uap_file();
+        let candidate = Path::new(raw);
+        if candidate.is_absolute() {
+            candidate.to_path_buf()
+        } else {
+            self.work_root.join(candidate)
+        }
+    }
+
+    fn proto_root(&self) -> PathBuf {
+        let raw = self.eng_conf.proto_root();
+        let candidate = Path::new(raw);
+        if candidate.is_absolute() {
+            candidate.to_path_buf()
+        } else {
+            self.work_root.join(candidate)
+        }
+    }
+
+    // grok 字段引用的模式需在模式目录或内置模式中定义
+    fn check_grok(pkg: &WplPackage, fp: &Path) -> RunResult<()> {
+        Checker::new(true, 0).check_grok(pkg).map_err(|e| {
+            RunReason::from_conf(format!("check wpl failed: {:?}: {}", fp, e)).to_err()
+        })
+    }
+
     /// Initialize WPL with example content for the specified project directory
     pub fn init_with_examples(&self) -> RunResult<()> {
         let work_root = &self.work_root;
@@ -90,59 +130,152 @@ impl Wpl {
     }
 
     pub fn check(&self) -> RunResult<()> {
-        let rule_root = self.rule_root();
-        let rules =
-            wp_conf::utils::find_conf_files(rule_root.to_string_lossy().as_ref(), WPARSE_RULE_FILE)
-                .unwrap_or_default();
+        let pkgs = self.load_pkgs()?;
+        Self::check_pkgs(pkgs)
+    }
 
-        // 如果没有找到规则文件，尝试手动查找 *.wpl 文件
-        if rules.is_empty() {
-            let absolute_rule_root = self.rule_root();
-            let wpl_pattern = format!("{}/*.wpl", absolute_rule_root.display());
-
-            if let Ok(glob_results) = glob::glob(&wpl_pattern) {
-                let wpl_files: Vec<_> = glob_results.filter_map(Result::ok).collect();
-
-                if !wpl_files.is_empty() {
-                    // 使用找到的 .wpl 文件
-                    for fp in wpl_files {
-                        let raw = std::fs::read_to_string(&fp).unwrap_or_default();
-                        if raw.trim().is_empty() {
-                            return ErrorHandler::config_error(format!(
-                                "配置错误: WPL文件为空: {:?}",
-                                fp
-                            ));
-                        }
-                        let code = WplCode::build(fp.clone(), raw.as_str()).map_err(|e| {
-                            RunReason::from_conf(format!("build wpl failed: {:?}: {}", fp, e))
-                                .to_err()
-                        })?;
-                        let _pkg = code.parse_pkg().map_err(|e| {
-                            RunReason::from_conf(format!("parse wpl failed: {:?}: {}", fp, e))
-                                .to_err()
-                        })?;
-                    }
-                    return Ok(());
-                }
-            }
+    /// 执行全部 WPL 文件中的 `test` 样例，打印差异；存在失败样例时返回错误
+    pub fn test_samples(&self) -> RunResult<()> {
+        let (_, pkgs) = Self::resolve_pkgs(self.load_pkgs()?)?;
+        let checker = Checker::new(false, 0);
+        let (mut passed, mut total) = (0, 0);
+        for pkg in &pkgs {
+            let (ok, all) = checker.check_samples(pkg).map_err(|e| {
+                RunReason::from_conf(format!("check wpl samples failed: {}", e)).to_err()
+            })?;
+            passed += ok;
+            total += all;
         }
-
-        // 检查是否有任何WPL规则文件存在
-        if rules.is_empty() {
-            return ErrorHandler::config_error("配置错误: 未找到任何WPL规则文件 (*.wpl)");
+        println!("WPL samples: {}/{} passed", passed, total);
+        if passed < total {
+            return ErrorHandler::config_error(format!(
+                "WPL samples failed: {}/{}",
+                total - passed,
+                total
+            ));
         }
+        Ok(())
+    }
+
+    /// 以 explain 模式解析单条样本，返回标注视图；`target` 为 `pkg/rule` 或规则名
+    pub fn explain_sample(&self, target: &str, sample: &str) -> RunResult<String> {
+        let (_, pkgs) = Self::resolve_pkgs(self.load_pkgs()?)?;
+        let found = pkgs.iter().find_map(|pkg| {
+            pkg.rules
+                .iter()
+                .find(|x| {
+                    x.name == target
+                        || format!("{}/{}", pkg.name.trim_end_matches('/'), x.name) == target
+                })
+                .map(|rule| (pkg, rule.name.clone()))
+        });
+        let Some((pkg, rule)) = found else {
+            return Err(RunReason::from_conf(format!("WPL rule not found: {}", target)).to_err());
+        };
+        let explain = explain_sample(pkg, rule.as_str(), sample).map_err(|e| {
+            RunReason::from_conf(format!("explain {} failed: {}", target, e)).to_err()
+        })?;
+        Ok(explain.render())
+    }
 
+    fn load_pkgs(&self) -> RunResult<Vec<(PathBuf, WplPackage)>> {
+        fetch_grok_patterns(self.grok_root().to_string_lossy().as_ref()).map_err(|e| {
+            RunReason::from_conf(format!("load grok patterns failed: {}", e)).to_err()
+        })?;
+        fetch_ua_db(self.uap_file().to_string_lossy().as_ref()).map_err(|e| {
+            RunReason::from_conf(format!("load uap regexes failed: {}", e)).to_err()
+        })?;
+        fetch_proto_descriptors(self.proto_root().to_string_lossy().as_ref()).map_err(|e| {
+            RunReason::from_conf(format!("load proto descriptors failed: {}", e)).to_err()
+        })?;
+        let rules = self.rule_files()?;
+        let mut pkgs = Vec::new();
         for fp in rules {
             let raw = std::fs::read_to_string(&fp).unwrap_or_default();
             if raw.trim().is_empty() {
-                return ErrorHandler::config_error(format!("配置错误: WPL文件为空: {:?}", fp));
+                return Err(
+                    RunReason::from_conf(format!("配置错误: WPL文件为空: {:?}", fp)).to_err(),
+                );
             }
             let code = WplCode::build(fp.clone(), raw.as_str()).map_err(|e| {
                 RunReason::from_conf(format!("build wpl failed: {:?}: {}", fp, e)).to_err()
             })?;
-            let _pkg = code.parse_pkg().map_err(|e| {
+            let pkg = code.parse_pkg().map_err(|e| {
                 RunReason::from_conf(format!("parse wpl failed: {:?}: {}", fp, e)).to_err()
             })?;
+            pkgs.push((fp, pkg));
+        }
+        Ok(pkgs)
+    }
+
+    fn rule_files(&self) -> RunResult<Vec<PathBuf>> {
+        let rule_root = self.rule_root();
+        let rules =
+            wp_conf::utils::find_conf_files(rule_root.to_string_lossy().as_ref(), WPARSE_RULE_FILE)
+                .unwrap_or_default();
+        if !rules.is_empty() {
+            return Ok(rules);
+        }
+        // 如果没有找到规则文件，尝试手动查找 *.wpl 文件
+        let wpl_pattern = format!("{}/*.wpl", rule_root.display());
+        let wpl_files: Vec<PathBuf> = glob::glob(&wpl_pattern)
+            .map(|x| x.filter_map(Result::ok).collect())
+            .unwrap_or_default();
+        if wpl_files.is_empty() {
+            return Err(RunReason::from_conf("配置错误: 未找到任何WPL规则文件 (*.wpl)").to_err());
+        }
+        Ok(wpl_files)
+    }
+
+    /// 按规范格式改写全部 WPL 文件；`check` 时只检查不改写，存在未格式化文件时返回错误
+    pub fn format(&self, check: bool) -> RunResult<()> {
+        Self::format_files(&self.rule_files()?, check)
+    }
+
+    pub fn format_files(files: &[PathBuf], check: bool) -> RunResult<()> {
+        let mut unformatted = Vec::new();
+        for fp in files {
+            let raw = std::fs::read_to_string(fp).map_err(|e| {
+                RunReason::from_conf(format!("read wpl failed: {:?}: {}", fp, e)).to_err()
+            })?;
+            let formatted = format_code(raw.as_str()).map_err(|e| {
+                RunReason::from_conf(format!("format wpl failed: {:?}: {}", fp, e)).to_err()
+            })?;
+            if formatted == raw {
+                continue;
+            }
+            if check {
+                println!("unformatted: {}", fp.display());
+            } else {
+                ConfigPathResolver::write_file_with_dir(fp, formatted.as_str())?;
+                println!("formatted: {}", fp.display());
+            }
+            unformatted.push(fp);
+        }
+        if check && !unformatted.is_empty() {
+            return ErrorHandler::config_error(format!(
+                "WPL files not formatted: {}",
+                unformatted.len()
+            ));
+        }
+        Ok(())
+    }
+
+    // 字段组 `use` 可跨文件引用，全部解析后统一展开再检查
+    fn resolve_pkgs(
+        items: Vec<(PathBuf, WplPackage)>,
+    ) -> RunResult<(Vec<PathBuf>, Vec<WplPackage>)> {
+        let (paths, mut pkgs): (Vec<_>, Vec<_>) = items.into_iter().unzip();
+        resolve_pkg_refs(&mut pkgs).map_err(|e| {
+            RunReason::from_conf(format!("resolve wpl fields failed: {}", e)).to_err()
+        })?;
+        Ok((paths, pkgs))
+    }
+
+    fn check_pkgs(items: Vec<(PathBuf, WplPackage)>) -> RunResult<()> {
+        let (paths, pkgs) = Self::resolve_pkgs(items)?;
+        for (fp, pkg) in paths.iter().zip(pkgs.iter()) {
+            Self::check_grok(pkg, fp)?;
         }
         Ok(())
     }
@@ -434,6 +434,16 @@ mod tests {
         assert!(project.sources_c().check_sources_config().is_ok());
         assert!(check_to_result(project.sources_c().check()).is_ok());
         assert!(check_to_result(project.wpl().check()).is_ok());
+        // 示例规则自带的 test 样例应全部通过
+        assert!(project.wpl().test_samples().is_ok());
+        let view = project
+            .wpl()
+            .explain_sample("/nginx/example", "not a log line")
+            .expect("explain sample");
+        assert!(view.contains("result: failed at byte"));
+        assert!(project.wpl().explain_sample("/nginx/missing", "x").is_err());
+        // 示例规则已是规范格式
+        assert!(project.wpl().format(true).is_ok());
 
         // 调试OML检查
         println!(
@@ -6,6 +6,8 @@ use std::path::{Path, PathBuf};
 use wp_engine::facade::config::load_warp_engine_confs;
 use wp_engine::facade::kit::engine_proc_file;
 use wp_error::run_error::{RunReason, RunResult};
+use wpl::checker::explain_sample;
+use wpl::{WplCode, WplPackage, resolve_pkg_refs};
 
 pub fn parse_wpl_samples(work_root: &str) -> RunResult<()> {
     let jobs = discover_sample_jobs(work_root)?;
@@ -31,6 +33,62 @@ pub fn parse_wpl_samples(work_root: &str) -> RunResult<()> {
     Ok(())
 }
 
+/// 以 explain 模式逐行解析各 sample.dat，输出每条规则消费的字节区间与失败原因
+pub fn explain_wpl_samples(work_root: &str, max_line: Option<usize>) -> RunResult<()> {
+    let jobs = discover_sample_jobs(work_root)?;
+    if jobs.is_empty() {
+        return Err(RunReason::from_conf("未在 wpl 目录中找到 sample.dat").to_err());
+    }
+    for job in jobs {
+        println!("→ 解释样本 {}", job.label);
+        let pkg = load_rule_pkg(&job.rule)?;
+        let text = fs::read_to_string(&job.sample).map_err(|e| {
+            RunReason::from_conf(format!("读取样本失败 {}: {}", job.sample.display(), e)).to_err()
+        })?;
+        let lines = text.lines().filter(|x| !x.trim().is_empty());
+        for (no, line) in lines.take(max_line.unwrap_or(usize::MAX)).enumerate() {
+            explain_line(&pkg, no + 1, line);
+        }
+    }
+    Ok(())
+}
+
+// 命中时只展示首条命中规则；全部未命中时逐条展示各规则的尝试过程
+fn explain_line(pkg: &WplPackage, no: usize, line: &str) {
+    let mut explains = Vec::new();
+    for rule in pkg.rules.iter().filter(|x| x.statement.express().is_some()) {
+        match explain_sample(pkg, rule.name.as_str(), line) {
+            Ok(explain) => explains.push((rule.name.clone(), explain)),
+            Err(e) => println!("✗ 规则 {}/{}: {}", pkg.name, rule.name, e),
+        }
+    }
+    if let Some((rule, explain)) = explains.iter().find(|(_, x)| x.is_match()) {
+        println!("✓ 第 {} 行命中 {}/{}", no, pkg.name, rule);
+        println!("{}", explain.render());
+        return;
+    }
+    for (rule, explain) in &explains {
+        println!("✗ 第 {} 行未命中 {}/{}", no, pkg.name, rule);
+        println!("{}", explain.render());
+    }
+}
+
+fn load_rule_pkg(rule_file: &Path) -> RunResult<WplPackage> {
+    let raw = fs::read_to_string(rule_file).map_err(|e| {
+        RunReason::from_conf(format!("读取规则失败 {}: {}", rule_file.display(), e)).to_err()
+    })?;
+    let pkg = WplCode::build(rule_file.to_path_buf(), raw.as_str())
+        .and_then(|code| code.parse_pkg())
+        .map_err(|e| {
+            RunReason::from_conf(format!("解析规则失败 {}: {}", rule_file.display(), e)).to_err()
+        })?;
+    let mut pkgs = vec![pkg];
+    resolve_pkg_refs(&mut pkgs).map_err(|e| {
+        RunReason::from_conf(format!("展开字段组失败 {}: {}", rule_file.display(), e)).to_err()
+    })?;
+    Ok(pkgs.remove(0))
+}
+
 fn parse_single_run<P: AsRef<Path> + Clone>(data_path: P, rule_file: P) -> RunResult<()> {
     let (work_rule, sinks) = simple_ins_run_res(Some(rule_file), None)?;
     let infra = sinks.infra_agent();
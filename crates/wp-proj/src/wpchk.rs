@@ -0,0 +1,124 @@
+//! `wpchk` 子命令分发：引擎配置检查、WPL 样例测试、explain 与格式化。
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use wp_engine::facade::cli::{DvChk, ExplainArgs, FmtArgs};
+use wp_engine::facade::config::load_warp_engine_confs;
+use wp_error::run_error::RunResult;
+
+use crate::models::Wpl;
+use crate::wparse::samples::explain_wpl_samples;
+
+/// 执行 `wpchk` 子命令；检查失败时返回错误，由调用方以非零码退出
+pub fn run_dv_chk(cmd: DvChk) -> RunResult<()> {
+    match cmd {
+        DvChk::Engine(args) => load_wpl(&args.work_root, args.wpl_dir.as_ref())?.check(),
+        DvChk::Test(args) => load_wpl(&args.work_root, args.wpl_dir.as_ref())?.test_samples(),
+        DvChk::Explain(args) => explain(&args),
+        DvChk::Fmt(args) => format(&args),
+    }
+}
+
+// 指定规则时解释单条样本，否则逐行解释规则目录下的 sample.dat
+fn explain(args: &ExplainArgs) -> RunResult<()> {
+    let Some(rule) = &args.rule else {
+        return explain_wpl_samples(args.work_root.as_str(), None);
+    };
+    let sample = args.sample_line()?;
+    let view = load_wpl(&args.work_root, None)?.explain_sample(rule, sample.as_str())?;
+    println!("{}", view);
+    Ok(())
+}
+
+// 指定文件时只处理这些文件，否则处理规则目录下全部 WPL 文件
+fn format(args: &FmtArgs) -> RunResult<()> {
+    if args.files.is_empty() {
+        return load_wpl(&args.work_root, None)?.format(args.check);
+    }
+    let files: Vec<PathBuf> = args.files.iter().map(PathBuf::from).collect();
+    Wpl::format_files(&files, args.check)
+}
+
+// 加载 wparse.toml；`--wpl` 覆盖规则目录
+fn load_wpl(work_root: &str, wpl_dir: Option<&String>) -> RunResult<Wpl> {
+    let (cm, mut main) = load_warp_engine_confs(work_root)?;
+    if let Some(dir) = wpl_dir {
+        main.set_rule_root(dir.clone());
+    }
+    Ok(Wpl::new(cm.work_root_path(), Arc::new(main)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{temp_workdir, write_basic_wparse_config, write_file};
+    use wp_engine::facade::cli::ParseArgs;
+
+    const NGINX_WPL: &str = r#"package nginx {
+  rule access { (ip:sip, digit:status) }
+  test access {
+    sample = "10.0.0.1 200"
+    expect { ip:sip = "10.0.0.1", digit:status = "200" }
+  }
+}
+"#;
+
+    fn parse_args(work_root: &std::path::Path) -> ParseArgs {
+        ParseArgs {
+            work_root: work_root.to_string_lossy().to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_command_runs_wpl_samples() {
+        let temp = temp_workdir();
+        write_basic_wparse_config(temp.path());
+        write_file(temp.path(), "models/wpl/parse.wpl", NGINX_WPL);
+        assert!(run_dv_chk(DvChk::Test(parse_args(temp.path()))).is_ok());
+
+        let broken = NGINX_WPL.replace("digit:status = \"200\"", "digit:status = \"404\"");
+        write_file(temp.path(), "models/wpl/parse.wpl", broken.as_str());
+        assert!(run_dv_chk(DvChk::Test(parse_args(temp.path()))).is_err());
+    }
+
+    #[test]
+    fn explain_command_renders_single_sample() {
+        let temp = temp_workdir();
+        write_basic_wparse_config(temp.path());
+        write_file(temp.path(), "models/wpl/parse.wpl", NGINX_WPL);
+        let args = |rule: &str| ExplainArgs {
+            work_root: temp.path().to_string_lossy().to_string(),
+            rule: Some(rule.to_string()),
+            sample: Some("10.0.0.1 200".to_string()),
+            file: None,
+        };
+        assert!(run_dv_chk(DvChk::Explain(args("access"))).is_ok());
+        assert!(run_dv_chk(DvChk::Explain(args("missing"))).is_err());
+    }
+
+    #[test]
+    fn fmt_command_checks_and_rewrites() {
+        let temp = temp_workdir();
+        let file = write_file(
+            temp.path(),
+            "parse.wpl",
+            "package x { rule a { (digit:id) } }",
+        );
+        let args = |check: bool| FmtArgs {
+            work_root: temp.path().to_string_lossy().to_string(),
+            files: vec![file.to_string_lossy().to_string()],
+            check,
+        };
+        assert!(run_dv_chk(DvChk::Fmt(args(true))).is_err());
+        assert!(run_dv_chk(DvChk::Fmt(args(false))).is_ok());
+        assert!(run_dv_chk(DvChk::Fmt(args(true))).is_ok());
+
+        // 语句内部的注释不改写
+        let code = "package x {\n  rule a {\n    // id\n    (digit:id)\n  }\n}\n";
+        std::fs::write(&file, code).unwrap();
+        assert!(run_dv_chk(DvChk::Fmt(args(false))).is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), code);
+    }
+}
@@ -65,6 +65,7 @@ impl ErrorHandlingPolicy for Err4Debug {
             WplCodeReason::Syntax(_) => ErrStrategy::Ignore,
             WplCodeReason::Empty(_) => ErrStrategy::Ignore,
             WplCodeReason::UnSupport(_) => ErrStrategy::Ignore,
+            WplCodeReason::Reference(_) => ErrStrategy::Ignore,
             WplCodeReason::Uvs(e) => self.err_4universal(e),
         }
     }
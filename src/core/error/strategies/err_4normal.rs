@@ -63,6 +63,7 @@ impl ErrorHandlingPolicy for Err4Normal {
             WplCodeReason::Syntax(_) => ErrStrategy::Ignore,
             WplCodeReason::Empty(_) => ErrStrategy::Ignore,
             WplCodeReason::UnSupport(_) => ErrStrategy::Ignore,
+            WplCodeReason::Reference(_) => ErrStrategy::Ignore,
             WplCodeReason::Uvs(e) => self.err_4universal(e),
         }
     }
@@ -14,10 +14,10 @@ use wp_error::{
     config_error::{ConfError, ConfReason, ConfResult},
     parse_error::OMLCodeResult,
 };
-use wp_model_core::model::DataType;
 use wpl::{
     ParserFactory, WplCode, WplPackage, WplRule, WplSep, WplStatementType,
     generator::{FieldsGenRule, FmtFieldVec, GenChannel, NamedFieldGF},
+    resolve_pkg_refs,
 };
 
 use crate::{resources::OmlRepository, stat::MonSend, types::AnyResult};
@@ -62,7 +62,9 @@ impl GenRuleUnit {
         let ups_sep = WplSep::default();
         for wpl_rule in self.get_rules() {
             let mut fieldset = FmtFieldVec::new();
-            let WplStatementType::Express(rule) = &wpl_rule.statement;
+            let WplStatementType::Express(rule) = &wpl_rule.statement else {
+                continue;
+            };
             for group in &rule.group {
                 for f_conf in &group.fields {
                     let rule = f_conf
@@ -70,8 +72,7 @@ impl GenRuleUnit {
                         .as_ref()
                         .and_then(|name| self.get_fields().get(name));
                     let mut ch = GenChannel::new();
-                    let meta = DataType::from(f_conf.meta_name.as_str())?;
-                    let parser = ParserFactory::create(&meta)?;
+                    let parser = ParserFactory::create_by_conf(f_conf)?;
                     let sep = group.resolve_sep(&ups_sep);
                     let field = parser.generate(&mut ch, &sep, f_conf, rule)?;
                     fieldset.push(field);
@@ -90,7 +91,7 @@ pub fn load_gen_confs(path: &str) -> ConfResult<Vec<GenRuleUnit>> {
         )));
     }
 
-    let mut result_vec = Vec::new();
+    let mut loaded = Vec::new();
     for f in files {
         let mut package_opt = None;
         if let Some(fst) = &f.fst {
@@ -107,11 +108,6 @@ pub fn load_gen_confs(path: &str) -> ConfResult<Vec<GenRuleUnit>> {
                 .with(&ctx)?;
             info_ctrl!("load conf file: {:?}", fst);
             let package = code_build.parse_pkg().owe_conf().with(&ctx)?;
-            if package.is_empty() {
-                return Err(ConfError::from(ConfReason::NotFound(
-                    "gen rule package is empty".into(),
-                )));
-            }
             package_opt = Some(package);
         }
         let mut fields = HashMap::new();
@@ -123,9 +119,25 @@ pub fn load_gen_confs(path: &str) -> ConfResult<Vec<GenRuleUnit>> {
             fields = conf.items;
             info_ctrl!("load conf file: {:?}", sec);
         }
-        if let Some(packages) = package_opt {
-            result_vec.push(GenRuleUnit::new(packages, fields.clone()));
+        if let Some(package) = package_opt {
+            loaded.push((package, fields));
+        }
+    }
+
+    // 全部包加载后统一展开，`use` 可引用其他包声明的字段组
+    let (mut packages, fields): (Vec<WplPackage>, Vec<NamedFieldGF>) = loaded.into_iter().unzip();
+    let mut ctx = WithContext::want("resolve gen code");
+    ctx.record("path", path);
+    resolve_pkg_refs(&mut packages).owe_conf().with(&ctx)?;
+
+    let mut result_vec = Vec::new();
+    for (package, fields) in packages.into_iter().zip(fields) {
+        if package.is_empty() {
+            return Err(ConfError::from(ConfReason::NotFound(
+                "gen rule package is empty".into(),
+            )));
         }
+        result_vec.push(GenRuleUnit::new(package, fields));
     }
     Ok(result_vec)
 }
@@ -0,0 +1,90 @@
+//! 规则分发索引
+//!
+//! 加载时按各规则可推导的前导字面量（见 `WplEvaluator::dispatch_prefix`）建立索引，
+//! 事件只需尝试前缀匹配的规则；无法推导前缀的规则进入回退列表，总是参与尝试。
+
+use std::collections::HashMap;
+
+use smol_str::SmolStr;
+use wp_parse_api::RawData;
+
+use crate::core::parser::wpl_engine::pipeline::WplPipeline;
+
+#[derive(Clone, Default, Debug)]
+pub struct DispatchIndex {
+    // 前缀首字节 -> (前缀, 规则位置)
+    buckets: HashMap<u8, Vec<(SmolStr, usize)>>,
+    fallback: Vec<usize>,
+}
+
+impl DispatchIndex {
+    pub fn build(pipelines: &[WplPipeline]) -> Self {
+        let mut index = Self::default();
+        for (pos, pipeline) in pipelines.iter().enumerate() {
+            match pipeline.parser().dispatch_prefix() {
+                Some(prefix) => index
+                    .buckets
+                    .entry(prefix.as_bytes()[0])
+                    .or_default()
+                    .push((SmolStr::from(prefix), pos)),
+                None => index.fallback.push(pos),
+            }
+        }
+        index
+    }
+
+    /// 已建立前缀索引的规则数
+    pub fn indexed_cnt(&self) -> usize {
+        self.buckets.values().map(Vec::len).sum()
+    }
+
+    /// 选出需要尝试的规则位置，保持原有（命中率）顺序
+    pub fn candidates(&self, payload: &RawData) -> Vec<usize> {
+        let data = payload.as_bytes().trim_ascii_start();
+        let mut cands = self.fallback.clone();
+        if let Some(bucket) = data.first().and_then(|b| self.buckets.get(b)) {
+            cands.extend(
+                bucket
+                    .iter()
+                    .filter(|(prefix, _)| data.starts_with(prefix.as_bytes()))
+                    .map(|(_, pos)| *pos),
+            );
+        }
+        cands.sort_unstable();
+        cands
+    }
+}
+
+/// 分发选择度统计：统计窗口内的事件数、候选规则数与实际尝试的规则数
+#[derive(Clone, Default, Debug, PartialEq, getset::CopyGetters)]
+#[get_copy = "pub"]
+pub struct DispatchStat {
+    events: usize,
+    candidates: usize,
+    tried: usize,
+    // 候选集覆盖全部规则（索引未起作用）的事件数
+    full_scan: usize,
+}
+
+impl DispatchStat {
+    pub fn record(&mut self, candidates: usize, tried: usize, rule_cnt: usize) {
+        self.events += 1;
+        self.candidates += candidates;
+        self.tried += tried;
+        if candidates >= rule_cnt {
+            self.full_scan += 1;
+        }
+    }
+
+    /// 平均每个事件尝试的规则数
+    pub fn avg_tried(&self) -> f64 {
+        if self.events == 0 {
+            return 0.0;
+        }
+        self.tried as f64 / self.events as f64
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
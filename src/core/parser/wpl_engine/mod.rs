@@ -1,3 +1,4 @@
+pub mod dispatch;
 pub mod engine;
 pub mod parser;
 pub mod pipeline;
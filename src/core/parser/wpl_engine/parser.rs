@@ -1,5 +1,6 @@
 //! 单个数据包解析逻辑
 
+use super::dispatch::{DispatchIndex, DispatchStat};
 use super::types::ProcessResult;
 use crate::core::parser::wpl_engine::pipeline::WplPipeline;
 use crate::{core::parser::ParseOption, stat::MonSend};
@@ -14,11 +15,18 @@ use wpl::{WparseError, WparseReason, WparseResult};
 #[get = "pub"]
 pub struct MultiParser {
     pipelines: Vec<WplPipeline>,
+    index: DispatchIndex,
+    dispatch_stat: DispatchStat,
 }
 
 impl MultiParser {
     pub fn new(pipelines: Vec<WplPipeline>) -> Self {
-        Self { pipelines }
+        let index = DispatchIndex::build(&pipelines);
+        Self {
+            pipelines,
+            index,
+            dispatch_stat: DispatchStat::default(),
+        }
     }
 
     /// 处理单个事件
@@ -28,9 +36,15 @@ impl MultiParser {
         let mut best_error = None;
         let rule_cnt = self.pipelines.len();
 
-        // 尝试用每个规则处理事件
-        for (idx, wpl_line) in self.pipelines.iter_mut().enumerate() {
-            let is_last = idx == rule_cnt - 1;
+        // 仅尝试分发索引选出、且来源选择允许的候选规则
+        let mut candidates = self.index.candidates(&event.payload);
+        candidates.retain(|pos| self.pipelines[*pos].accept_source(event));
+        let cand_cnt = candidates.len();
+        let mut tried = 0;
+        for (idx, rule_pos) in candidates.into_iter().enumerate() {
+            let is_last = idx == cand_cnt - 1;
+            let wpl_line = &mut self.pipelines[rule_pos];
+            tried += 1;
 
             // 调用 WPL 处理
             match wpl_line.proc(event, max_depth) {
@@ -43,6 +57,7 @@ impl MultiParser {
                         }
                     }
                     wpl_line.hit_cnt += 1;
+                    self.dispatch_stat.record(cand_cnt, tried, rule_cnt);
 
                     let wpl_key = wpl_line.wpl_key().to_string();
 
@@ -86,6 +101,7 @@ impl MultiParser {
                 }
             }
         }
+        self.dispatch_stat.record(cand_cnt, tried, rule_cnt);
 
         // 所有规则都失败，返回深度最高的失败信息
         let best_error = best_error.unwrap_or_else(|| {
@@ -116,6 +132,8 @@ impl MultiParser {
         for pipeline in &mut self.pipelines {
             pipeline.hit_cnt = 0;
         }
+        // 排序后规则位置变化，重建分发索引
+        self.index = DispatchIndex::build(&self.pipelines);
     }
 
     /// 更新规则命中计数并排序
@@ -128,6 +146,20 @@ impl MultiParser {
         for i in self.pipelines.iter_mut() {
             i.send_stat(mon_send).await?;
         }
+        let stat = &self.dispatch_stat;
+        if stat.events() > 0 {
+            info_mtrc!(
+                "wpl dispatch: rules={}, indexed={}, events={}, candidates={}, tried={}, avg_tried={:.2}, full_scan={}",
+                self.pipelines.len(),
+                self.index.indexed_cnt(),
+                stat.events(),
+                stat.candidates(),
+                stat.tried(),
+                stat.avg_tried(),
+                stat.full_scan()
+            );
+        }
+        self.dispatch_stat.reset();
         Ok(())
     }
 }
@@ -137,23 +169,42 @@ mod tests {
     use super::*;
     use crate::core::parser::wpl_engine::pipeline::WplPipeline;
     use crate::sinks::SinkGroupAgent;
-    use wpl::WplEvaluator;
+    use std::collections::BTreeMap;
+    use wp_connector_api::Tags;
+    use wp_parse_api::RawData;
+    use wpl::{AnnFun, SourceGuard, WplEvaluator, gen_pkg_id};
 
-    fn dummy_pipeline(idx: usize, hit: usize) -> WplPipeline {
-        let evaluator = WplEvaluator::from_code("rule dummy { ( _ ) }").expect("build wpl");
-        let parser = evaluator;
-        let mut pipeline = WplPipeline::new(
+    fn rule_pipeline(idx: usize, code: &str) -> WplPipeline {
+        let parser = WplEvaluator::from_code(code).expect("build wpl");
+        WplPipeline::new(
             idx,
             format!("rule-{}", idx),
             Vec::new(),
             parser,
             vec![SinkGroupAgent::null()],
             Vec::new(),
-        );
+        )
+    }
+
+    fn dummy_pipeline(idx: usize, hit: usize) -> WplPipeline {
+        let mut pipeline = rule_pipeline(idx, "rule dummy { ( _ ) }");
         pipeline.hit_cnt = hit;
         pipeline
     }
 
+    fn build_event(payload: &str) -> SourceEvent {
+        build_src_event("test-src", payload)
+    }
+
+    fn build_src_event(src_key: &str, payload: &str) -> SourceEvent {
+        SourceEvent::new(
+            gen_pkg_id(),
+            src_key,
+            RawData::String(payload.to_string()),
+            Arc::new(Tags::new()),
+        )
+    }
+
     #[test]
     fn optimized_reorders_by_hit_count() {
         let pipelines = vec![
@@ -173,6 +224,77 @@ mod tests {
         assert_eq!(order, vec!["rule-1", "rule-2", "rule-0"]);
         assert!(parser.pipelines.iter().all(|p| p.hit_cnt == 0));
     }
+
+    #[test]
+    fn dispatch_index_selects_by_prefix() {
+        let mut parser = MultiParser::new(vec![
+            rule_pipeline(0, "rule a { (symbol(<134>), chars:host) }"),
+            rule_pipeline(1, "rule b { (symbol(CEF:), chars:body) }"),
+            rule_pipeline(2, "rule c { (digit:code) }"),
+        ]);
+        assert_eq!(parser.index.indexed_cnt(), 2);
+        let cands = |data: &str| parser.index.candidates(&RawData::from_string(data));
+        assert_eq!(cands("<134> web01"), vec![0, 2]);
+        assert_eq!(cands("  CEF:0|x"), vec![1, 2]);
+        assert_eq!(cands("404"), vec![2]);
+
+        let option = ParseOption::default();
+        let result = parser.parse_event(&build_event("CEF:abc"), &option);
+        assert!(
+            matches!(result, ProcessResult::Success { ref wpl_key, .. } if wpl_key == "rule-1")
+        );
+        let result = parser.parse_event(&build_event("404"), &option);
+        assert!(
+            matches!(result, ProcessResult::Success { ref wpl_key, .. } if wpl_key == "rule-2")
+        );
+
+        let stat = parser.dispatch_stat().clone();
+        assert_eq!(stat.events(), 2);
+        assert_eq!(stat.candidates(), 3);
+        // CEF 事件先试中 rule-1；数字事件只剩回退规则
+        assert_eq!(stat.tried(), 2);
+        assert_eq!(stat.full_scan(), 0);
+
+        // 排序后索引随规则位置重建
+        parser.optimized(0);
+        let pos = |key: &str| {
+            parser
+                .pipelines
+                .iter()
+                .position(|p| p.wpl_key() == key)
+                .unwrap()
+        };
+        let (cef, fallback) = (pos("rule-1"), pos("rule-2"));
+        let mut expect = vec![cef, fallback];
+        expect.sort();
+        assert_eq!(
+            parser.index.candidates(&RawData::from_string("CEF:x")),
+            expect
+        );
+    }
+
+    #[test]
+    fn source_guard_skips_other_sources() {
+        let fw_only = AnnFun {
+            source: BTreeMap::from([("src_key".into(), "fw_*".into())]),
+            ..Default::default()
+        };
+        let mut parser = MultiParser::new(vec![
+            rule_pipeline(0, "rule fw { (digit:code) }")
+                .with_source(SourceGuard::convert(&Some(fw_only))),
+            rule_pipeline(1, "rule any { (digit:code) }"),
+        ]);
+        let option = ParseOption::default();
+        let result = parser.parse_event(&build_src_event("fw_syslog", "200"), &option);
+        assert!(
+            matches!(result, ProcessResult::Success { ref wpl_key, .. } if wpl_key == "rule-0")
+        );
+        let result = parser.parse_event(&build_src_event("proxy_file", "200"), &option);
+        assert!(
+            matches!(result, ProcessResult::Success { ref wpl_key, .. } if wpl_key == "rule-1")
+        );
+        assert_eq!(parser.dispatch_stat().tried(), 2);
+    }
 }
 
 // 重新导出主要类型
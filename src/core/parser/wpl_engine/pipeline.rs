@@ -8,13 +8,14 @@ use wp_parse_api::{DataResult, RawData};
 use wp_stat::StatRecorder;
 use wp_stat::StatReq;
 use wpl::WparseResult;
-use wpl::{AnnotationFunc, AnnotationType};
+use wpl::{AnnotationFunc, AnnotationType, SourceGuard};
 use wpl::{OPTIMIZE_TIMES, WplEvaluator};
 
 #[derive(Getters, Clone)]
 pub struct WplPipeline {
     parser: WplEvaluator,
     fun_vec: Vec<AnnotationType>,
+    source: Option<SourceGuard>,
     pub hit_cnt: usize,
     pub access_cnt: usize,
     pub index: usize,
@@ -40,6 +41,7 @@ impl WplPipeline {
         Self {
             parser,
             fun_vec,
+            source: None,
             index,
             wpl_key,
             output,
@@ -50,6 +52,17 @@ impl WplPipeline {
         }
     }
 
+    /// 设置规则来源选择（`#[source(...)]`）
+    pub fn with_source(mut self, source: Option<SourceGuard>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// 事件来源是否满足规则的来源选择，未声明时总是满足
+    pub fn accept_source(&self, data: &SourceEvent) -> bool {
+        self.source.as_ref().is_none_or(|guard| guard.accept(data))
+    }
+
     pub fn short_name(&self) -> &str {
         self.s_name.as_str()
     }
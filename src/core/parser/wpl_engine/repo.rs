@@ -9,7 +9,7 @@ use wp_model_core::model::DataRecord;
 use derive_getters::Getters;
 use orion_error::ErrStrategy;
 use std::collections::HashSet;
-use wpl::WplPackage;
+use wpl::{WplPackage, resolve_pkg_refs};
 
 #[derive(Clone, Default)]
 pub struct WplRepository {
@@ -83,6 +83,8 @@ impl WplRepository {
             }
         }
 
+        // 字段组 `use` 可跨文件引用，全部包载入后统一展开
+        resolve_pkg_refs(&mut rules)?;
         Ok(Self { packages: rules })
     }
     pub fn from_wpl_tolerant(value: WplCodePKG, error: &impl RecSyncSink) -> WplCodeResult<Self> {
@@ -2,7 +2,8 @@ use clap::{Args, Parser, Subcommand};
 use wpl::check_level_or_stop;
 
 //use crate::build::CLAP_LONG_VERSION;
-use wp_error::run_error::RunResult;
+use orion_error::{ToStructError, UvsConfFrom};
+use wp_error::run_error::{RunReason, RunResult};
 
 use orion_overload::conv::val_or;
 use wp_conf::RunArgs;
@@ -34,6 +35,63 @@ pub enum WParseCLI {
 pub enum DvChk {
     #[command(name = "engine")]
     Engine(ParseArgs),
+    /// Run `test` samples in WPL files, print diffs, fail on mismatch/执行 WPL 文件内 test 样例，输出差异，失败时非零退出
+    #[command(name = "test")]
+    Test(ParseArgs),
+    /// Explain how a WPL rule consumes one sample/以 explain 模式解析单条样本，标注各字段消费的区间与失败原因
+    #[command(name = "explain")]
+    Explain(ExplainArgs),
+    /// Format WPL files in canonical layout, keeping comments/按规范格式改写 WPL 文件，保留注释
+    #[command(name = "fmt")]
+    Fmt(FmtArgs),
+}
+
+#[derive(Parser, Debug, Default)]
+#[command(name = "fmt")]
+pub struct FmtArgs {
+    /// Work root directory (contains conf/ etc.)/工作根目录（包含 conf/ 等）
+    #[clap(long, default_value = ".")]
+    pub work_root: String,
+    /// WPL files to format; all rule files under work root when absent/待格式化的 WPL 文件；不传时处理工作目录下全部规则文件
+    pub files: Vec<String>,
+    /// Check only, exit non-zero if any file is not formatted/仅检查，存在未格式化文件时非零退出
+    #[clap(long, default_value = "false")]
+    pub check: bool,
+}
+
+#[derive(Parser, Debug, Default)]
+#[command(name = "explain")]
+pub struct ExplainArgs {
+    /// Work root directory (contains conf/ etc.)/工作根目录（包含 conf/ 等）
+    #[clap(long, default_value = ".")]
+    pub work_root: String,
+    /// Rule to explain: `pkg/rule` or rule name; explain every sample.dat when absent/要解释的规则：`pkg/rule` 或规则名；不传时解释规则目录下全部 sample.dat
+    #[clap(short, long)]
+    pub rule: Option<String>,
+    /// Sample line; read the first line of --file when absent/样本行；不传时读取 --file 的首行
+    pub sample: Option<String>,
+    /// Sample file/样本文件
+    #[clap(short, long)]
+    pub file: Option<String>,
+}
+
+impl ExplainArgs {
+    /// 待解释的样本：优先取命令行参数，否则取 --file 的首个非空行
+    pub fn sample_line(&self) -> RunResult<String> {
+        if let Some(sample) = &self.sample {
+            return Ok(sample.clone());
+        }
+        let Some(file) = &self.file else {
+            return Err(RunReason::from_conf("explain needs a sample or --file").to_err());
+        };
+        let text = std::fs::read_to_string(file).map_err(|e| {
+            RunReason::from_conf(format!("read sample file failed {}: {}", file, e)).to_err()
+        })?;
+        text.lines()
+            .find(|x| !x.trim().is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| RunReason::from_conf(format!("sample file is empty: {}", file)).to_err())
+    }
 }
 
 #[derive(Args, Debug)]
@@ -1,3 +1,3 @@
 //! CLI 类型的稳定 re-export，避免应用层深入内部命名空间。
 
-pub use crate::facade::args::{ParseArgs, WParseCLI};
+pub use crate::facade::args::{DvChk, ExplainArgs, FmtArgs, ParseArgs, WParseCLI};
@@ -53,6 +53,9 @@ impl WpApp {
         if let Some(dir) = &args.wpl_dir {
             main_conf.set_rule_root(dir.clone());
         }
+        if let Some(limit) = main_conf.decompress_max_bytes() {
+            wpl::eval::builtins::compress::set_max_decompressed_bytes(limit);
+        }
         let run_args = args.completion_from(&main_conf)?;
         let stat_reqs = stat_reqs_from(main_conf.stat_conf());
         // 注：log_profile 覆盖行为在 EngineConfig 内部不再可变；此处直接使用配置文件
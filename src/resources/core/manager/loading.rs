@@ -2,6 +2,7 @@ use crate::orchestrator::config::build_sinks::SinkRouteTable;
 use crate::resources::ModelName;
 use crate::resources::utils::{load_engine_code, load_oml_code};
 use crate::sinks::SinkGroupAgent;
+use oml::PrivacyConf;
 use oml::core::ConfADMExt;
 use oml::language::{DataModel, ObjModel};
 use orion_conf::{ErrorWith, UvsConfFrom};
@@ -30,19 +31,30 @@ impl ResManager {
         Ok(())
     }
 
-    pub async fn load_all_model(&mut self, oml_root: &str) -> RunResult<()> {
+    pub async fn load_all_model(&mut self, main_conf: &EngineConfig) -> RunResult<()> {
         info_ctrl!("load all oml model");
-        let oml_spc = load_oml_code(oml_root).await?;
+        let oml_spc = load_oml_code(main_conf.oml_root()).await?;
+        let privacy = PrivacyConf::new(main_conf.privacy_salt());
         let wpl_index = self
             .wpl_index
             .clone()
             .ok_or(RunReason::from_logic("not init  wpl all rule key"))?;
         for (path, _code) in oml_spc.items {
             if std::path::Path::new(path.as_str()).exists() && path.ends_with(".oml") {
-                let mdl = ObjModel::load(path.as_str())
+                let mut mdl = ObjModel::load(path.as_str())
                     .err_conv()
                     .want("load oml")
                     .with(path.as_str())?;
+                // 空 salt 的 hash/token 可被暴力还原，拒绝加载
+                if mdl.needs_privacy_salt() && privacy.salt.is_empty() {
+                    return Err(RunReason::from_conf(format!(
+                        "oml model '{}' uses hash/token privacy but [privacy].salt is empty: {}",
+                        mdl.name(),
+                        path
+                    ))
+                    .to_err());
+                }
+                mdl.bind_privacy_conf(privacy.clone());
                 info_data!("oml load success, from {} ", path);
                 for w_rule in mdl.rules().as_ref() {
                     for r_path in wpl_index.rule_key().iter() {
@@ -80,7 +80,7 @@ impl ResManager {
         res_center
             .load_all_wpl_code(main_conf, infra_sinks.agent().error())
             .await?;
-        res_center.load_all_model(main_conf.oml_root()).await?;
+        res_center.load_all_model(main_conf).await?;
         res_center
             .load_all_sink(main_conf.sinks_root())
             .owe_conf()?;
@@ -105,7 +105,7 @@ impl ResManager {
         .owe_rule()?;
         res_center.wpl_index = Some(crate::core::parser::SpaceIndex::from(&wpl_space));
         res_center.wpl_space = Some(wpl_space);
-        res_center.load_all_model(main_conf.oml_root()).await?;
+        res_center.load_all_model(main_conf).await?;
         res_center
             .load_all_sink(main_conf.sinks_root())
             .owe_conf()?;
@@ -11,9 +11,13 @@ use wp_conf::engine::EngineConfig;
 use wp_error::run_error::RunResult;
 use wp_stat::StatReq;
 use wpl::AnnotationType;
+use wpl::SourceGuard;
 use wpl::WplEvaluator;
-use wpl::util::fetch_wpl_data;
-use wpl::{WplCode, WplExpress, WplPackage, WplRule, WplStatementType};
+use wpl::parser::error::WplCodeReason;
+use wpl::util::{fetch_grok_patterns, fetch_proto_descriptors, fetch_ua_db, fetch_wpl_data};
+use wpl::{
+    WplCode, WplCodeError, WplCodeResult, WplExpress, WplPackage, WplRule, WplStatementType,
+};
 
 use super::RuleKey;
 use super::core::allocator::ParserResAlloc;
@@ -38,7 +42,8 @@ pub fn multi_code_ins_parse_units(
             parser,
             agent,
             stat_reqs.clone(),
-        );
+        )
+        .with_source(source_guard(rule));
         items.push(ppu);
     }
     Ok(items)
@@ -63,7 +68,8 @@ pub fn code_ins_parse_units(
             parser,
             agent,
             Vec::new(),
-        );
+        )
+        .with_source(source_guard(rule));
         items.push(ppu);
     }
     Ok(items)
@@ -73,6 +79,10 @@ pub fn annotate_funcs(rule: &WplRule) -> Vec<AnnotationType> {
     AnnotationType::convert(rule.statement.tags())
 }
 
+pub fn source_guard(rule: &WplRule) -> Option<SourceGuard> {
+    SourceGuard::convert(rule.statement.tags())
+}
+
 pub fn build_multi_src_parser_set(rule: &WplRule) -> RunResult<WplEvaluator> {
     let parser = rule_to_parser_ex(rule, None)?;
     Ok(parser)
@@ -81,15 +91,21 @@ pub fn build_multi_src_parser_set(rule: &WplRule) -> RunResult<WplEvaluator> {
 pub fn rule_to_parser_ex(rule: &WplRule, preorder: Option<&WplExpress>) -> RunResult<WplEvaluator> {
     let parser = match &rule.statement {
         WplStatementType::Express(code) => WplEvaluator::from(code, preorder).owe_rule()?,
+        // fields/use 声明在包加载时展开，不单独生成解析器
+        _ => return not_rule(rule).owe_rule(),
     };
     Ok(parser)
 }
 
 pub fn rule_to_parser(rule: &WplRule) -> RunResult<WplEvaluator> {
-    let parser = match &rule.statement {
-        WplStatementType::Express(code) => WplEvaluator::from(code, None).owe_rule()?,
-    };
-    Ok(parser)
+    rule_to_parser_ex(rule, None)
+}
+
+fn not_rule(rule: &WplRule) -> WplCodeResult<WplEvaluator> {
+    Err(WplCodeError::from(WplCodeReason::UnSupport(format!(
+        "'{}' is not a rule",
+        rule.name
+    ))))
 }
 
 pub async fn load_oml_code(oml_root: &str) -> RunResult<OmlRepository> {
@@ -101,6 +117,11 @@ pub async fn load_wpl_code(
     rule_file: Option<String>,
 ) -> RunResult<Vec<WplCode>> {
     let rule_path: String = rule_file.clone().unwrap_or(conf.rule_root().to_string());
+    // grok 字段在规则加载时展开，模式目录需先于 WPL 载入
+    fetch_grok_patterns(conf.grok_root()).owe_conf()?;
+    fetch_ua_db(conf.uap_file()).owe_conf()?;
+    // decode/proto/<message> 在规则加载时解析消息描述
+    fetch_proto_descriptors(conf.proto_root()).owe_conf()?;
     fetch_wpl_data(rule_path.as_str(), WPARSE_RULE_FILE).owe_conf()
 }
 
@@ -18,12 +18,10 @@ impl RuleGenSource {
             }
             let fields_map = u.get_fields().clone();
             for wpl_rule in u.get_rules().iter() {
-                match &wpl_rule.statement {
-                    WplStatementType::Express(_) => {
-                        let cr = wpl_compile_rule(wpl_rule, &fields_map)
-                            .map_err(|e| anyhow!("compile_rule error: {}", e))?;
-                        compiled.push(cr);
-                    }
+                if let WplStatementType::Express(_) = &wpl_rule.statement {
+                    let cr = wpl_compile_rule(wpl_rule, &fields_map)
+                        .map_err(|e| anyhow!("compile_rule error: {}", e))?;
+                    compiled.push(cr);
                 }
             }
         }
@@ -249,6 +249,7 @@ impl SinkService {
                 .alloc_sink_res(&SinkID::from(group_conf.name()))
                 .await?,
         );
+        sink_group.enable_drop_stat(stat_reqs.to_owned());
         for conf in group_conf.sinks() {
             Self::init_sink_group(
                 rescue.clone(),
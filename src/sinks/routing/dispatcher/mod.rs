@@ -5,12 +5,15 @@ use wp_conf::limits::sink_channel_cap;
 use crate::resources::SinkResUnit;
 use crate::sinks::SinkRuntime;
 use crate::sinks::{ASinkSender, SinkDatYReceiver, SinkDatYSender, SinkPackage, SinkRecUnit};
-use crate::stat::MonSend;
+use crate::stat::metric_collect::MetricCollectors;
+use crate::stat::{MonSend, STAT_INTERVAL_MS};
 use derive_getters::Getters;
+use orion_error::{ErrorOwe, ErrorWith};
 use orion_overload::append::Appendable;
 use wp_conf::structure::SinkGroupConf;
 use wp_connector_api::SinkResult;
 use wp_data_model::cache::FieldQueryCache;
+use wp_stat::{StatReq, TimedStat};
 
 // split internal helpers
 
@@ -56,12 +59,17 @@ pub struct SinkDispatcher {
     dat_r: SinkDatYReceiver,
     res: SinkResUnit,
     unit_pool: SinkRecUnitPool,
+    // OML 过滤丢弃按组计数：每条记录只计一次，不归属组内任何 sink
+    drop_stat: MetricCollectors,
+    drop_timer: TimedStat,
+    drop_used: bool,
 }
 
 impl SinkDispatcher {
     pub fn new(conf: SinkGroupConf, res: SinkResUnit) -> Self {
         // 改用 tokio::mpsc 事件化通道，便于与 runtime 协作
         let (dat_s, dat_r) = tokio::sync::mpsc::channel(sink_channel_cap());
+        let drop_stat = MetricCollectors::new(Self::drop_target(&conf), Vec::new());
         Self {
             conf,
             sinks: Vec::new(),
@@ -69,8 +77,18 @@ impl SinkDispatcher {
             dat_r,
             res,
             unit_pool: SinkRecUnitPool::new(),
+            drop_stat,
+            drop_timer: TimedStat::new(),
+            drop_used: false,
         }
     }
+    fn drop_target(conf: &SinkGroupConf) -> String {
+        format!("{}_drop", conf.name())
+    }
+    /// 按统计需求启用组级 drop 统计
+    pub fn enable_drop_stat(&mut self, stat_reqs: Vec<StatReq>) {
+        self.drop_stat = MetricCollectors::new(Self::drop_target(&self.conf), stat_reqs);
+    }
     pub fn get_dat_r_mut(&mut self) -> &mut SinkDatYReceiver {
         &mut self.dat_r
     }
@@ -159,10 +177,30 @@ impl SinkDispatcher {
                 self.unit_pool.recycle(leftover);
             }
         }
+        if let Some(mon_stat) = mon {
+            self.timed_drop_stat(mon_stat).await?;
+        }
 
         Ok(processed_count)
     }
 
+    async fn timed_drop_stat(&mut self, mon_send: &MonSend) -> SinkResult<()> {
+        if !self.drop_used
+            || !self
+                .drop_timer
+                .over_reset_timed_millis(STAT_INTERVAL_MS as u128)
+        {
+            return Ok(());
+        }
+        self.drop_stat
+            .send_stat(mon_send)
+            .await
+            .owe_sys()
+            .want("group drop stat")?;
+        self.drop_timer.reset_now();
+        Ok(())
+    }
+
     // heavy OML pipeline helpers are moved to dispatcher::oml
 
     // 直发与原始数据下发在 dispatcher::io
@@ -15,6 +15,7 @@ use wp_connector_api::SinkResult;
 use wp_data_model::cache::FieldQueryCache;
 use wp_data_model::conditions::evaluate_expression;
 use wp_model_core::model::{DataField, DataRecord};
+use wp_stat::StatRecorder;
 
 // 说明：原实现通过构建 HashMap<name, DataRecord> 聚合每个 sink 的待投递数据，
 // 这会导致对同一条记录进行 N 次 clone（每命中一个 sink 就 clone 一次），
@@ -29,6 +30,8 @@ use wp_model_core::model::{DataField, DataRecord};
 enum OmlOutcome {
     Success(DataRecord),
     Failure(DataRecord),
+    // 被模型 where/drop when 过滤，携带原始记录用于计数
+    Dropped(DataRecord),
 }
 
 struct TransformedRecUnit {
@@ -51,6 +54,12 @@ impl TransformedRecUnit {
     }
 }
 
+type OmlBatchOutcome = (
+    Vec<TransformedRecUnit>,
+    Vec<SinkRecUnit>,
+    Vec<Arc<DataRecord>>,
+);
+
 impl SinkDispatcher {
     fn has_conditions(&self) -> bool {
         self.sinks.iter().any(|sink| sink.get_cond().is_some())
@@ -81,7 +90,10 @@ impl SinkDispatcher {
         };
 
         let original_len = input.items.len();
-        let output = om_ins.transform(input, cache);
+        // 单条路径不支持 1→N，explode 仅在批处理路径 run_oml_pipeline_vec 中生效
+        let Some(output) = om_ins.transform_or_drop(&input, cache) else {
+            return Ok(OmlOutcome::Dropped(input));
+        };
         if output.items.is_empty() {
             let mut failed = output.clone();
             Self::annotate_err(
@@ -98,12 +110,13 @@ impl SinkDispatcher {
         Ok(OmlOutcome::Success(output))
     }
 
+    // 返回 (成功, 失败, 被过滤丢弃)；单条输入可展开为多条成功记录
     fn run_oml_pipeline_vec(
         &self,
         wpl_meta: &ProcMeta,
         input: Vec<SinkRecUnit>,
         cache: &mut FieldQueryCache,
-    ) -> SinkResult<(Vec<TransformedRecUnit>, Vec<SinkRecUnit>)> {
+    ) -> SinkResult<OmlBatchOutcome> {
         let Some(om_ins) = self.get_match_oml(wpl_meta) else {
             let passthrough = input
                 .into_iter()
@@ -114,36 +127,44 @@ impl SinkDispatcher {
                     TransformedRecUnit::new(pkg_id, meta, record)
                 })
                 .collect();
-            return Ok((passthrough, Vec::new()));
+            return Ok((passthrough, Vec::new(), Vec::new()));
         };
 
         let mut successes = Vec::with_capacity(input.len());
         let mut failures = Vec::new();
+        let mut dropped = Vec::new();
         for unit in input {
             let (pkg_id, meta, record_arc) = unit.into_parts();
             let original_len = record_arc.items.len();
-            let output = om_ins.transform_ref(record_arc.as_ref(), cache);
-            if output.items.is_empty() {
-                let mut failed = output.clone();
-                Self::annotate_err(
-                    &mut failed,
-                    "oml_transform_empty",
-                    wpl_meta,
-                    self.conf.name(),
-                    om_ins.name(),
-                    original_len,
-                    output.items.len(),
-                );
-                failures.push(SinkRecUnit::with_record(
-                    pkg_id,
-                    meta.clone(),
-                    Arc::new(failed),
-                ));
-            } else {
-                successes.push(TransformedRecUnit::new(pkg_id, meta, output));
+            // explode 模型可产出多条记录，共享同一 pkg_id 与 meta
+            // None 为过滤丢弃；空数组展开得到空 Vec，不计入 drop
+            let Some(outputs) = om_ins.transform_many(record_arc.as_ref(), cache) else {
+                dropped.push(record_arc);
+                continue;
+            };
+            for output in outputs {
+                if output.items.is_empty() {
+                    let mut failed = output.clone();
+                    Self::annotate_err(
+                        &mut failed,
+                        "oml_transform_empty",
+                        wpl_meta,
+                        self.conf.name(),
+                        om_ins.name(),
+                        original_len,
+                        output.items.len(),
+                    );
+                    failures.push(SinkRecUnit::with_record(
+                        pkg_id,
+                        meta.clone(),
+                        Arc::new(failed),
+                    ));
+                } else {
+                    successes.push(TransformedRecUnit::new(pkg_id, meta.clone(), output));
+                }
             }
         }
-        Ok((successes, failures))
+        Ok((successes, failures, dropped))
     }
 
     // 为错误记录添加标准诊断字段
@@ -220,7 +241,10 @@ impl SinkDispatcher {
             return Ok(self.emit_without_transform_batch(batch));
         }
 
-        let (successes, failures) = self.run_oml_pipeline_vec(rule, batch, cache)?;
+        let (successes, failures, dropped) = self.run_oml_pipeline_vec(rule, batch, cache)?;
+        for record in dropped.iter() {
+            self.record_oml_drop(record);
+        }
         for bad in failures {
             let (pkg_id, _, bad_arc) = bad.into_parts();
             let record = Arc::try_unwrap(bad_arc).unwrap_or_else(|arc| arc.as_ref().clone());
@@ -282,6 +306,10 @@ impl SinkDispatcher {
                 self.emit_oml_failure(pkg_id, infra, rule, bad)?;
                 return Ok(Vec::new());
             }
+            OmlOutcome::Dropped(record) => {
+                self.record_oml_drop(&record);
+                return Ok(Vec::new());
+            }
         };
         let matches = self.evaluate_sink_matches(&base);
         let mut remaining = matches.iter().filter(|&&m| m).count();
@@ -384,6 +412,14 @@ impl SinkDispatcher {
         }
     }
 
+    // 被过滤的记录不进入任何 sink，只在组级 drop 统计中计一次
+    fn record_oml_drop(&mut self, record: &DataRecord) {
+        debug_data!("sink group {} oml drop record", self.conf.name());
+        self.drop_used = true;
+        self.drop_stat
+            .record_task(self.conf.name().as_str(), Some(record));
+    }
+
     fn emit_oml_failure(
         &self,
         pkg_id: PkgID,
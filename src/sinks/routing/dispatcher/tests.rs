@@ -380,4 +380,137 @@ converted : chars = chars(done) ;
     }
 }
 
+#[test]
+fn batch_oml_filter_drops_records() {
+    use wp_model_core::model::DataField;
+    use wp_stat::{StatReq, StatTarget};
+
+    let mut sink_res = SinkResUnit::use_null();
+    let mut code = r#"
+name : drop_model
+rule :
+    /batch/drop
+drop when : match read(level) { chars(debug) } ;
+---
+level : chars = read() ;
+"#;
+    let model = oml_parse(&mut code).expect("parse oml model");
+    sink_res.push_model(DataModel::Object(model));
+
+    let mut group = FlexGroup::default();
+    group.name = "drop".to_string();
+    let mut dispatcher = SinkDispatcher::new(SinkGroupConf::Flexi(group), sink_res);
+    dispatcher.enable_drop_stat(vec![StatReq::simple_test(StatTarget::All, Vec::new(), 10)]);
+    for name in ["sink_a", "sink_b"] {
+        let sink_conf = SinkInstanceConf::null_new(name.to_string(), TextFmt::Json, None);
+        dispatcher.append(SinkRuntime::new(
+            "./rescue".to_string(),
+            name.to_string(),
+            sink_conf,
+            SinkBackendType::Proxy(crate::sinks::builtin_factories::make_blackhole_sink()),
+            None,
+            Vec::new(),
+        ));
+    }
+
+    let rule = crate::sinks::ProcMeta::Rule("/batch/drop".to_string());
+    let mut keep = DataRecord::default();
+    keep.append(DataField::from_chars("level", "error"));
+    let mut drop = DataRecord::default();
+    drop.append(DataField::from_chars("level", "debug"));
+    let batch = vec![
+        SinkRecUnit::with_record(1, rule.clone(), Arc::new(keep)),
+        SinkRecUnit::with_record(2, rule.clone(), Arc::new(drop.clone())),
+    ];
+
+    let mut cache = FieldQueryCache::default();
+    let outputs = dispatcher
+        .oml_proc_batch(batch, &InfraSinkAgent::use_null(), &mut cache, &rule)
+        .unwrap();
+    assert_eq!(outputs[0].len(), 1);
+    assert_eq!(outputs[0][0].id(), &1);
+
+    // 单条路径同样不下发
+    let out = dispatcher
+        .oml_proc(
+            3,
+            &InfraSinkAgent::use_null(),
+            &mut cache,
+            &rule,
+            Arc::new(drop),
+        )
+        .unwrap();
+    assert!(out.is_empty());
+
+    // 两条被过滤记录只在组级统计计数一次，不随 sink 数量放大
+    assert!(dispatcher.drop_used);
+    let report = dispatcher.drop_stat.items[0].collect_stat();
+    assert_eq!(report.get_data().len(), 1);
+    assert_eq!(report.get_data()[0].stat.total, 2);
+}
+
+#[test]
+fn batch_oml_explode_fans_out_records() {
+    use wp_model_core::model::DataField;
+    use wp_model_core::model::types::value::ObjectValue;
+
+    let mut sink_res = SinkResUnit::use_null();
+    let mut code = r#"
+name : explode_model
+rule :
+    /batch/explode
+explode : records keep (host) ;
+---
+user : chars = read() ;
+"#;
+    let model = oml_parse(&mut code).expect("parse oml model");
+    sink_res.push_model(DataModel::Object(model));
+
+    let mut group = FlexGroup::default();
+    group.name = "explode".to_string();
+    let mut dispatcher = SinkDispatcher::new(SinkGroupConf::Flexi(group), sink_res);
+    let sink_conf = SinkInstanceConf::null_new("sink_a".to_string(), TextFmt::Json, None);
+    dispatcher.append(SinkRuntime::new(
+        "./rescue".to_string(),
+        "sink_a".to_string(),
+        sink_conf,
+        SinkBackendType::Proxy(crate::sinks::builtin_factories::make_blackhole_sink()),
+        None,
+        Vec::new(),
+    ));
+
+    let events: Vec<DataField> = ["alice", "bob", "carol"]
+        .iter()
+        .map(|user| {
+            let mut obj = ObjectValue::default();
+            obj.insert("user", DataField::from_chars("user", *user));
+            DataField::from_obj("", obj)
+        })
+        .collect();
+    let mut rec = DataRecord::default();
+    rec.append(DataField::from_chars("host", "cloud-a"));
+    rec.append(DataField::from_arr("records", events));
+    let mut empty = DataRecord::default();
+    empty.append(DataField::from_arr("records", Vec::new()));
+
+    let rule = crate::sinks::ProcMeta::Rule("/batch/explode".to_string());
+    let batch = vec![
+        SinkRecUnit::with_record(7, rule.clone(), Arc::new(rec)),
+        SinkRecUnit::with_record(8, rule.clone(), Arc::new(empty)),
+    ];
+    let mut cache = FieldQueryCache::default();
+    let outputs = dispatcher
+        .oml_proc_batch(batch, &InfraSinkAgent::use_null(), &mut cache, &rule)
+        .unwrap();
+
+    assert_eq!(outputs[0].len(), 3);
+    for (unit, user) in outputs[0].iter().zip(["alice", "bob", "carol"]) {
+        assert_eq!(unit.id(), &7);
+        assert_eq!(unit.data().get_value("user"), Some(&Value::from(user)));
+        assert_eq!(unit.data().get_value("host"), Some(&Value::from("cloud-a")));
+    }
+    // 空数组展开不是过滤丢弃
+    assert!(!dispatcher.drop_used);
+}
+
 // 隐私相关逻辑与字段已移除：对应行为测试一并删除
@@ -3,11 +3,12 @@ use crate::sinks::pdm_outer::TDMDataAble;
 use crate::sinks::prelude::*;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use orion_error::ErrorOwe;
 use wp_data_fmt::{DataFormat, FormatType};
 use wp_model_core::model::fmt_def::TextFmt;
 use wp_parse_api::RawData;
-use wpl::generator::{CSVGenFmt, JsonGenFmt, KVGenFmt, ProtoGenFmt, RAWGenFmt};
+use wpl::generator::{CSVGenFmt, JsonGenFmt, KVGenFmt, ProtoBinFmt, ProtoGenFmt, RAWGenFmt};
 
 use crate::sinks::SinkRecUnit;
 use crate::types::AnyResult;
@@ -16,6 +17,10 @@ use wp_connector_api::SinkResult;
 use wp_model_core::model::{DataField, DataRecord};
 
 pub fn fds_fmt_proc(fmt: TextFmt, line: DataRecord) -> AnyResult<RawData> {
+    // 二进制 protobuf：带长度前缀的 WpRecord
+    if fmt == TextFmt::Proto {
+        return Ok(RawData::Bytes(Bytes::from(ProtoBinFmt(&line).encode())));
+    }
     let formatter = FormatType::from(&fmt);
     let res = RawData::String(format!("{}\n", formatter.format_record(&line)));
 
@@ -29,9 +34,7 @@ pub fn gen_fmt_dat(fmt: TextFmt, line: FmtFieldVec) -> AnyResult<RawData> {
         TextFmt::Show => RawData::String(format!("{:?}\n", line)),
         TextFmt::Csv => RawData::String(format!("{}\n", CSVGenFmt(&line))),
         TextFmt::Raw => RawData::String(format!("{}\n", RAWGenFmt(&line))),
-        TextFmt::Proto => {
-            unimplemented!("unsupport proto buf gen")
-        }
+        TextFmt::Proto => RawData::Bytes(Bytes::from(ProtoBinFmt(&line).encode())),
         TextFmt::ProtoText => RawData::String(format!("{}\n", ProtoGenFmt(&line))),
     };
     Ok(data)
@@ -114,14 +117,7 @@ where
 {
     async fn sink_str(&mut self, data: &str) -> SinkResult<()> {
         if let Some(ref mut next) = self.next_proc {
-            match self.fmt {
-                TextFmt::Proto => {
-                    unimplemented!("unsupport protobuf format")
-                }
-                _ => {
-                    return next.sink_str(data).await;
-                }
-            }
+            return next.sink_str(data).await;
         }
         Ok(())
     }
@@ -1,4 +1,5 @@
 use super::source::{FileEncoding, FileSource};
+use crate::sources::multiline::{MULTILINE_PARAMS, MultilineConf};
 use async_trait::async_trait;
 use orion_conf::UvsConfFrom;
 use orion_error::ToStructError;
@@ -19,6 +20,7 @@ struct FileSourceSpec {
     path: String,
     encoding: FileEncoding,
     instances: usize,
+    multiline: Option<MultilineConf>,
 }
 
 impl FileSourceSpec {
@@ -39,7 +41,9 @@ impl FileSourceSpec {
             std::path::Path::new(base).join(file).display().to_string()
         };
         let encoding = match resolved.params.get("encode").and_then(|v| v.as_str()) {
+            None if path.ends_with(".gz") => FileEncoding::Gzip,
             None | Some("text") => FileEncoding::Text,
+            Some("gzip") => FileEncoding::Gzip,
             Some("base64") => FileEncoding::Base64,
             Some("hex") => FileEncoding::Hex,
             Some(v) => {
@@ -56,10 +60,25 @@ impl FileSourceSpec {
             .and_then(|v| v.as_i64())
             .map(|n| n.clamp(1, FILE_SOURCE_MAX_INSTANCES as i64) as usize)
             .unwrap_or(1);
+        let multiline = MultilineConf::from_params(&resolved.params)?;
+        if multiline.is_some() && !matches!(encoding, FileEncoding::Text | FileEncoding::Gzip) {
+            anyhow::bail!(
+                "multiline requires encode=text or gzip for file source '{}'",
+                resolved.name
+            );
+        }
+        // 按字节切分会把跨分片的多行事件截成两半，多行模式只能单实例读取
+        if multiline.is_some() && instances > 1 && !matches!(encoding, FileEncoding::Gzip) {
+            anyhow::bail!(
+                "multiline requires instances=1 for file source '{}'",
+                resolved.name
+            );
+        }
         Ok(Self {
             path,
             encoding,
             instances,
+            multiline,
         })
     }
 }
@@ -91,8 +110,13 @@ impl SourceFactory for FileSourceFactory {
         let fut = async {
             let spec = FileSourceSpec::from_resolved(resolved)?;
             let tagset = Tags::from_parse(&resolved.tags);
-            let ranges = compute_file_ranges(Path::new(&spec.path), spec.instances)
-                .map_err(|e| anyhow::anyhow!("Failed to compute file ranges: {}", e))?;
+            // 压缩文件无法按字节切分，只能单实例顺序读取
+            let ranges = if matches!(spec.encoding, FileEncoding::Gzip) {
+                vec![(0, None)]
+            } else {
+                compute_file_ranges(Path::new(&spec.path), spec.instances)
+                    .map_err(|e| anyhow::anyhow!("Failed to compute file ranges: {}", e))?
+            };
             let mut handles = Vec::with_capacity(ranges.len());
             let multi = ranges.len() > 1;
             for (idx, (start, end)) in ranges.into_iter().enumerate() {
@@ -110,7 +134,8 @@ impl SourceFactory for FileSourceFactory {
                     end,
                 )
                 .await
-                .map_err(|e| anyhow::anyhow!("Failed to create FileSource: {}", e))?;
+                .map_err(|e| anyhow::anyhow!("Failed to create FileSource: {}", e))?
+                .with_multiline(spec.multiline.clone());
                 let mut meta = SourceMeta::new(key, resolved.kind.clone());
                 for (k, v) in tagset.iter() {
                     meta.tags.set(k, v);
@@ -135,7 +160,11 @@ impl SourceDefProvider for FileSourceFactory {
             id: "file_src".into(),
             kind: self.kind().into(),
             scope: ConnectorScope::Source,
-            allow_override: vec!["base".into(), "file".into(), "encode".into()],
+            allow_override: ["base", "file", "encode"]
+                .into_iter()
+                .chain(MULTILINE_PARAMS)
+                .map(String::from)
+                .collect(),
             default_params: params,
             origin: Some("builtin:file_source".into()),
         }
@@ -207,9 +236,10 @@ fn align_to_next_line(path: &Path, offset: u64, file_size: u64) -> std::io::Resu
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sources::multiline::MULTILINE_LINES_TAG;
     use tempfile::NamedTempFile;
     use toml::map::Map as TomlMap;
-    use wp_connector_api::{SourceBuildCtx, SourceFactory, parammap_from_toml_map};
+    use wp_connector_api::{RawData, SourceBuildCtx, SourceFactory, parammap_from_toml_map};
 
     fn build_spec_with_instances(instances: Option<i64>) -> ResolvedSourceSpec {
         let mut params = TomlMap::new();
@@ -241,6 +271,23 @@ mod tests {
         assert_eq!(resolved_under.instances, 1);
     }
 
+    #[test]
+    fn file_spec_rejects_multiline_with_instances() {
+        let mut spec = build_spec_with_instances(Some(4));
+        spec.params
+            .insert("multiline_start".into(), json!(r"^\d{4}-"));
+        let err = FileSourceSpec::from_resolved(&spec).expect_err("multiline + instances");
+        assert!(err.to_string().contains("instances=1"));
+        assert!(FileSourceFactory.validate_spec(&spec).is_err());
+
+        let mut single = build_spec_with_instances(Some(1));
+        single
+            .params
+            .insert("multiline_start".into(), json!(r"^\d{4}-"));
+        let resolved = FileSourceSpec::from_resolved(&single).expect("multiline single");
+        assert!(resolved.multiline.is_some());
+    }
+
     #[test]
     fn compute_file_ranges_aligns_to_line_boundaries() {
         let file = NamedTempFile::new().expect("temp file");
@@ -296,4 +343,109 @@ mod tests {
         assert_eq!(event.tags.len(), 3);
         handle.source.close().await.expect("close source");
     }
+
+    #[tokio::test]
+    async fn build_assembles_multiline_events() {
+        let file = NamedTempFile::new().expect("temp file");
+        std::fs::write(
+            file.path(),
+            b"2024-01-01 ERROR boom\njava.lang.Exception: x\n\tat a.B.c(B.java:1)\n2024-01-01 INFO ok\n",
+        )
+        .expect("write temp file");
+        let mut params = TomlMap::new();
+        params.insert(
+            "path".into(),
+            toml::Value::String(file.path().display().to_string()),
+        );
+        params.insert(
+            "multiline_start".into(),
+            toml::Value::String(r"^\d{4}-".into()),
+        );
+        let spec = ResolvedSourceSpec {
+            name: "file_multiline".into(),
+            kind: "file".into(),
+            connector_id: String::new(),
+            params: parammap_from_toml_map(params),
+            tags: vec![],
+        };
+        let ctx = SourceBuildCtx::new(std::path::PathBuf::from("."));
+        let mut svc = FileSourceFactory
+            .build(&spec, &ctx)
+            .await
+            .expect("build multiline file source");
+        let mut handle = svc.sources.remove(0);
+        let (_tx, rx) = async_broadcast::broadcast::<wp_connector_api::ControlEvent>(1);
+        handle.source.start(rx).await.expect("start file source");
+        let batch = handle.source.receive().await.expect("read batch");
+        let events: Vec<(String, Option<&str>)> = batch
+            .iter()
+            .map(|ev| match &ev.payload {
+                RawData::Bytes(b) => (
+                    String::from_utf8_lossy(b).to_string(),
+                    ev.tags.get(MULTILINE_LINES_TAG),
+                ),
+                _ => panic!("expected bytes payload"),
+            })
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    "2024-01-01 ERROR boom\njava.lang.Exception: x\n\tat a.B.c(B.java:1)"
+                        .to_string(),
+                    Some("3")
+                ),
+                ("2024-01-01 INFO ok".to_string(), Some("1")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn build_reads_gz_file_transparently() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let file = tempfile::Builder::new()
+            .suffix(".gz")
+            .tempfile()
+            .expect("temp gz file");
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(b"hello\nworld\n").expect("compress");
+        std::fs::write(file.path(), enc.finish().expect("finish gz")).expect("write gz");
+
+        let mut spec = build_spec_with_instances(Some(4));
+        spec.params
+            .insert("path".into(), json!(file.path().display().to_string()));
+        let resolved = FileSourceSpec::from_resolved(&spec).expect("gz spec");
+        assert!(matches!(resolved.encoding, FileEncoding::Gzip));
+
+        let ctx = SourceBuildCtx::new(std::path::PathBuf::from("."));
+        let mut svc = FileSourceFactory
+            .build(&spec, &ctx)
+            .await
+            .expect("build gz file source");
+        // 压缩文件不分片
+        assert_eq!(svc.sources.len(), 1);
+        let mut handle = svc.sources.remove(0);
+        let (_tx, rx) = async_broadcast::broadcast::<wp_connector_api::ControlEvent>(1);
+        handle.source.start(rx).await.expect("start file source");
+        let batch = handle.source.receive().await.expect("read batch");
+        let lines: Vec<String> = batch
+            .iter()
+            .map(|ev| match &ev.payload {
+                RawData::Bytes(b) => String::from_utf8_lossy(b).to_string(),
+                _ => panic!("expected bytes payload"),
+            })
+            .collect();
+        assert_eq!(lines, vec!["hello", "world"]);
+    }
+
+    #[test]
+    fn multiline_requires_text_encoding() {
+        let mut spec = build_spec_with_instances(None);
+        spec.params.insert("multiline_start".into(), json!("^x"));
+        spec.params.insert("encode".into(), json!("hex"));
+        assert!(FileSourceSpec::from_resolved(&spec).is_err());
+    }
 }
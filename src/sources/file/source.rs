@@ -1,5 +1,6 @@
 use super::chunk_reader::ChunkedLineReader;
 use crate::sources::event_id::next_event_id;
+use crate::sources::multiline::{Assembled, MultilineAssembler, MultilineConf};
 use async_trait::async_trait;
 use base64::Engine;
 use base64::engine::general_purpose;
@@ -17,6 +18,8 @@ pub enum FileEncoding {
     Text,
     Base64,
     Hex,
+    /// gzip 压缩的文本文件，读取时透明解压
+    Gzip,
 }
 
 const DEFAULT_BATCH_LINES: usize = 128;
@@ -32,6 +35,7 @@ pub struct FileSource {
     pub(super) base_tags: Tags,
     pub(super) batch_lines: usize,
     pub(super) batch_bytes_budget: usize,
+    pub(super) multiline: Option<MultilineAssembler>,
 }
 
 impl FileSource {
@@ -62,8 +66,12 @@ impl FileSource {
         let batch_lines = DEFAULT_BATCH_LINES;
         let batch_bytes_budget = DEFAULT_BATCH_BYTES;
         let chunk_bytes = DEFAULT_CHUNK_BYTES.clamp(MIN_CHUNK_BYTES, MAX_CHUNK_BYTES);
-        let limit = range_end.map(|end| end.saturating_sub(range_start));
-        let reader = ChunkedLineReader::new(file, chunk_bytes, limit);
+        let reader = if matches!(encode, FileEncoding::Gzip) {
+            ChunkedLineReader::gzip(file, chunk_bytes)
+        } else {
+            let limit = range_end.map(|end| end.saturating_sub(range_start));
+            ChunkedLineReader::new(file, chunk_bytes, limit)
+        };
         Ok(Self {
             key,
             reader,
@@ -71,12 +79,19 @@ impl FileSource {
             base_tags: tags,
             batch_lines,
             batch_bytes_budget,
+            multiline: None,
         })
     }
 
+    /// 开启多行聚合（仅 text/gzip 编码）
+    pub fn with_multiline(mut self, conf: Option<MultilineConf>) -> Self {
+        self.multiline = conf.map(MultilineAssembler::new);
+        self
+    }
+
     fn payload_from_line(encode: &FileEncoding, line: Vec<u8>) -> SourceResult<RawData> {
         match encode {
-            FileEncoding::Text => Ok(RawData::Bytes(Bytes::from(line))),
+            FileEncoding::Text | FileEncoding::Gzip => Ok(RawData::Bytes(Bytes::from(line))),
             FileEncoding::Base64 => {
                 let s = std::str::from_utf8(&line).map_err(|_| {
                     SourceError::from(SourceReason::SupplierError(
@@ -113,6 +128,42 @@ impl FileSource {
         )
     }
 
+    fn make_assembled_event(&self, done: Assembled) -> SourceEvent {
+        let tags = done.tags(&self.base_tags);
+        SourceEvent::new(
+            next_event_id(),
+            &self.key,
+            RawData::Bytes(done.payload),
+            Arc::new(tags),
+        )
+    }
+
+    // 读取下一个事件；开启多行聚合时合并物理行，EOF 时输出未完成的事件
+    async fn next_event(&mut self) -> SourceResult<Option<(SourceEvent, usize)>> {
+        loop {
+            let line = self.reader.next_line().await?;
+            let Some(asm) = self.multiline.as_mut() else {
+                let Some(line) = line else {
+                    return Ok(None);
+                };
+                let size = line.len();
+                let payload = Self::payload_from_line(&self.encode, line)?;
+                return Ok(Some((self.make_event(payload), size)));
+            };
+            let done = match line {
+                Some(line) => asm.push(&line),
+                None => match asm.flush() {
+                    Some(done) => Some(done),
+                    None => return Ok(None),
+                },
+            };
+            if let Some(done) = done {
+                let size = done.payload.len();
+                return Ok(Some((self.make_assembled_event(done), size)));
+            }
+        }
+    }
+
     pub fn identifier(&self) -> String {
         self.key.clone()
     }
@@ -125,11 +176,10 @@ impl DataSource for FileSource {
         let mut produced_rows = 0usize;
         let mut used_bytes = 0usize;
         loop {
-            match self.reader.next_line().await? {
-                Some(line) => {
-                    used_bytes = used_bytes.saturating_add(line.len());
-                    let payload = Self::payload_from_line(&self.encode, line)?;
-                    batch.push(self.make_event(payload));
+            match self.next_event().await? {
+                Some((event, size)) => {
+                    used_bytes = used_bytes.saturating_add(size);
+                    batch.push(event);
                     produced_rows += 1;
                     if produced_rows >= self.batch_lines
                         || (self.batch_bytes_budget > 0 && used_bytes >= self.batch_bytes_budget)
@@ -1,6 +1,7 @@
 pub mod config;
 pub mod event_id;
 pub mod file;
+pub mod multiline;
 pub mod net;
 pub mod syslog;
 pub mod tcp;
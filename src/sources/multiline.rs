@@ -0,0 +1,288 @@
+//! 多行事件聚合
+//!
+//! Java 堆栈、数据库审计等记录跨越多个物理行；按行分帧后先经聚合器合并为一个事件再交给解析。
+//! 边界判定：
+//! - `multiline_start`：匹配的行开启新事件，其余行并入当前事件；
+//! - `multiline_continue`：匹配的行并入当前事件，其余行开启新事件；
+//! - 两者同时配置时，匹配起始模式优先开启新事件。
+//!
+//! 当前事件达到 `multiline_max_lines` 行后，下一行另起事件；
+//! 超过 `multiline_timeout_ms` 没有新行时，未完成的事件被强制输出（由调用方驱动）。
+//! 合并后的事件以 `\n` 连接各行，并在标签 `multiline_lines` 中记录物理行数。
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, ensure};
+use bytes::{Bytes, BytesMut};
+use regex::bytes::Regex;
+use wp_connector_api::{ParamMap, Tags};
+
+pub const MULTILINE_LINES_TAG: &str = "multiline_lines";
+/// 可在源配置中覆盖的聚合参数
+pub const MULTILINE_PARAMS: [&str; 4] = [
+    "multiline_start",
+    "multiline_continue",
+    "multiline_max_lines",
+    "multiline_timeout_ms",
+];
+
+const DEFAULT_MAX_LINES: usize = 500;
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+
+#[derive(Debug, Clone)]
+pub struct MultilineConf {
+    start: Option<Regex>,
+    cont: Option<Regex>,
+    max_lines: usize,
+    timeout: Duration,
+}
+
+impl MultilineConf {
+    /// 未配置起始/续行模式时返回 None（不聚合）
+    pub fn from_params(params: &ParamMap) -> anyhow::Result<Option<Self>> {
+        let pattern = |key: &str| -> anyhow::Result<Option<Regex>> {
+            match params.get(key).and_then(|v| v.as_str()) {
+                None | Some("") => Ok(None),
+                Some(p) => Regex::new(p)
+                    .map(Some)
+                    .map_err(|e| anyhow!("Invalid {}: {}", key, e)),
+            }
+        };
+        let start = pattern("multiline_start")?;
+        let cont = pattern("multiline_continue")?;
+        if start.is_none() && cont.is_none() {
+            ensure!(
+                !params.contains_key("multiline_max_lines")
+                    && !params.contains_key("multiline_timeout_ms"),
+                "multiline needs multiline_start or multiline_continue"
+            );
+            return Ok(None);
+        }
+        let max_lines = params
+            .get("multiline_max_lines")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(DEFAULT_MAX_LINES as i64);
+        ensure!(max_lines > 0, "multiline_max_lines must be > 0");
+        let timeout_ms = params
+            .get("multiline_timeout_ms")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(DEFAULT_TIMEOUT_MS as i64);
+        ensure!(timeout_ms > 0, "multiline_timeout_ms must be > 0");
+        Ok(Some(Self {
+            start,
+            cont,
+            max_lines: max_lines as usize,
+            timeout: Duration::from_millis(timeout_ms as u64),
+        }))
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn is_boundary(&self, line: &[u8]) -> bool {
+        if self.start.as_ref().is_some_and(|re| re.is_match(line)) {
+            return true;
+        }
+        self.cont.as_ref().is_some_and(|re| !re.is_match(line))
+    }
+}
+
+/// 聚合完成的事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assembled {
+    pub payload: Bytes,
+    pub lines: usize,
+}
+
+impl Assembled {
+    /// 在基础标签上记录合并的物理行数
+    pub fn tags(&self, base: &Tags) -> Tags {
+        let mut tags = base.clone();
+        tags.set(MULTILINE_LINES_TAG, self.lines.to_string());
+        tags
+    }
+}
+
+pub struct MultilineAssembler {
+    conf: MultilineConf,
+    buf: BytesMut,
+    lines: usize,
+    last_line: Instant,
+}
+
+impl MultilineAssembler {
+    pub fn new(conf: MultilineConf) -> Self {
+        Self {
+            conf,
+            buf: BytesMut::new(),
+            lines: 0,
+            last_line: Instant::now(),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.lines > 0
+    }
+
+    /// 送入一个物理行；若该行结束了上一个事件则返回上一个事件
+    pub fn push(&mut self, line: &[u8]) -> Option<Assembled> {
+        self.last_line = Instant::now();
+        let done = if self.is_pending()
+            && (self.lines >= self.conf.max_lines || self.conf.is_boundary(line))
+        {
+            self.flush()
+        } else {
+            None
+        };
+        if self.is_pending() {
+            self.buf.extend_from_slice(b"\n");
+        }
+        self.buf.extend_from_slice(line);
+        self.lines += 1;
+        done
+    }
+
+    /// 输出未完成的事件（EOF、连接关闭）
+    pub fn flush(&mut self) -> Option<Assembled> {
+        if !self.is_pending() {
+            return None;
+        }
+        let lines = std::mem::take(&mut self.lines);
+        Some(Assembled {
+            payload: self.buf.split().freeze(),
+            lines,
+        })
+    }
+
+    /// 距最后一行超过 flush 超时则输出未完成的事件
+    pub fn flush_expired(&mut self, now: Instant) -> Option<Assembled> {
+        if self.is_pending() && now.duration_since(self.last_line) >= self.conf.timeout {
+            return self.flush();
+        }
+        None
+    }
+
+    /// 距 flush 超时的剩余时间；无未完成事件时为 None
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.is_pending().then(|| {
+            self.conf
+                .timeout
+                .saturating_sub(now.duration_since(self.last_line))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn conf(pairs: &[(&str, serde_json::Value)]) -> MultilineConf {
+        let params: ParamMap = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        MultilineConf::from_params(&params).unwrap().unwrap()
+    }
+
+    fn feed(asm: &mut MultilineAssembler, lines: &[&str]) -> Vec<(String, usize)> {
+        let mut out: Vec<Assembled> = lines
+            .iter()
+            .filter_map(|l| asm.push(l.as_bytes()))
+            .collect();
+        out.extend(asm.flush());
+        out.into_iter()
+            .map(|x| (String::from_utf8(x.payload.to_vec()).unwrap(), x.lines))
+            .collect()
+    }
+
+    #[test]
+    fn start_pattern_merges_stack_trace() {
+        let mut asm =
+            MultilineAssembler::new(conf(&[("multiline_start", json!(r"^\d{4}-\d{2}-\d{2}"))]));
+        let out = feed(
+            &mut asm,
+            &[
+                "2024-01-01 ERROR boom",
+                "java.lang.IllegalStateException: x",
+                "\tat a.b.C.run(C.java:10)",
+                "2024-01-01 INFO ok",
+            ],
+        );
+        assert_eq!(
+            out,
+            vec![
+                (
+                    "2024-01-01 ERROR boom\njava.lang.IllegalStateException: x\n\tat a.b.C.run(C.java:10)"
+                        .to_string(),
+                    3
+                ),
+                ("2024-01-01 INFO ok".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn continue_pattern_and_max_lines() {
+        let mut asm = MultilineAssembler::new(conf(&[
+            ("multiline_continue", json!(r"^\s")),
+            ("multiline_max_lines", json!(2)),
+        ]));
+        let out = feed(&mut asm, &["a", " a1", " a2", "b"]);
+        assert_eq!(
+            out,
+            vec![
+                ("a\n a1".to_string(), 2),
+                (" a2".to_string(), 1),
+                ("b".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn flush_after_timeout() {
+        let mut asm = MultilineAssembler::new(conf(&[
+            ("multiline_start", json!("^BEGIN")),
+            ("multiline_timeout_ms", json!(50)),
+        ]));
+        assert!(asm.push(b"BEGIN").is_none());
+        let now = Instant::now();
+        assert!(asm.flush_expired(now).is_none());
+        assert!(asm.remaining(now).is_some());
+        let done = asm.flush_expired(now + Duration::from_millis(60)).unwrap();
+        assert_eq!(done.lines, 1);
+        assert!(!asm.is_pending());
+        assert!(asm.remaining(now).is_none());
+    }
+
+    #[test]
+    fn params_validation() {
+        assert!(
+            MultilineConf::from_params(&ParamMap::new())
+                .unwrap()
+                .is_none()
+        );
+        let mut params = ParamMap::new();
+        params.insert("multiline_max_lines".into(), json!(10));
+        assert!(MultilineConf::from_params(&params).is_err());
+        params.insert("multiline_start".into(), json!("("));
+        assert!(MultilineConf::from_params(&params).is_err());
+        params.insert("multiline_start".into(), json!("^x"));
+        params.insert("multiline_max_lines".into(), json!(0));
+        assert!(MultilineConf::from_params(&params).is_err());
+    }
+
+    #[test]
+    fn tags_record_line_count() {
+        let done = Assembled {
+            payload: Bytes::from_static(b"a\nb"),
+            lines: 2,
+        };
+        let mut base = Tags::new();
+        base.set("dev", "db");
+        let tags = done.tags(&base);
+        assert_eq!(tags.get(MULTILINE_LINES_TAG), Some("2"));
+        assert_eq!(tags.get("dev"), Some("db"));
+    }
+}
@@ -1,4 +1,5 @@
 use super::framing::{DEFAULT_TCP_RECV_BYTES, FramingMode};
+use crate::sources::multiline::MultilineConf;
 use anyhow::{anyhow, ensure};
 
 #[derive(Debug, Clone)]
@@ -8,6 +9,7 @@ pub struct TcpSourceSpec {
     pub tcp_recv_bytes: usize,
     pub framing: FramingMode,
     pub instances: usize,
+    pub multiline: Option<MultilineConf>,
 }
 
 pub const DEFAULT_TCP_SOURCE_INSTANCES: usize = 1;
@@ -57,12 +59,19 @@ impl TcpSourceSpec {
         );
         let instances = instances as usize;
 
+        let multiline = MultilineConf::from_params(params)?;
+        ensure!(
+            multiline.is_none() || !matches!(framing, FramingMode::Len),
+            "multiline requires line or auto framing"
+        );
+
         Ok(Self {
             addr,
             port,
             tcp_recv_bytes,
             framing,
             instances,
+            multiline,
         })
     }
 
@@ -1,10 +1,12 @@
 use crate::sources::event_id::next_event_id;
+use crate::sources::multiline::{Assembled, MultilineAssembler, MultilineConf};
 use crate::sources::tcp::framing::{FramingExtractor, FramingMode};
 use bytes::{Bytes, BytesMut};
 use std::collections::VecDeque;
 use std::io::ErrorKind;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::net::TcpStream;
 use wp_connector_api::{SourceBatch, SourceEvent, SourceReason, SourceResult, Tags};
 use wp_parse_api::RawData;
@@ -62,6 +64,7 @@ struct BatchBuilder {
     source_key: String,
     pending_events: VecDeque<SourceEvent>,
     max_batch_bytes: usize,
+    multiline: Option<MultilineAssembler>,
 }
 
 impl TcpConnection {
@@ -94,6 +97,12 @@ impl TcpConnection {
         conn
     }
 
+    /// 开启多行聚合：分帧后的消息按物理行合并为事件
+    pub fn with_multiline(mut self, conf: Option<MultilineConf>) -> Self {
+        self.batcher.multiline = conf.map(MultilineAssembler::new);
+        self
+    }
+
     pub fn try_read_batch(&mut self) -> SourceResult<ReadOutcome> {
         let mut produced = SourceBatch::with_capacity(self.batcher.batch_capacity);
         let mut produced_bytes = 0usize;
@@ -111,6 +120,11 @@ impl TcpConnection {
                         self.batcher.pending_len(),
                         self.batcher.pending_bytes()
                     );
+                    // 连接关闭前输出未完成的多行事件
+                    if let Some(event) = self.batcher.flush_multiline(self.client_addr.ip(), None) {
+                        produced.push(event);
+                        return Ok(ReadOutcome::Produced(produced));
+                    }
                     return Ok(ReadOutcome::Closed);
                 }
                 Ok(_) => {
@@ -132,6 +146,12 @@ impl TcpConnection {
                     continue;
                 }
                 Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    if let Some(event) = self
+                        .batcher
+                        .flush_multiline(self.client_addr.ip(), Some(Instant::now()))
+                    {
+                        produced.push(event);
+                    }
                     if produced.is_empty() {
                         // No immediate data; opportunistically shrink buffer if idle
                         self.batcher.maybe_shrink();
@@ -160,7 +180,24 @@ impl TcpConnection {
             return Ok(ReadOutcome::Produced(produced));
         }
         loop {
-            if let Err(e) = self.stream.readable().await {
+            // 有未完成的多行事件时，最多等待到 flush 超时
+            let readable = match self.batcher.multiline_remaining() {
+                Some(wait) => match tokio::time::timeout(wait, self.stream.readable()).await {
+                    Ok(res) => res,
+                    Err(_) => {
+                        if let Some(event) = self
+                            .batcher
+                            .flush_multiline(self.client_addr.ip(), Some(Instant::now()))
+                        {
+                            produced.push(event);
+                            return Ok(ReadOutcome::Produced(produced));
+                        }
+                        continue;
+                    }
+                },
+                None => self.stream.readable().await,
+            };
+            if let Err(e) = readable {
                 return Err(SourceReason::Disconnect(format!(
                     "tcp readable error ({}): {}",
                     self.client_addr, e
@@ -175,6 +212,11 @@ impl TcpConnection {
                         self.batcher.pending_len(),
                         self.batcher.pending_bytes()
                     );
+                    // 连接关闭前输出未完成的多行事件
+                    if let Some(event) = self.batcher.flush_multiline(self.client_addr.ip(), None) {
+                        produced.push(event);
+                        return Ok(ReadOutcome::Produced(produced));
+                    }
                     return Ok(ReadOutcome::Closed);
                 }
                 Ok(_) => {
@@ -243,6 +285,7 @@ impl BatchBuilder {
             source_key,
             pending_events: VecDeque::new(),
             max_batch_bytes,
+            multiline: None,
         }
     }
 
@@ -294,7 +337,9 @@ impl BatchBuilder {
         produced_bytes: &mut usize,
     ) {
         while let Some(payload) = extract_message(framing, &mut self.buffer) {
-            let event = self.build_event(payload, peer_ip);
+            let Some(event) = self.assemble(payload, peer_ip) else {
+                continue;
+            };
             let event_size = event_payload_len(&event);
             let would_exceed = *produced_bytes + event_size > self.max_batch_bytes;
             if batch.len() >= self.batch_capacity {
@@ -337,6 +382,43 @@ impl BatchBuilder {
         self.pending_events.iter().map(event_payload_len).sum()
     }
 
+    /// 未开启多行聚合时直接成事件；否则仅在上一事件结束时返回
+    fn assemble(&mut self, payload: Bytes, peer_ip: IpAddr) -> Option<SourceEvent> {
+        let Some(asm) = self.multiline.as_mut() else {
+            return Some(self.build_event(payload, peer_ip));
+        };
+        let done = asm.push(&payload)?;
+        Some(self.build_assembled_event(done, peer_ip))
+    }
+
+    /// `now` 为 None 时无条件输出未完成的事件，否则仅在超时后输出
+    fn flush_multiline(&mut self, peer_ip: IpAddr, now: Option<Instant>) -> Option<SourceEvent> {
+        let asm = self.multiline.as_mut()?;
+        let done = match now {
+            Some(now) => asm.flush_expired(now),
+            None => asm.flush(),
+        }?;
+        Some(self.build_assembled_event(done, peer_ip))
+    }
+
+    fn multiline_remaining(&self) -> Option<Duration> {
+        self.multiline
+            .as_ref()
+            .and_then(|asm| asm.remaining(Instant::now()))
+    }
+
+    fn build_assembled_event(&self, done: Assembled, peer_ip: IpAddr) -> SourceEvent {
+        let tags = done.tags(&self.base_tags);
+        let mut event = SourceEvent::new(
+            next_event_id(),
+            &self.source_key,
+            RawData::Bytes(done.payload),
+            Arc::new(tags),
+        );
+        event.ups_ip = Some(peer_ip);
+        event
+    }
+
     fn build_event(&self, payload: Bytes, peer_ip: IpAddr) -> SourceEvent {
         let mut event = SourceEvent::new(
             next_event_id(),
@@ -12,6 +12,7 @@ use wp_connector_api::{SourceDefProvider, SourceReason};
 use super::TcpAcceptor;
 use super::config::TcpSourceSpec;
 use super::source::TcpSource;
+use crate::sources::multiline::MULTILINE_PARAMS;
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
@@ -68,7 +69,8 @@ impl SourceFactory for TcpSourceFactory {
                     conf.framing,
                     connection_registry.clone(),
                     reader_reg_rx,
-                )?;
+                )?
+                .with_multiline(conf.multiline.clone());
 
                 let mut meta = SourceMeta::new(key.clone(), spec.kind.clone());
                 for (k, v) in tags.iter() {
@@ -113,13 +115,11 @@ impl SourceDefProvider for TcpSourceFactory {
             id: "tcp_src".into(),
             kind: self.kind().into(),
             scope: ConnectorScope::Source,
-            allow_override: vec![
-                "addr".into(),
-                "port".into(),
-                "framing".into(),
-                "tcp_recv_bytes".into(),
-                "instances".into(),
-            ],
+            allow_override: ["addr", "port", "framing", "tcp_recv_bytes", "instances"]
+                .into_iter()
+                .chain(MULTILINE_PARAMS)
+                .map(String::from)
+                .collect(),
             default_params: params,
             origin: Some("builtin:tcp_source".into()),
         }
@@ -159,6 +159,28 @@ mod tests {
         assert_eq!(svc.sources[0].source.identifier(), "tcp_test");
     }
 
+    #[test]
+    fn multiline_rejects_len_framing() {
+        let spec = |framing: &str| {
+            let mut t = toml::map::Map::new();
+            t.insert("framing".into(), toml::Value::String(framing.into()));
+            t.insert(
+                "multiline_start".into(),
+                toml::Value::String(r"^\d{4}-".into()),
+            );
+            ResolvedSourceSpec {
+                name: "tcp_multiline".into(),
+                kind: "tcp".into(),
+                connector_id: String::new(),
+                params: wp_connector_api::parammap_from_toml_map(t),
+                tags: vec![],
+            }
+        };
+        let fac = TcpSourceFactory;
+        assert!(fac.validate_spec(&spec("line")).is_ok());
+        assert!(fac.validate_spec(&spec("len")).is_err());
+    }
+
     #[tokio::test]
     async fn end_to_end_line_and_len() {
         // 在受限沙箱（无网络权限）环境下跳过
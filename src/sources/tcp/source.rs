@@ -10,6 +10,7 @@ use super::ConnectionRegistry;
 use super::conn::connection::{ReadOutcome, TcpConnection, batch_bytes};
 use super::framing::FramingMode;
 use super::worker::ConnectionRegistration;
+use crate::sources::multiline::MultilineConf;
 
 struct ConnectionGuard<'a> {
     source: &'a mut TcpSource,
@@ -221,6 +222,7 @@ pub struct TcpSource {
     connection_order: VecDeque<u64>,
     started: bool,
     awaiting_logged: bool,
+    multiline: Option<MultilineConf>,
 }
 
 impl TcpSource {
@@ -248,9 +250,16 @@ impl TcpSource {
             connection_order: VecDeque::new(),
             started: false,
             awaiting_logged: false,
+            multiline: None,
         })
     }
 
+    /// 为后续接入的连接开启多行聚合
+    pub fn with_multiline(mut self, conf: Option<MultilineConf>) -> Self {
+        self.multiline = conf;
+        self
+    }
+
     pub fn active_connections(&self) -> usize {
         self.connections.len()
     }
@@ -263,7 +272,8 @@ impl TcpSource {
             self.base_tags.clone(),
             self.tcp_recv_bytes,
             self.key.clone(),
-        );
+        )
+        .with_multiline(self.multiline.clone());
         self.registry.lock().unwrap().insert(reg.connection_id);
         self.connections.insert(reg.connection_id, connection);
         self.connection_order.push_back(reg.connection_id);